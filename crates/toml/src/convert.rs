@@ -0,0 +1,152 @@
+//! Direct conversions between [`Value`] and the [`toml_edit`] document tree
+//!
+//! These walk the tree rather than going through serde, keeping the order of keys when the
+//! `preserve_order` feature is enabled.
+
+use crate::{Table, Value};
+
+impl From<toml_edit::Value> for Value {
+    fn from(value: toml_edit::Value) -> Self {
+        match value {
+            toml_edit::Value::String(v) => Value::String(v.into_value()),
+            toml_edit::Value::Integer(v) => Value::Integer(v.into_value()),
+            toml_edit::Value::Float(v) => Value::Float(v.into_value()),
+            toml_edit::Value::Boolean(v) => Value::Boolean(v.into_value()),
+            toml_edit::Value::Datetime(v) => Value::Datetime(v.into_value()),
+            toml_edit::Value::Array(v) => v.into(),
+            toml_edit::Value::InlineTable(v) => Value::Table(v.into()),
+        }
+    }
+}
+
+/// [`Item::None`][toml_edit::Item::None] has no equivalent and is an error.
+///
+/// `Value::try_from` names the inherent [`Value::try_from`], which serializes its argument, so
+/// convert with [`TryInto::try_into`] instead:
+///
+/// ```
+/// let item = toml_edit::value(5);
+/// let value: toml::Value = item.try_into().unwrap();
+/// assert_eq!(value, toml::Value::Integer(5));
+///
+/// let none: Result<toml::Value, _> = toml_edit::Item::None.try_into();
+/// assert!(none.is_err());
+/// ```
+impl TryFrom<toml_edit::Item> for Value {
+    type Error = crate::ser::Error;
+
+    fn try_from(item: toml_edit::Item) -> Result<Self, Self::Error> {
+        from_item(item).ok_or_else(crate::ser::Error::unsupported_none)
+    }
+}
+
+fn from_item(item: toml_edit::Item) -> Option<Value> {
+    match item {
+        toml_edit::Item::None => None,
+        toml_edit::Item::Value(v) => Some(v.into()),
+        toml_edit::Item::Table(v) => Some(Value::Table(v.into())),
+        toml_edit::Item::ArrayOfTables(v) => Some(v.into()),
+    }
+}
+
+impl From<toml_edit::Array> for Value {
+    fn from(array: toml_edit::Array) -> Self {
+        Value::Array(array.into_iter().map(Value::from).collect())
+    }
+}
+
+impl From<toml_edit::ArrayOfTables> for Value {
+    fn from(array: toml_edit::ArrayOfTables) -> Self {
+        Value::Array(
+            array
+                .into_iter()
+                .map(|table| Value::Table(table.into()))
+                .collect(),
+        )
+    }
+}
+
+impl From<toml_edit::Table> for Table {
+    fn from(table: toml_edit::Table) -> Self {
+        table
+            .into_iter()
+            .filter_map(|(key, item)| Some((key.as_str().to_owned(), from_item(item)?)))
+            .collect()
+    }
+}
+
+impl From<toml_edit::InlineTable> for Table {
+    fn from(table: toml_edit::InlineTable) -> Self {
+        table
+            .into_iter()
+            .map(|(key, value)| (key.as_str().to_owned(), value.into()))
+            .collect()
+    }
+}
+
+impl From<toml_edit::DocumentMut> for Table {
+    fn from(mut doc: toml_edit::DocumentMut) -> Self {
+        std::mem::take(doc.as_table_mut()).into()
+    }
+}
+
+/// Tables become inline tables
+impl From<Value> for toml_edit::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::String(v) => v.into(),
+            Value::Integer(v) => v.into(),
+            Value::Float(v) => v.into(),
+            Value::Boolean(v) => v.into(),
+            Value::Datetime(v) => v.into(),
+            Value::Array(v) => v.into_iter().collect::<toml_edit::Array>().into(),
+            Value::Table(v) => toml_edit::InlineTable::from(v).into(),
+        }
+    }
+}
+
+/// Tables become standard tables and arrays of tables become arrays of tables, like
+/// [`to_string`][crate::to_string]
+impl From<Value> for toml_edit::Item {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Table(v) => toml_edit::Item::Table(v.into()),
+            Value::Array(v) if !v.is_empty() && v.iter().all(Value::is_table) => {
+                toml_edit::Item::ArrayOfTables(
+                    v.into_iter()
+                        .filter_map(|v| match v {
+                            Value::Table(t) => Some(toml_edit::Table::from(t)),
+                            _ => None,
+                        })
+                        .collect(),
+                )
+            }
+            v => toml_edit::Item::Value(v.into()),
+        }
+    }
+}
+
+impl From<Table> for toml_edit::Table {
+    fn from(table: Table) -> Self {
+        // Empty tables could be semantically meaningful, so make sure they are not implicit
+        let implicit = !table.is_empty();
+        let mut converted = toml_edit::Table::new();
+        for (key, value) in table {
+            converted.insert(&key, value.into());
+        }
+        converted.set_implicit(implicit);
+        converted
+    }
+}
+
+impl From<Table> for toml_edit::InlineTable {
+    fn from(table: Table) -> Self {
+        table.into_iter().collect()
+    }
+}
+
+impl From<Table> for toml_edit::DocumentMut {
+    fn from(table: Table) -> Self {
+        toml_edit::Table::from(table).into()
+    }
+}
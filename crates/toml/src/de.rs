@@ -299,9 +299,20 @@ impl<'de, 'a> serde::Deserializer<'de> for ValueDeserializer<'a> {
             .map_err(Error::new)
     }
 
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let inner = self
+            .input
+            .parse::<toml_edit::de::ValueDeserializer>()
+            .map_err(Error::new)?;
+        inner.deserialize_ignored_any(visitor).map_err(Error::new)
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
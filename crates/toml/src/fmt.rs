@@ -1,6 +1,8 @@
 #[derive(Copy, Clone, Default)]
 pub(crate) struct DocumentFormatter {
     pub(crate) multiline_array: bool,
+    pub(crate) canonical: bool,
+    pub(crate) string_style: Option<toml_edit::StringStyle>,
     is_value: bool,
 }
 
@@ -49,6 +51,13 @@ impl toml_edit::visit_mut::VisitMut for DocumentFormatter {
         toml_edit::visit_mut::visit_value_mut(self, node);
     }
 
+    fn visit_string_mut(&mut self, node: &mut toml_edit::Formatted<String>) {
+        if let Some(style) = self.string_style {
+            // Strings that can't be written in the preferred style keep the default one
+            let _ = node.set_style(style);
+        }
+    }
+
     fn visit_array_mut(&mut self, node: &mut toml_edit::Array) {
         toml_edit::visit_mut::visit_array_mut(self, node);
 
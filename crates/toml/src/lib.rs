@@ -159,6 +159,8 @@ pub mod ser;
 #[doc(hidden)]
 pub mod macros;
 
+#[cfg(any(feature = "parse", feature = "display"))]
+mod convert;
 mod edit;
 #[cfg(feature = "display")]
 mod fmt;
@@ -169,7 +171,9 @@ mod table;
 pub use crate::de::{from_str, Deserializer};
 #[cfg(feature = "display")]
 #[doc(inline)]
-pub use crate::ser::{to_string, to_string_pretty, Serializer};
+pub use crate::ser::{
+    to_string, to_string_canonical, to_string_pretty, to_writer, to_writer_pretty, Serializer,
+};
 #[doc(inline)]
 pub use crate::value::Value;
 
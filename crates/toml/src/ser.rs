@@ -73,6 +73,128 @@ where
     Ok(output)
 }
 
+/// Serialize the given data structure as a canonical String of TOML, for hashing and signing
+///
+/// The output only depends on the data, and is stable across releases.  See
+/// [`toml_edit::DocumentMut::to_canonical_string`] for the format.
+///
+/// # Examples
+///
+/// ```
+/// let value: toml::Value = toml::from_str(r#"
+/// version = '1.0'
+/// name = "foo"
+/// dependencies.serde = { version = "1" }
+/// "#).unwrap();
+///
+/// let toml = toml::to_string_canonical(&value).unwrap();
+/// assert_eq!(toml, r#"name = "foo"
+/// version = "1.0"
+/// [dependencies.serde]
+/// version = "1"
+/// "#);
+/// ```
+#[cfg(feature = "display")]
+pub fn to_string_canonical<T: ?Sized>(value: &T) -> Result<String, Error>
+where
+    T: serde::ser::Serialize,
+{
+    let mut output = String::new();
+    let serializer = Serializer::canonical(&mut output);
+    value.serialize(serializer)?;
+    Ok(output)
+}
+
+/// Serialize the given data structure as TOML into the IO stream.
+///
+/// Unlike [`to_string`], top-level key/value pairs and the tables of top-level arrays of tables
+/// are written as they are serialized, rather than once the whole document has been built, see
+/// [`StreamSerializer`].
+///
+/// The output is the same as [`to_string`]'s, but for the few inputs [`StreamSerializer`] can't
+/// write as they come, which are errors: a trailing array of tables holding other values too,
+/// like `[{}, 1]`, and fields beyond the length a struct gave to `serialize_struct`.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Export {
+///     version: u32,
+///     record: Vec<Record>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Record {
+///     id: u32,
+/// }
+///
+/// let export = Export {
+///     version: 1,
+///     record: (1..=2).map(|id| Record { id }).collect(),
+/// };
+///
+/// let mut output = Vec::new();
+/// toml::to_writer(&mut output, &export).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     toml::to_string(&export).unwrap(),
+/// );
+/// ```
+#[cfg(feature = "display")]
+pub fn to_writer<W, T: ?Sized>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: std::io::Write,
+    T: serde::ser::Serialize,
+{
+    let mut writer = IoWriter::new(writer);
+    let result = value.serialize(StreamSerializer::new(&mut writer));
+    writer.finish(result)
+}
+
+/// Serialize the given data structure as "pretty" TOML into the IO stream.
+///
+/// This is identical to `to_writer` except the output has a more "pretty" output.  See
+/// `Serializer::pretty` for more details.
+#[cfg(feature = "display")]
+pub fn to_writer_pretty<W, T: ?Sized>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: std::io::Write,
+    T: serde::ser::Serialize,
+{
+    let mut writer = IoWriter::new(writer);
+    let result = value.serialize(StreamSerializer::pretty(&mut writer));
+    writer.finish(result)
+}
+
+/// How strings are quoted, see [`Serializer::string_style`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StringStyle {
+    /// `"basic string"`
+    Basic,
+    /// `'literal string'`
+    Literal,
+    /// `"""multi-line basic string"""`
+    MultilineBasic,
+    /// `'''multi-line literal string'''`
+    MultilineLiteral,
+}
+
+#[cfg(feature = "display")]
+impl StringStyle {
+    fn to_edit(self) -> toml_edit::StringStyle {
+        match self {
+            StringStyle::Basic => toml_edit::StringStyle::Basic,
+            StringStyle::Literal => toml_edit::StringStyle::Literal,
+            StringStyle::MultilineBasic => toml_edit::StringStyle::MultilineBasic,
+            StringStyle::MultilineLiteral => toml_edit::StringStyle::MultilineLiteral,
+        }
+    }
+}
+
 /// Errors that can occur when serializing a type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Error {
@@ -167,6 +289,47 @@ impl<'d> Serializer<'d> {
         ser.settings.multiline_array = true;
         ser
     }
+
+    /// Write the document in its canonical form
+    ///
+    /// See [`to_string_canonical`].
+    pub fn canonical(dst: &'d mut String) -> Self {
+        let mut ser = Serializer::new(dst);
+        ser.settings.canonical = true;
+        ser
+    }
+
+    /// Quote strings in `style` where possible
+    ///
+    /// Strings that can't be written in `style`, like literal strings holding a `'`, are quoted
+    /// as usual.  This has no effect on [`Serializer::canonical`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use toml::ser::{Serializer, StringStyle};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Paths {
+    ///     home: &'static str,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let paths = Paths {
+    ///     home: r"C:\Users\me",
+    ///     name: "it's me",
+    /// };
+    /// let mut toml = String::new();
+    /// paths
+    ///     .serialize(Serializer::new(&mut toml).string_style(StringStyle::Literal))
+    ///     .unwrap();
+    /// assert_eq!(toml, "home = 'C:\\Users\\me'\nname = \"it's me\"\n");
+    /// ```
+    pub fn string_style(mut self, style: StringStyle) -> Self {
+        self.settings.string_style = Some(style.to_edit());
+        self
+    }
 }
 
 #[cfg(feature = "display")]
@@ -749,15 +912,289 @@ impl<'d> serde::ser::Serializer for ValueSerializer<'d> {
     }
 }
 
+/// Serialization for TOML documents, written as they are serialized.
+///
+/// [`Serializer`] builds the whole document before writing it.  `StreamSerializer` instead
+/// writes each top-level key/value pair, and each table of a top-level array of tables, once it
+/// has been serialized, so exporting many `[[record]]`s doesn't hold them all in memory.
+///
+/// The output is the same as [`Serializer`]'s.  TOML requires the top-level key/value pairs
+/// before any table, so top-level tables are kept, already written out, until no more key/value
+/// pairs can follow.  For a struct, that is from its last field on, so a trailing `Vec` of
+/// records is written one table at a time.  For a map, whose entries aren't known in advance,
+/// the tables are kept until the end.
+///
+/// Where that differs from [`Serializer`] is in what it rejects, as tables written directly can't
+/// be taken back:
+/// - The last field's array, when its first element is a table but it holds other values too,
+///   like `[{}, 1]`, is an error, as its tables may already have been written as an array of
+///   tables.  Other fields' arrays are written like [`Serializer`] writes them.
+/// - A key/value pair following such tables is an error, which can only happen when a struct
+///   has more fields than the length it passed to `serialize_struct`.
+///
+/// To serialize into an [`std::io::Write`], see [`to_writer`].
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use toml::ser::StreamSerializer;
+///
+/// #[derive(Serialize)]
+/// struct Export {
+///     version: u32,
+///     record: Vec<Record>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Record {
+///     id: u32,
+///     tags: Vec<&'static str>,
+/// }
+///
+/// let export = Export {
+///     version: 1,
+///     record: vec![
+///         Record { id: 1, tags: vec!["a"] },
+///         Record { id: 2, tags: vec![] },
+///     ],
+/// };
+///
+/// let mut toml = String::new();
+/// export.serialize(StreamSerializer::new(&mut toml)).unwrap();
+/// assert_eq!(toml, r#"version = 1
+///
+/// [[record]]
+/// id = 1
+/// tags = ["a"]
+///
+/// [[record]]
+/// id = 2
+/// tags = []
+/// "#);
+/// ```
+#[non_exhaustive]
+#[cfg(feature = "display")]
+pub struct StreamSerializer<W> {
+    dst: W,
+    settings: crate::fmt::DocumentFormatter,
+}
+
+#[cfg(feature = "display")]
+impl<W: std::fmt::Write> StreamSerializer<W> {
+    /// Creates a new serializer which will emit TOML into `dst`.
+    pub fn new(dst: W) -> Self {
+        Self {
+            dst,
+            settings: Default::default(),
+        }
+    }
+
+    /// Apply a default "pretty" policy to the document
+    ///
+    /// See [`Serializer::pretty`].
+    pub fn pretty(dst: W) -> Self {
+        let mut ser = StreamSerializer::new(dst);
+        ser.settings.multiline_array = true;
+        ser
+    }
+
+    /// Quote strings in `style` where possible
+    ///
+    /// See [`Serializer::string_style`].
+    pub fn string_style(mut self, style: StringStyle) -> Self {
+        self.settings.string_style = Some(style.to_edit());
+        self
+    }
+}
+
+#[cfg(feature = "display")]
+impl<W: std::fmt::Write> serde::ser::Serializer for StreamSerializer<W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = SerializeDocumentStream<W>;
+    type SerializeStruct = SerializeDocumentStream<W>;
+    type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+
+    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_none())
+    }
+
+    fn serialize_some<T: ?Sized>(self, v: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::ser::Serialize,
+    {
+        v.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(Some("unit")))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(Some(name)))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(
+        self,
+        _name: &'static str,
+        v: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::ser::Serialize,
+    {
+        v.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: serde::ser::Serialize,
+    {
+        use serde::ser::SerializeMap as _;
+
+        let mut ser = self.serialize_map(Some(1))?;
+        ser.serialize_entry(variant, value)?;
+        ser.end()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::unsupported_type(None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        // A map's length is only a hint, so it can't tell which entry is the last
+        Ok(SerializeDocumentStream::new(self, None))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        if name == toml_datetime::__unstable::NAME {
+            Err(Error::unsupported_type(None))
+        } else {
+            Ok(SerializeDocumentStream::new(self, Some(len)))
+        }
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Error::unsupported_type(Some(name)))
+    }
+}
+
 #[cfg(feature = "display")]
 use internal::{
-    write_document, write_value, SerializeDocumentArray, SerializeDocumentTable,
-    SerializeValueArray, SerializeValueTable,
+    write_document, write_value, IoWriter, SerializeDocumentArray, SerializeDocumentStream,
+    SerializeDocumentTable, SerializeValueArray, SerializeValueTable,
 };
 
 #[cfg(feature = "display")]
 mod internal {
-    use super::{Error, Serializer, ValueSerializer};
+    use super::{Error, Serializer, StreamSerializer, ValueSerializer};
 
     use crate::fmt::DocumentFormatter;
 
@@ -924,6 +1361,12 @@ mod internal {
             }
         };
 
+        if settings.canonical {
+            let doc: toml_edit::DocumentMut = table.into();
+            dst.push_str(&doc.to_canonical_string());
+            return Ok(());
+        }
+
         settings.visit_table_mut(&mut table);
 
         let doc: toml_edit::DocumentMut = table.into();
@@ -1087,4 +1530,641 @@ mod internal {
 
         Ok(())
     }
+
+    // Where a `StreamSerializer` is at in the document
+    pub(crate) struct Stream<W> {
+        dst: W,
+        settings: DocumentFormatter,
+        has_output: bool,
+        // Top-level tables, written out, that wait for the key/value pairs that may follow them
+        pending: String,
+        // Whether no more top-level key/value pairs can follow, so tables can be written directly
+        is_last_entry: bool,
+        // Whether tables were written directly, so key/value pairs would end up inside the last
+        has_tables: bool,
+    }
+
+    impl<W: std::fmt::Write> Stream<W> {
+        // Writes `key = value`, or the sections `value` makes up when it is a table or an array
+        // of tables, the way `write_document` would write them as part of the whole document
+        fn write_entry(&mut self, key: &str, value: toml_edit::Value) -> Result<(), Error> {
+            use std::fmt::Write as _;
+            use toml_edit::visit_mut::VisitMut as _;
+
+            let mut entry = toml_edit::InlineTable::new();
+            entry.insert(key, value);
+            let mut table = entry.into_table();
+            self.settings.visit_table_mut(&mut table);
+
+            let is_value = table.get(key).is_some_and(toml_edit::Item::is_value);
+            let doc: toml_edit::DocumentMut = table.into();
+            if is_value {
+                if self.has_tables {
+                    // Only possible when a struct has more fields than its length said
+                    return Err(Error::new(format!(
+                        "`{key}` follows tables that were already written, which can't be streamed"
+                    )));
+                }
+                write!(self.dst, "{}", doc).map_err(Error::new)?;
+                self.has_output = true;
+            } else if self.is_last_entry {
+                self.flush()?;
+                if self.has_output {
+                    // The blank line `toml_edit` puts between tables
+                    self.dst.write_str("\n").map_err(Error::new)?;
+                }
+                write!(self.dst, "{}", doc).map_err(Error::new)?;
+                self.has_output = true;
+                self.has_tables = true;
+            } else {
+                if !self.pending.is_empty() {
+                    self.pending.push('\n');
+                }
+                write!(self.pending, "{}", doc).expect("writing to a `String` can't fail");
+            }
+            Ok(())
+        }
+
+        // Writes the tables kept back for the key/value pairs that could follow them
+        fn flush(&mut self) -> Result<(), Error> {
+            if !self.pending.is_empty() {
+                if self.has_output {
+                    self.dst.write_str("\n").map_err(Error::new)?;
+                }
+                self.dst.write_str(&self.pending).map_err(Error::new)?;
+                self.pending = String::new();
+                self.has_output = true;
+            }
+            Ok(())
+        }
+    }
+
+    #[doc(hidden)]
+    pub struct SerializeDocumentStream<W> {
+        stream: Stream<W>,
+        key: Option<String>,
+        // The number of entries left, when known
+        remaining: Option<usize>,
+    }
+
+    impl<W> SerializeDocumentStream<W> {
+        pub(crate) fn new(ser: StreamSerializer<W>, len: Option<usize>) -> Self {
+            Self {
+                stream: Stream {
+                    dst: ser.dst,
+                    settings: ser.settings,
+                    has_output: false,
+                    pending: String::new(),
+                    is_last_entry: false,
+                    has_tables: false,
+                },
+                key: None,
+                remaining: len,
+            }
+        }
+
+        fn next_entry(&mut self) -> &mut Stream<W> {
+            if let Some(remaining) = self.remaining.as_mut() {
+                *remaining = remaining.saturating_sub(1);
+                self.stream.is_last_entry |= *remaining == 0;
+            }
+            &mut self.stream
+        }
+    }
+
+    impl<W: std::fmt::Write> serde::ser::SerializeMap for SerializeDocumentStream<W> {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_key<T: ?Sized>(&mut self, input: &T) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            let key = match input.serialize(toml_edit::ser::ValueSerializer::new()) {
+                Ok(toml_edit::Value::String(key)) => key.into_value(),
+                _ => return Err(Error::key_not_string()),
+            };
+            self.key = Some(key);
+            Ok(())
+        }
+
+        fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            let key = self.key.take().expect("serialize_key is called first");
+            value.serialize(SerializeStreamEntry {
+                stream: self.next_entry(),
+                key,
+            })
+        }
+
+        fn end(mut self) -> Result<Self::Ok, Self::Error> {
+            self.stream.flush()
+        }
+    }
+
+    impl<W: std::fmt::Write> serde::ser::SerializeStruct for SerializeDocumentStream<W> {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: ?Sized>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            value.serialize(SerializeStreamEntry {
+                stream: self.next_entry(),
+                key: key.to_owned(),
+            })
+        }
+
+        fn end(mut self) -> Result<Self::Ok, Self::Error> {
+            self.stream.flush()
+        }
+    }
+
+    type InnerValueSerializer = toml_edit::ser::ValueSerializer;
+
+    // Serializes the value of a top-level key, writing it as soon as it is complete
+    struct SerializeStreamEntry<'s, W> {
+        stream: &'s mut Stream<W>,
+        key: String,
+    }
+
+    impl<'s, W: std::fmt::Write> SerializeStreamEntry<'s, W> {
+        fn write(
+            self,
+            value: Result<toml_edit::Value, crate::edit::ser::Error>,
+        ) -> Result<(), Error> {
+            let value = value.map_err(Error::wrap)?;
+            self.stream.write_entry(&self.key, value)
+        }
+
+        fn wrap<S>(
+            self,
+            inner: Result<S, crate::edit::ser::Error>,
+        ) -> Result<SerializeStreamValue<'s, W, S>, Error> {
+            let inner = inner.map_err(Error::wrap)?;
+            Ok(SerializeStreamValue {
+                stream: self.stream,
+                key: self.key,
+                inner,
+            })
+        }
+    }
+
+    impl<'s, W: std::fmt::Write> serde::ser::Serializer for SerializeStreamEntry<'s, W> {
+        type Ok = ();
+        type Error = Error;
+        type SerializeSeq = SerializeStreamArray<'s, W>;
+        type SerializeTuple = SerializeStreamArray<'s, W>;
+        type SerializeTupleStruct = SerializeStreamArray<'s, W>;
+        type SerializeTupleVariant = SerializeStreamValue<
+            's,
+            W,
+            <InnerValueSerializer as serde::Serializer>::SerializeTupleVariant,
+        >;
+        type SerializeMap =
+            SerializeStreamValue<'s, W, <InnerValueSerializer as serde::Serializer>::SerializeMap>;
+        type SerializeStruct = SerializeStreamValue<
+            's,
+            W,
+            <InnerValueSerializer as serde::Serializer>::SerializeStruct,
+        >;
+        type SerializeStructVariant = SerializeStreamValue<
+            's,
+            W,
+            <InnerValueSerializer as serde::Serializer>::SerializeStructVariant,
+        >;
+
+        fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_bool(v))
+        }
+
+        fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_i8(v))
+        }
+
+        fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_i16(v))
+        }
+
+        fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_i32(v))
+        }
+
+        fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_i64(v))
+        }
+
+        fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_u8(v))
+        }
+
+        fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_u16(v))
+        }
+
+        fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_u32(v))
+        }
+
+        fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_u64(v))
+        }
+
+        fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_f32(v))
+        }
+
+        fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_f64(v))
+        }
+
+        fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_char(v))
+        }
+
+        fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_str(v))
+        }
+
+        fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_bytes(v))
+        }
+
+        fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+            // Like in tables, keys without a value are left out
+            Ok(())
+        }
+
+        fn serialize_some<T: ?Sized>(self, v: &T) -> Result<Self::Ok, Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            v.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_unit())
+        }
+
+        fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_unit_struct(name))
+        }
+
+        fn serialize_unit_variant(
+            self,
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+        ) -> Result<Self::Ok, Self::Error> {
+            self.write(InnerValueSerializer::new().serialize_unit_variant(
+                name,
+                variant_index,
+                variant,
+            ))
+        }
+
+        fn serialize_newtype_struct<T: ?Sized>(
+            self,
+            _name: &'static str,
+            v: &T,
+        ) -> Result<Self::Ok, Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            v.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: ?Sized>(
+            self,
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            value: &T,
+        ) -> Result<Self::Ok, Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.write(InnerValueSerializer::new().serialize_newtype_variant(
+                name,
+                variant_index,
+                variant,
+                value,
+            ))
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+            // The elements of an array of tables aren't kept, so no room is made for them
+            Ok(SerializeStreamArray {
+                stream: self.stream,
+                key: self.key,
+                values: Vec::new(),
+                is_array_of_tables: false,
+            })
+        }
+
+        fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+            let inner = InnerValueSerializer::new().serialize_tuple_variant(
+                name,
+                variant_index,
+                variant,
+                len,
+            );
+            self.wrap(inner)
+        }
+
+        fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+            let inner = InnerValueSerializer::new().serialize_map(len);
+            self.wrap(inner)
+        }
+
+        fn serialize_struct(
+            self,
+            name: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeStruct, Self::Error> {
+            let inner = InnerValueSerializer::new().serialize_struct(name, len);
+            self.wrap(inner)
+        }
+
+        fn serialize_struct_variant(
+            self,
+            name: &'static str,
+            variant_index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeStructVariant, Self::Error> {
+            let inner = InnerValueSerializer::new().serialize_struct_variant(
+                name,
+                variant_index,
+                variant,
+                len,
+            );
+            self.wrap(inner)
+        }
+    }
+
+    // Serializes a top-level array, writing each table of an array of tables as soon as it is
+    // complete
+    struct SerializeStreamArray<'s, W> {
+        stream: &'s mut Stream<W>,
+        key: String,
+        // Elements written together at the end, for an array that isn't an array of tables or
+        // that can't be written yet
+        values: Vec<toml_edit::Value>,
+        is_array_of_tables: bool,
+    }
+
+    impl<'s, W: std::fmt::Write> SerializeStreamArray<'s, W> {
+        fn push<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            let value = value
+                .serialize(InnerValueSerializer::new())
+                .map_err(Error::wrap)?;
+            let is_table = value.is_inline_table();
+            // Tables that can't be written yet are kept with the other values, so the array is
+            // written like `to_string` would once it is complete
+            if !self.stream.is_last_entry {
+                self.values.push(value);
+                Ok(())
+            } else if self.is_array_of_tables || (is_table && self.values.is_empty()) {
+                if !is_table {
+                    return Err(Error::new(format!(
+                        "`{}` mixes tables with other values, which can't be streamed",
+                        self.key
+                    )));
+                }
+                self.is_array_of_tables = true;
+                let table = std::iter::once(value).collect::<toml_edit::Array>();
+                self.stream.write_entry(&self.key, table.into())
+            } else {
+                self.values.push(value);
+                Ok(())
+            }
+        }
+
+        fn end(self) -> Result<(), Error> {
+            if self.is_array_of_tables {
+                Ok(())
+            } else {
+                let array = self.values.into_iter().collect::<toml_edit::Array>();
+                self.stream.write_entry(&self.key, array.into())
+            }
+        }
+    }
+
+    impl<'s, W: std::fmt::Write> serde::ser::SerializeSeq for SerializeStreamArray<'s, W> {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            SerializeStreamArray::end(self)
+        }
+    }
+
+    impl<'s, W: std::fmt::Write> serde::ser::SerializeTuple for SerializeStreamArray<'s, W> {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            SerializeStreamArray::end(self)
+        }
+    }
+
+    impl<'s, W: std::fmt::Write> serde::ser::SerializeTupleStruct for SerializeStreamArray<'s, W> {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            SerializeStreamArray::end(self)
+        }
+    }
+
+    // Serializes the value of a top-level key through `toml_edit`, writing it once complete
+    struct SerializeStreamValue<'s, W, S> {
+        stream: &'s mut Stream<W>,
+        key: String,
+        inner: S,
+    }
+
+    impl<'s, W, S> serde::ser::SerializeMap for SerializeStreamValue<'s, W, S>
+    where
+        W: std::fmt::Write,
+        S: serde::ser::SerializeMap<Ok = toml_edit::Value, Error = crate::edit::ser::Error>,
+    {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_key<T: ?Sized>(&mut self, input: &T) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.inner.serialize_key(input).map_err(Error::wrap)
+        }
+
+        fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.inner.serialize_value(value).map_err(Error::wrap)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            let value = self.inner.end().map_err(Error::wrap)?;
+            self.stream.write_entry(&self.key, value)
+        }
+    }
+
+    impl<'s, W, S> serde::ser::SerializeStruct for SerializeStreamValue<'s, W, S>
+    where
+        W: std::fmt::Write,
+        S: serde::ser::SerializeStruct<Ok = toml_edit::Value, Error = crate::edit::ser::Error>,
+    {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: ?Sized>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.inner.serialize_field(key, value).map_err(Error::wrap)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            let value = self.inner.end().map_err(Error::wrap)?;
+            self.stream.write_entry(&self.key, value)
+        }
+    }
+
+    impl<'s, W, S> serde::ser::SerializeTupleVariant for SerializeStreamValue<'s, W, S>
+    where
+        W: std::fmt::Write,
+        S: serde::ser::SerializeTupleVariant<
+            Ok = toml_edit::Value,
+            Error = crate::edit::ser::Error,
+        >,
+    {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.inner.serialize_field(value).map_err(Error::wrap)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            let value = self.inner.end().map_err(Error::wrap)?;
+            self.stream.write_entry(&self.key, value)
+        }
+    }
+
+    impl<'s, W, S> serde::ser::SerializeStructVariant for SerializeStreamValue<'s, W, S>
+    where
+        W: std::fmt::Write,
+        S: serde::ser::SerializeStructVariant<
+            Ok = toml_edit::Value,
+            Error = crate::edit::ser::Error,
+        >,
+    {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: ?Sized>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), Self::Error>
+        where
+            T: serde::ser::Serialize,
+        {
+            self.inner.serialize_field(key, value).map_err(Error::wrap)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            let value = self.inner.end().map_err(Error::wrap)?;
+            self.stream.write_entry(&self.key, value)
+        }
+    }
+
+    // Adapts an `std::io::Write` for `StreamSerializer`, keeping the IO error that `fmt::Write`
+    // can't carry
+    pub(crate) struct IoWriter<W> {
+        inner: W,
+        error: Option<std::io::Error>,
+    }
+
+    impl<W: std::io::Write> IoWriter<W> {
+        pub(crate) fn new(inner: W) -> Self {
+            Self { inner, error: None }
+        }
+
+        pub(crate) fn finish(mut self, result: Result<(), Error>) -> Result<(), Error> {
+            match (result, self.error.take()) {
+                (Err(_), Some(error)) => Err(Error::new(error)),
+                (result, _) => result,
+            }
+        }
+    }
+
+    impl<W: std::io::Write> std::fmt::Write for IoWriter<W> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.inner.write_all(s.as_bytes()).map_err(|error| {
+                self.error = Some(error);
+                std::fmt::Error
+            })
+        }
+    }
 }
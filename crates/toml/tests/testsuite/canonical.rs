@@ -0,0 +1,60 @@
+use serde::Serialize;
+use snapbox::assert_eq;
+
+#[test]
+fn canonical_value() {
+    let a: toml::Value = toml::from_str(
+        r#"
+title = 'example'   # comment
+owner = { name = "Tom", dob = 1979-05-27 07:32:00-08:00 }
+
+[[servers]]
+ip = "10.0.0.1"
+ports = [ 8000, 8001 ]
+"#,
+    )
+    .unwrap();
+    let b: toml::Value = toml::from_str(
+        r#"
+servers = [{ ports = [8000, 8001], ip = "10.0.0.1" }]
+title = "example"
+[owner]
+dob = 1979-05-27T07:32:00-08:00
+name = "Tom"
+"#,
+    )
+    .unwrap();
+
+    let expected = r#"title = "example"
+[owner]
+dob = 1979-05-27T07:32:00-08:00
+name = "Tom"
+[[servers]]
+ip = "10.0.0.1"
+ports = [8000, 8001]
+"#;
+    assert_eq(expected, toml::to_string_canonical(&a).unwrap());
+    assert_eq(expected, toml::to_string_canonical(&b).unwrap());
+}
+
+#[test]
+fn canonical_struct() {
+    #[derive(Serialize)]
+    struct Config {
+        version: f64,
+        name: &'static str,
+        features: Vec<&'static str>,
+        optional: Option<u8>,
+    }
+
+    let config = Config {
+        version: 1.0,
+        name: "foo",
+        features: vec![],
+        optional: None,
+    };
+    assert_eq(
+        "features = []\nname = \"foo\"\nversion = 1.0\n",
+        toml::to_string_canonical(&config).unwrap(),
+    );
+}
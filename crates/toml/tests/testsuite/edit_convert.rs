@@ -0,0 +1,66 @@
+use snapbox::assert_eq;
+use toml::{Table, Value};
+
+const INPUT: &str = r#"title = "example"
+float = 1.5
+date = 1979-05-27T07:32:00Z
+numbers = [1, 2, 3]
+points = [{ x = 1 }, { x = 2 }]
+
+[owner]
+name = "Tom"
+
+[owner.address]
+city = "Here"
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+"#;
+
+#[test]
+fn from_document() {
+    let doc = INPUT.parse::<toml_edit::DocumentMut>().unwrap();
+    let table = Table::from(doc);
+    assert_eq!(table, INPUT.parse::<Table>().unwrap());
+
+    let value: Value = toml_edit::value(5).try_into().unwrap();
+    assert_eq!(value, Value::Integer(5));
+    let none: Result<Value, _> = toml_edit::Item::None.try_into();
+    assert_eq("unsupported None value", none.unwrap_err().to_string());
+}
+
+#[test]
+fn to_document() {
+    let table = INPUT.parse::<Table>().unwrap();
+    let doc = toml_edit::DocumentMut::from(table.clone());
+    assert_eq(toml::to_string(&table).unwrap(), doc.to_string());
+    assert_eq!(Table::from(doc), table);
+}
+
+#[test]
+fn to_item() {
+    let value = INPUT.parse::<Table>().unwrap()["owner"].clone();
+    let item = toml_edit::Item::from(value.clone());
+    assert!(item.is_table());
+    assert!(item["address"].is_table());
+    assert_eq!(<Value as TryFrom<_>>::try_from(item).unwrap(), value);
+
+    let value = INPUT.parse::<Table>().unwrap()["bin"].clone();
+    assert!(toml_edit::Item::from(value).is_array_of_tables());
+    let value = INPUT.parse::<Table>().unwrap()["points"].clone();
+    let inline = toml_edit::Value::from(value);
+    assert_eq("[{ x = 1 }, { x = 2 }]", inline.to_string());
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn keeps_order() {
+    let input = "z = 1\na = 2\nm = 3\n";
+    let doc = input.parse::<toml_edit::DocumentMut>().unwrap();
+    let table = Table::from(doc);
+    assert_eq!(table.keys().collect::<Vec<_>>(), ["z", "a", "m"]);
+    assert_eq(input, toml_edit::DocumentMut::from(table).to_string());
+}
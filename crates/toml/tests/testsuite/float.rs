@@ -78,3 +78,65 @@ fn float_inf() {
     float_inf_tests!(f32);
     float_inf_tests!(f64);
 }
+
+#[test]
+fn float_f32_shortest() {
+    #[derive(Serialize, Deserialize)]
+    struct S {
+        tenth: f32,
+        whole: f32,
+        big: f32,
+        small: f32,
+        neg_inf: f32,
+        nan: f32,
+        widened: f64,
+    }
+    let s = S {
+        tenth: 0.1,
+        whole: 16_777_216.0,
+        big: 3.4e38,
+        small: -1.5e-7,
+        neg_inf: f32::NEG_INFINITY,
+        nan: -f32::NAN,
+        widened: f64::from(0.1f32),
+    };
+
+    let toml = toml::to_string(&s).unwrap();
+    assert_eq!(
+        toml,
+        "\
+tenth = 0.1
+whole = 16777216.0
+big = 340000000000000000000000000000000000000.0
+small = -0.00000015
+neg_inf = -inf
+nan = nan
+widened = 0.10000000149011612
+"
+    );
+
+    let roundtrip: S = toml::from_str(&toml).unwrap();
+    assert_eq!(roundtrip.tenth, s.tenth);
+    assert_eq!(roundtrip.big, s.big);
+    assert_eq!(roundtrip.small, s.small);
+}
+
+#[test]
+fn float_f32_value_matches_repr() {
+    #[derive(Serialize)]
+    struct S {
+        x: f32,
+        y: f32,
+    }
+    let s = S { x: 0.1, y: -1.5e-7 };
+
+    // The canonical form is written from the stored value rather than its repr
+    let toml = toml::to_string(&s).unwrap();
+    let canonical = toml::to_string_canonical(&s).unwrap();
+    assert_eq!(toml, "x = 0.1\ny = -0.00000015\n");
+    assert_eq!(canonical, toml);
+
+    let reparsed: Value = toml::from_str(&toml).unwrap();
+    assert_eq!(reparsed["x"].as_float(), Some(0.1));
+    assert_eq!(reparsed["y"].as_float(), Some(-1.5e-7));
+}
@@ -1,9 +1,11 @@
 #![recursion_limit = "256"]
 #![cfg(all(feature = "parse", feature = "display"))]
 
+mod canonical;
 mod de_errors;
 mod display;
 mod display_tricky;
+mod edit_convert;
 mod enum_external_deserialize;
 mod float;
 mod formatting;
@@ -12,4 +14,5 @@ mod pretty;
 mod serde;
 mod spanned;
 mod spanned_impls;
+mod stream;
 mod tables_last;
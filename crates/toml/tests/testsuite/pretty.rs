@@ -182,3 +182,23 @@ debug = true
     let pretty = toml::to_string_pretty(&pkg).unwrap();
     assert_eq(raw, pretty);
 }
+
+#[test]
+fn string_style() {
+    let toml = "\
+[example]
+multiline = \"\"\"
+first line
+second line\"\"\"
+oneline = \"\"\"
+it's one line\"\"\"
+";
+    let value: toml::Value = toml::from_str(toml).unwrap();
+    let mut result = String::with_capacity(128);
+    value
+        .serialize(
+            toml::Serializer::new(&mut result).string_style(toml::ser::StringStyle::MultilineBasic),
+        )
+        .unwrap();
+    assert_eq(toml, &result);
+}
@@ -0,0 +1,362 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use snapbox::assert_eq;
+
+#[derive(Serialize)]
+struct Export {
+    version: u32,
+    name: &'static str,
+    comment: Option<&'static str>,
+    tags: Vec<&'static str>,
+    empty: Vec<Record>,
+    owner: Owner,
+    record: Vec<Record>,
+}
+
+#[derive(Serialize)]
+struct Owner {
+    contact: Contact,
+}
+
+#[derive(Serialize)]
+struct Contact {
+    email: &'static str,
+}
+
+#[derive(Serialize)]
+struct Record {
+    id: u32,
+    labels: BTreeMap<&'static str, &'static str>,
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize)]
+struct Part {
+    size: f64,
+}
+
+fn export() -> Export {
+    Export {
+        version: 2,
+        name: "export",
+        comment: None,
+        tags: vec!["a", "b", "c"],
+        empty: vec![],
+        owner: Owner {
+            contact: Contact {
+                email: "me@example.com",
+            },
+        },
+        record: (1..=3)
+            .map(|id| Record {
+                id,
+                labels: [("kind", "test")].into_iter().collect(),
+                parts: vec![Part { size: 0.5 }],
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn stream_matches_to_string() {
+    let export = export();
+    let expected = r#"version = 2
+name = "export"
+tags = ["a", "b", "c"]
+empty = []
+
+[owner.contact]
+email = "me@example.com"
+
+[[record]]
+id = 1
+
+[record.labels]
+kind = "test"
+
+[[record.parts]]
+size = 0.5
+
+[[record]]
+id = 2
+
+[record.labels]
+kind = "test"
+
+[[record.parts]]
+size = 0.5
+
+[[record]]
+id = 3
+
+[record.labels]
+kind = "test"
+
+[[record.parts]]
+size = 0.5
+"#;
+    assert_eq(expected, toml::to_string(&export).unwrap());
+
+    let mut output = String::new();
+    export
+        .serialize(toml::ser::StreamSerializer::new(&mut output))
+        .unwrap();
+    assert_eq(expected, output);
+
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &export).unwrap();
+    assert_eq(expected, String::from_utf8(output).unwrap());
+}
+
+#[test]
+fn stream_pretty_matches_to_string_pretty() {
+    let export = export();
+    let mut output = Vec::new();
+    toml::to_writer_pretty(&mut output, &export).unwrap();
+    assert_eq(
+        toml::to_string_pretty(&export).unwrap(),
+        String::from_utf8(output).unwrap(),
+    );
+}
+
+#[test]
+fn stream_value() {
+    let value: toml::Value = toml::from_str(
+        r#"
+title = "example"
+owner = { name = "Tom", dob = 1979-05-27T07:32:00-08:00 }
+
+[[servers]]
+ip = "10.0.0.1"
+ports = [8000, 8001]
+"#,
+    )
+    .unwrap();
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &value).unwrap();
+    assert_eq(
+        toml::to_string(&value).unwrap(),
+        String::from_utf8(output).unwrap(),
+    );
+}
+
+#[test]
+fn stream_value_after_table() {
+    #[derive(Serialize)]
+    struct Late {
+        owner: Contact,
+        records: Vec<Part>,
+        version: u32,
+        empty: Vec<u32>,
+    }
+
+    let late = Late {
+        owner: Contact {
+            email: "me@example.com",
+        },
+        records: vec![Part { size: 1.0 }, Part { size: 2.0 }],
+        version: 1,
+        empty: vec![],
+    };
+    let expected = r#"version = 1
+empty = []
+
+[owner]
+email = "me@example.com"
+
+[[records]]
+size = 1.0
+
+[[records]]
+size = 2.0
+"#;
+    assert_eq(expected, toml::to_string(&late).unwrap());
+
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &late).unwrap();
+    assert_eq(expected, String::from_utf8(output).unwrap());
+
+    // Maps keep their tables until the end
+    let mut owner = toml::Table::new();
+    owner.insert("email".to_owned(), "me@example.com".into());
+    let mut map = BTreeMap::new();
+    map.insert("a", toml::Value::Table(owner));
+    map.insert("b", toml::Value::Integer(1));
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &map).unwrap();
+    assert_eq(
+        toml::to_string(&map).unwrap(),
+        String::from_utf8(output).unwrap(),
+    );
+}
+
+#[test]
+fn stream_records_unbuffered() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Sink(Rc<RefCell<Vec<u8>>>);
+
+    impl std::io::Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Record {
+        id: u32,
+    }
+
+    // Checks, as each record is serialized, that the one before it was already written
+    struct Records {
+        count: u32,
+        sink: Sink,
+    }
+
+    impl Serialize for Records {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeSeq as _;
+
+            let mut seq = serializer.serialize_seq(Some(self.count as usize))?;
+            for id in 0..self.count {
+                if 0 < id {
+                    let output = self.sink.0.borrow();
+                    let output = std::str::from_utf8(&output).unwrap();
+                    assert!(output.ends_with(&format!("id = {}\n", id - 1)), "{output}");
+                }
+                seq.serialize_element(&Record { id })?;
+            }
+            seq.end()
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Export {
+        version: u32,
+        record: Records,
+    }
+
+    let sink = Sink::default();
+    let export = Export {
+        version: 1,
+        record: Records {
+            count: 3,
+            sink: sink.clone(),
+        },
+    };
+    toml::to_writer(sink.clone(), &export).unwrap();
+    assert_eq(
+        "version = 1\n\n[[record]]\nid = 0\n\n[[record]]\nid = 1\n\n[[record]]\nid = 2\n",
+        String::from_utf8(sink.0.take()).unwrap(),
+    );
+}
+
+#[test]
+fn stream_mixed_array() {
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Table { id: u32 },
+        Number(u32),
+    }
+
+    #[derive(Serialize)]
+    struct Mixed {
+        entries: Vec<Entry>,
+    }
+
+    #[derive(Serialize)]
+    struct MixedFirst {
+        entries: Vec<Entry>,
+        version: u32,
+    }
+
+    let mixed = Mixed {
+        entries: vec![Entry::Table { id: 1 }, Entry::Number(2)],
+    };
+    let err = toml::to_writer(Vec::new(), &mixed).unwrap_err();
+    assert_eq(
+        "`entries` mixes tables with other values, which can't be streamed",
+        err.to_string(),
+    );
+
+    // Arrays starting with another value are written together, like `to_string` does
+    let mixed = Mixed {
+        entries: vec![Entry::Number(2), Entry::Table { id: 1 }],
+    };
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &mixed).unwrap();
+    assert_eq(
+        toml::to_string(&mixed).unwrap(),
+        String::from_utf8(output).unwrap(),
+    );
+
+    // As are arrays whose tables can't be written yet anyway
+    let mixed = MixedFirst {
+        entries: vec![Entry::Table { id: 1 }, Entry::Number(2)],
+        version: 1,
+    };
+    let mut output = Vec::new();
+    toml::to_writer(&mut output, &mixed).unwrap();
+    assert_eq(
+        "entries = [{ id = 1 }, 2]\nversion = 1\n",
+        String::from_utf8(output).unwrap(),
+    );
+    assert_eq(
+        "entries = [{ id = 1 }, 2]\nversion = 1\n",
+        toml::to_string(&mixed).unwrap(),
+    );
+}
+
+#[test]
+fn stream_struct_len_too_small() {
+    struct Bad;
+
+    impl Serialize for Bad {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeStruct as _;
+
+            let mut s = serializer.serialize_struct("Bad", 1)?;
+            s.serialize_field("record", &[Part { size: 1.0 }])?;
+            s.serialize_field("version", &2)?;
+            s.end()
+        }
+    }
+
+    assert_eq(
+        "version = 2\n\n[[record]]\nsize = 1.0\n",
+        toml::to_string(&Bad).unwrap(),
+    );
+    // The tables were already written, so `version` can't go before them
+    let err = toml::to_writer(Vec::new(), &Bad).unwrap_err();
+    assert_eq(
+        "`version` follows tables that were already written, which can't be streamed",
+        err.to_string(),
+    );
+}
+
+#[test]
+fn stream_io_error() {
+    struct Full;
+
+    impl std::io::Write for Full {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WriteZero.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = toml::to_writer(Full, &export()).unwrap_err();
+    assert_eq("write zero", err.to_string());
+}
@@ -0,0 +1,258 @@
+use std::ops::Range;
+
+use crate::comment::raw;
+use crate::table::{KeyValuePairs, DEFAULT_KEY_DECOR};
+use crate::value::DEFAULT_VALUE_DECOR;
+use crate::{Item, Key, Table, Value};
+
+/// Column alignment
+///
+/// Alignment applies to blocks of key/value lines, including dotted keys, within a table's body.
+/// A blank line ends a block, while comment lines don't.
+impl Table {
+    /// Pads the keys of each block so their `=` line up
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    /// let mut doc = "name = \"foo\"\nversion = \"1.0\"\n\nedition = \"2021\"\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    ///
+    /// doc.as_table_mut().align_entries();
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "name    = \"foo\"\nversion = \"1.0\"\n\nedition = \"2021\"\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn align_entries(&mut self) {
+        align_entries(self, 1);
+    }
+
+    /// Pads the values of each block so the comments after them line up
+    ///
+    /// Lines whose value spans several lines are left alone.
+    pub fn align_comments(&mut self) {
+        align_comments(self);
+    }
+
+    /// Inserts a key-value pair, following the alignment of the block it ends up in
+    ///
+    /// When the `=` of the other lines of the block line up, and some keys are padded to do so,
+    /// the new key is padded to the same column.  If the new key is too wide for it, the whole
+    /// block is realigned.  Otherwise, this is the same as [`Table::insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{value, DocumentMut};
+    /// let mut doc = "[package]\nname    = \"foo\"\nversion = \"1.0\"\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    ///
+    /// let package = doc["package"].as_table_mut().unwrap();
+    /// package.insert_aligned("edition", value("2021"));
+    /// package.insert_aligned("description", value("bar"));
+    /// assert_eq!(doc.to_string(), r#"[package]
+    /// name        = "foo"
+    /// version     = "1.0"
+    /// edition     = "2021"
+    /// description = "bar"
+    /// "#);
+    /// # }
+    /// ```
+    pub fn insert_aligned(&mut self, key: &str, item: Item) -> Option<Item> {
+        let old = self.insert(key, item);
+
+        let lines = lines(self);
+        let Some(index) = lines.iter().position(|l| l.key.as_deref() == Some(key)) else {
+            return old;
+        };
+        let block = blocks(&lines)
+            .into_iter()
+            .find(|block| block.contains(&index))
+            .expect("every line is in a block");
+        let mut others = block.clone().filter(|&i| i != index).map(|i| &lines[i]);
+        let Some(first) = others.next() else {
+            return old;
+        };
+        let column = first.key_width + first.pad;
+        let is_aligned = others.clone().all(|l| l.key_width + l.pad == column)
+            && std::iter::once(first).chain(others).any(|l| 1 < l.pad);
+        if !is_aligned {
+            return old;
+        }
+
+        let mut pads = vec![None; lines.len()];
+        let width = lines[index].key_width;
+        if width < column {
+            pads[index] = Some(column - width);
+        } else {
+            for i in block {
+                pads[i] = Some(width + 1 - lines[i].key_width);
+            }
+        }
+        set_pads(self, &pads);
+        old
+    }
+}
+
+/// Pads the keys of each block of `table` so their `=` line up, with at least `min_pad` spaces
+pub(crate) fn align_entries(table: &mut Table, min_pad: usize) {
+    let lines = lines(table);
+    let mut pads = vec![None; lines.len()];
+    for block in blocks(&lines) {
+        let width = lines[block.clone()]
+            .iter()
+            .map(|l| l.key_width)
+            .max()
+            .unwrap_or(0);
+        for i in block {
+            pads[i] = Some(width + min_pad - lines[i].key_width);
+        }
+    }
+    set_pads(table, &pads);
+}
+
+/// Pads the values of each block of `table` so the comments after them line up
+pub(crate) fn align_comments(table: &mut Table) {
+    let lines = lines(table);
+    let mut gaps = vec![None; lines.len()];
+    for block in blocks(&lines) {
+        let commented = || block.clone().filter(|&i| lines[i].has_comment);
+        let Some(column) = commented().filter_map(|i| lines[i].end()).max() else {
+            continue;
+        };
+        for i in commented() {
+            if let Some(end) = lines[i].end() {
+                gaps[i] = Some(column + 1 - end);
+            }
+        }
+    }
+
+    let mut gaps = gaps.into_iter();
+    for_each_line(&mut table.items, &mut |_, value| {
+        let Some(gap) = gaps.next().expect("same traversal") else {
+            return;
+        };
+        let suffix = raw(value.decor().suffix()).unwrap_or(DEFAULT_VALUE_DECOR.1);
+        if let Some(start) = suffix.find('#') {
+            let suffix = format!("{}{}", " ".repeat(gap), &suffix[start..]);
+            value.decor_mut().set_suffix(suffix);
+        }
+    });
+}
+
+// The layout of a `key = value` line
+struct Line {
+    // The key, for keys directly in the table
+    key: Option<String>,
+    // Whether a blank line comes before
+    is_first: bool,
+    // The width of the indentation and keys
+    key_width: usize,
+    // The width of the whitespace between the keys and the `=`
+    pad: usize,
+    // The width of the whitespace and value after the `=`, if on one line
+    value_width: Option<usize>,
+    has_comment: bool,
+}
+
+impl Line {
+    // The column the value ends at, if on one line
+    fn end(&self) -> Option<usize> {
+        let value_width = self.value_width?;
+        Some(self.key_width + self.pad + "=".len() + value_width)
+    }
+}
+
+fn lines(table: &Table) -> Vec<Line> {
+    table
+        .get_values()
+        .into_iter()
+        .map(|(path, value)| {
+            let leaf = path.last().expect("always at least one key").leaf_decor();
+            let prefix = raw(leaf.prefix()).unwrap_or(DEFAULT_KEY_DECOR.0);
+            let (head, indent) = prefix.rsplit_once('\n').unwrap_or(("", prefix));
+            let is_first = prefix.contains('\n') && head.split('\n').any(|l| l.trim().is_empty());
+
+            let mut key_width = indent.chars().count();
+            for (i, key) in path.iter().enumerate() {
+                let dotted = key.dotted_decor();
+                if i != 0 {
+                    key_width +=
+                        ".".len() + raw(dotted.prefix()).unwrap_or_default().chars().count();
+                }
+                key_width += key.display_repr().chars().count();
+                if i + 1 != path.len() {
+                    key_width += raw(dotted.suffix()).unwrap_or_default().chars().count();
+                }
+            }
+            let pad = raw(leaf.suffix())
+                .unwrap_or(DEFAULT_KEY_DECOR.1)
+                .chars()
+                .count();
+
+            let mut value = value.clone();
+            let prefix = raw(value.decor().prefix())
+                .unwrap_or(DEFAULT_VALUE_DECOR.0)
+                .to_owned();
+            let has_comment = value.decor().suffix_comment().is_some();
+            *value.decor_mut() = crate::Decor::new(prefix, "");
+            let value = value.to_string();
+            let value_width = (!value.contains('\n')).then(|| value.chars().count());
+
+            Line {
+                key: (path.len() == 1).then(|| path[0].get().to_owned()),
+                is_first,
+                key_width,
+                pad,
+                value_width,
+                has_comment,
+            }
+        })
+        .collect()
+}
+
+// Runs of lines without blank lines between them
+fn blocks(lines: &[Line]) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_first && start < i {
+            blocks.push(start..i);
+            start = i;
+        }
+    }
+    if start < lines.len() {
+        blocks.push(start..lines.len());
+    }
+    blocks
+}
+
+fn set_pads(table: &mut Table, pads: &[Option<usize>]) {
+    let mut pads = pads.iter();
+    for_each_line(&mut table.items, &mut |key, _| {
+        if let Some(pad) = pads.next().expect("same traversal") {
+            key.leaf_decor_mut().set_suffix(" ".repeat(*pad));
+        }
+    });
+}
+
+// Visits the lines in the order of `Table::get_values`
+fn for_each_line(items: &mut KeyValuePairs, f: &mut dyn FnMut(&mut Key, &mut Value)) {
+    for kv in items.values_mut() {
+        match &mut kv.value {
+            Item::Table(table) if table.is_dotted() => for_each_line(&mut table.items, f),
+            Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+                for_each_line(&mut table.items, f);
+            }
+            Item::Value(value) => f(&mut kv.key, value),
+            Item::None | Item::Table(_) | Item::ArrayOfTables(_) => {}
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::iter::FromIterator;
 use std::mem;
+use std::ops::RangeBounds;
 
 use crate::repr::Decor;
 use crate::value::{DEFAULT_LEADING_VALUE_DECOR, DEFAULT_VALUE_DECOR};
@@ -7,11 +8,11 @@ use crate::{Item, RawString, Value};
 
 /// Type representing a TOML array,
 /// payload of the `Value::Array` variant's value
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default)]
 pub struct Array {
     // `trailing` represents whitespaces, newlines
     // and comments in an empty array or after the trailing comma
-    trailing: RawString,
+    pub(crate) trailing: RawString,
     trailing_comma: bool,
     // prefix before `[` and suffix after `]`
     decor: Decor,
@@ -26,6 +27,8 @@ pub type ArrayIntoIter = Box<dyn Iterator<Item = Value>>;
 pub type ArrayIter<'a> = Box<dyn Iterator<Item = &'a Value> + 'a>;
 /// An iterator type over `Array`'s values.
 pub type ArrayIterMut<'a> = Box<dyn Iterator<Item = &'a mut Value> + 'a>;
+/// An iterator type over the values removed by [`Array::drain`].
+pub type ArrayDrain<'a> = Box<dyn Iterator<Item = Value> + 'a>;
 
 /// Constructors
 ///
@@ -43,10 +46,9 @@ impl Array {
     }
 
     pub(crate) fn with_vec(values: Vec<Item>) -> Self {
-        Self {
-            values,
-            ..Default::default()
-        }
+        let mut array = Self::new();
+        array.values = values;
+        array
     }
 }
 
@@ -87,6 +89,210 @@ impl Array {
         &self.decor
     }
 
+    /// Returns the comment lines before the value at `index`, without the leading `#`
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn leading_comments(&self, index: usize) -> Vec<&str> {
+        if index >= self.len() {
+            return Vec::new();
+        }
+        let prefix = self.value_prefix(index);
+        let prefix = if index == 0 {
+            prefix
+        } else {
+            // The first line belongs to the previous value
+            prefix
+                .split_once('\n')
+                .map(|(_, rest)| rest)
+                .unwrap_or_default()
+        };
+        crate::comment::leading_comments(prefix)
+    }
+
+    /// Replaces the comment lines before the value at `index`
+    ///
+    /// Each line of a comment becomes its own `#` comment line.  If the array is on a single
+    /// line, each value is moved to its own line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn set_leading_comments<I, S>(&mut self, index: usize, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.assert_index(index);
+        let comments = comments.into_iter().collect::<Vec<_>>();
+        if !comments.is_empty() {
+            self.make_multiline();
+        }
+        let prefix = self.value_prefix(index).to_owned();
+        let newline = crate::comment::newline(&prefix);
+        let (first, rest) = if let Some((first, rest)) = prefix.split_once('\n') {
+            if index == 0 {
+                (None, prefix.clone())
+            } else {
+                (Some(first.to_owned()), rest.to_owned())
+            }
+        } else if comments.is_empty() {
+            return;
+        } else {
+            (Some(String::new()), self.indent())
+        };
+        let mut prefix = first
+            .map(|first| format!("{}{}", first.trim_end_matches('\r'), newline))
+            .unwrap_or_default();
+        prefix.push_str(&crate::comment::set_leading_comments(&rest, &comments));
+        self.value_decor_mut(index).set_prefix(prefix);
+    }
+
+    /// Returns the comment on the same line, after the value at `index`, without the leading `#`
+    ///
+    /// A comment after the value's comma is considered to belong to the value, even when it was
+    /// parsed into the [`Decor`] of the next value.
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let array = "[\n  1, # one\n  2, # two\n]".parse::<toml_edit::Value>().unwrap();
+    /// let array = array.as_array().unwrap();
+    /// assert_eq!(array.trailing_comment(0), Some("one"));
+    /// assert_eq!(array.trailing_comment(1), Some("two"));
+    /// # }
+    /// ```
+    pub fn trailing_comment(&self, index: usize) -> Option<&str> {
+        if index >= self.len() {
+            return None;
+        }
+        crate::comment::trailing_comment(self.value_suffix(index)).or_else(|| {
+            self.comma_trailing(index)
+                .and_then(crate::comment::trailing_comment)
+        })
+    }
+
+    /// Sets or clears the comment on the same line, after the value at `index`
+    ///
+    /// The comment is kept in the value's own [`Decor`], moving it out of the next value's if
+    /// that is where it was parsed, and is written after the value's comma.  If the array is on a
+    /// single line, each value is moved to its own line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    pub fn set_trailing_comment(&mut self, index: usize, comment: Option<&str>) {
+        self.assert_index(index);
+        if comment.is_some() {
+            self.make_multiline();
+        }
+
+        if let Some(after_comma) = self.comma_trailing(index) {
+            if crate::comment::trailing_comment(after_comma).is_some() {
+                let after_comma = crate::comment::set_trailing_comment(after_comma, None);
+                self.set_comma_trailing(index, after_comma);
+            }
+        }
+
+        let suffix = self.value_suffix(index);
+        if comment.is_some() || crate::comment::trailing_comment(suffix).is_some() {
+            let suffix = crate::comment::set_trailing_comment(suffix, None);
+            let suffix = if suffix.trim().is_empty() {
+                String::new()
+            } else {
+                suffix
+            };
+            let suffix = crate::comment::set_trailing_comment(&suffix, comment);
+            self.value_decor_mut(index).set_suffix(suffix);
+        }
+    }
+
+    // Moves each value of a single-line array to its own line
+    fn make_multiline(&mut self) {
+        let is_single_line = (0..self.len())
+            .all(|i| !self.value_prefix(i).contains('\n') && !self.value_suffix(i).contains('\n'))
+            && !self.trailing.as_str().unwrap_or_default().contains('\n');
+        if self.is_empty() || !is_single_line {
+            return;
+        }
+        for i in 0..self.len() {
+            let comment = crate::comment::trailing_comment(self.value_suffix(i));
+            let suffix = crate::comment::set_trailing_comment("", comment);
+            let decor = self.value_decor_mut(i);
+            decor.set_prefix(format!("\n{}", DEFAULT_INDENT));
+            decor.set_suffix(suffix);
+        }
+        self.trailing_comma = true;
+        self.set_trailing("\n");
+    }
+
+    fn value_prefix(&self, index: usize) -> &str {
+        let default = if index == 0 {
+            DEFAULT_LEADING_VALUE_DECOR.0
+        } else {
+            DEFAULT_VALUE_DECOR.0
+        };
+        self.values[index]
+            .as_value()
+            .and_then(|v| v.decor().prefix())
+            .and_then(RawString::as_str)
+            .unwrap_or(default)
+    }
+
+    fn value_suffix(&self, index: usize) -> &str {
+        self.values[index]
+            .as_value()
+            .and_then(|v| v.decor().suffix())
+            .and_then(RawString::as_str)
+            .unwrap_or(DEFAULT_VALUE_DECOR.1)
+    }
+
+    // Whitespace and comments after the comma that follows the value at `index`
+    fn comma_trailing(&self, index: usize) -> Option<&str> {
+        if index + 1 < self.len() {
+            Some(self.value_prefix(index + 1))
+        } else if self.trailing_comma {
+            Some(self.trailing.as_str().unwrap_or_default())
+        } else {
+            None
+        }
+    }
+
+    fn set_comma_trailing(&mut self, index: usize, raw: String) {
+        if index + 1 < self.len() {
+            self.value_decor_mut(index + 1).set_prefix(raw);
+        } else {
+            self.set_trailing(raw);
+        }
+    }
+
+    // Indentation used for values that start on their own line
+    fn indent(&self) -> String {
+        (0..self.len())
+            .filter_map(|i| self.value_prefix(i).rsplit_once('\n'))
+            .map(|(_, indent)| indent.to_owned())
+            .next()
+            .unwrap_or_else(|| DEFAULT_INDENT.to_owned())
+    }
+
+    fn value_decor_mut(&mut self, index: usize) -> &mut Decor {
+        match &mut self.values[index] {
+            Item::Value(v) => v.decor_mut(),
+            x => panic!("non-value item {:?} in an array", x),
+        }
+    }
+
+    fn assert_index(&self, index: usize) {
+        assert!(
+            index < self.len(),
+            "index {} out of bounds (len = {})",
+            index,
+            self.len()
+        );
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -375,6 +581,133 @@ impl Array {
         });
     }
 
+    /// Appends already formatted values to the end of the array.
+    ///
+    /// Like [`Array::push_formatted`], the values keep their decor.
+    pub fn extend_formatted<I>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        self.values.extend(values.into_iter().map(Item::Value));
+    }
+
+    /// Replaces the values in `range` with `replace_with`, returning the removed values.
+    ///
+    /// The new values get default formatting, like [`Array::insert`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut arr = toml_edit::Array::from_iter([1, 2, 3]);
+    /// let removed = arr.splice(1..2, ["a", "b"]).collect::<Vec<_>>();
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(arr.len(), 4);
+    /// ```
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> ArrayIntoIter
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator,
+        I::Item: Into<Value>,
+    {
+        let start = match range.start_bound() {
+            std::ops::Bound::Included(start) => *start,
+            std::ops::Bound::Excluded(start) => start + 1,
+            std::ops::Bound::Unbounded => 0,
+        };
+        let replace_with = replace_with
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let mut value = value.into();
+                if start + i == 0 {
+                    value.decorate("", "");
+                } else {
+                    value.decorate(" ", "");
+                }
+                Item::Value(value)
+            })
+            .collect::<Vec<_>>();
+        let removed = self.values.splice(range, replace_with).collect::<Vec<_>>();
+        Box::new(removed.into_iter().filter_map(|v| v.into_value().ok()))
+    }
+
+    /// Removes the values in `range`, returning them as an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn drain<R>(&mut self, range: R) -> ArrayDrain<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        Box::new(self.values.drain(range).filter_map(|v| v.into_value().ok()))
+    }
+
+    /// Shortens the array, keeping the first `len` values.
+    ///
+    /// Has no effect if `len` is greater than the array's current length.
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    /// Swaps two values, along with their decor.
+    ///
+    /// See [`Array::fixed_layout`] to only swap the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.values.swap(a, b);
+    }
+
+    /// Reverses the order of the values, along with their decor.
+    ///
+    /// See [`Array::fixed_layout`] to only reverse the values.
+    pub fn reverse(&mut self) {
+        self.values.reverse();
+    }
+
+    /// Removes consecutive repeated values.
+    ///
+    /// Values are compared by their meaning, ignoring formatting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut arr = toml_edit::Array::from_iter([1, 1, 2, 1]);
+    /// arr.dedup();
+    /// assert_eq!(arr.len(), 3);
+    /// ```
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes all but the first of consecutive values for which `same_bucket` returns `true`.
+    ///
+    /// Like [`Vec::dedup_by`], `same_bucket` is passed the later value first.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut Value, &mut Value) -> bool,
+    {
+        self.values
+            .dedup_by(|a, b| match (a.as_value_mut(), b.as_value_mut()) {
+                (Some(a), Some(b)) => same_bucket(a, b),
+                _ => false,
+            });
+    }
+
+    /// Reorders values while the whitespace and comments of each slot stay in place
+    ///
+    /// The comments before and after a value move with it, see [`FixedLayout`].
+    pub fn fixed_layout(&mut self) -> FixedLayout<'_> {
+        FixedLayout { array: self }
+    }
+
     fn value_op<T>(
         &mut self,
         v: Value,
@@ -385,16 +718,169 @@ impl Array {
         if !self.is_empty() && decorate {
             value.decorate(" ", "");
         } else if decorate {
-            value.decorate("", "");
+            // Left unset, so the first value follows the document's array padding
+            value.decor_mut().clear();
         }
         op(&mut self.values, value)
     }
 }
 
+/// Reorders the values of an [`Array`] without changing its layout
+///
+/// Each slot of the array keeps its whitespace, so a multi-line array stays multi-line with one
+/// value per line.  The comment lines before a value and the comment at the end of its line are
+/// moved along with the value.
+///
+/// Created with [`Array::fixed_layout`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// let mut arr = "[\n  \"serde\", # serialization\n  # logging\n  \"log\",\n]"
+///     .parse::<toml_edit::Value>()
+///     .unwrap();
+/// let arr = arr.as_array_mut().unwrap();
+/// arr.fixed_layout().sort_by_key(|v| v.as_str().map(String::from));
+/// assert_eq!(
+///     arr.to_string(),
+///     "[\n  # logging\n  \"log\",\n  \"serde\", # serialization\n]"
+/// );
+/// # }
+/// # }
+/// ```
+pub struct FixedLayout<'a> {
+    array: &'a mut Array,
+}
+
+impl<'a> FixedLayout<'a> {
+    /// Sorts the values with a comparator function, see [`Array::sort_by`]
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Value, &Value) -> std::cmp::Ordering,
+    {
+        let mut order = (0..self.array.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| match (self.array.get(a), self.array.get(b)) {
+            (Some(a), Some(b)) => compare(a, b),
+            _ => std::cmp::Ordering::Equal,
+        });
+        self.permute(order);
+    }
+
+    /// Sorts the values with a key extraction function, see [`Array::sort_by_key`]
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Value) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Swaps two values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let mut order = (0..self.array.len()).collect::<Vec<_>>();
+        order.swap(a, b);
+        self.permute(order);
+    }
+
+    /// Reverses the order of the values
+    pub fn reverse(&mut self) {
+        let order = (0..self.array.len()).rev().collect::<Vec<_>>();
+        self.permute(order);
+    }
+
+    // Moves the value at `order[i]` to slot `i`
+    fn permute(&mut self, order: Vec<usize>) {
+        let array = &mut *self.array;
+        let comments = (0..array.len())
+            .map(|i| {
+                let leading = array
+                    .leading_comments(i)
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                (leading, array.trailing_comment(i).map(String::from))
+            })
+            .collect::<Vec<_>>();
+        for (i, (leading, trailing)) in comments.iter().enumerate() {
+            if trailing.is_some() {
+                array.set_trailing_comment(i, None);
+            }
+            if !leading.is_empty() {
+                array.set_leading_comments(i, Vec::<String>::new());
+            }
+        }
+
+        let mut values = mem::take(&mut array.values)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let decors = values
+            .iter()
+            .map(|v| {
+                v.as_ref()
+                    .and_then(Item::as_value)
+                    .map(|v| v.decor().clone())
+            })
+            .collect::<Vec<_>>();
+        array.values = order
+            .iter()
+            .zip(decors)
+            .map(|(&from, decor)| {
+                let mut item = values[from].take().expect("`order` is a permutation");
+                if let (Some(value), Some(decor)) = (item.as_value_mut(), decor) {
+                    *value.decor_mut() = decor;
+                }
+                item
+            })
+            .collect();
+
+        for (i, &from) in order.iter().enumerate() {
+            let (leading, trailing) = &comments[from];
+            if !leading.is_empty() {
+                array.set_leading_comments(i, leading);
+            }
+            if trailing.is_some() {
+                array.set_trailing_comment(i, trailing.as_deref());
+            }
+        }
+    }
+}
+
+impl Array {
+    // A copy of `self` holding `values`
+    pub(crate) fn clone_with(&self, values: Vec<Item>) -> Self {
+        Self {
+            trailing: self.trailing.clone(),
+            trailing_comma: self.trailing_comma,
+            decor: self.decor.clone(),
+            span: self.span.clone(),
+            values,
+        }
+    }
+}
+
+impl Clone for Array {
+    fn clone(&self) -> Self {
+        self.clone_with(crate::item::clone_items(self.values.iter()))
+    }
+}
+
+impl Drop for Array {
+    fn drop(&mut self) {
+        crate::item::drop_items(mem::take(&mut self.values));
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for Array {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_array(self, f, None, ("", ""))
+        crate::encode::encode_array(self, f, None, ("", ""), &Default::default())
     }
 }
 
@@ -412,10 +898,7 @@ impl<V: Into<Value>> FromIterator<V> for Array {
         I: IntoIterator<Item = V>,
     {
         let v = iter.into_iter().map(|a| Item::Value(a.into()));
-        Array {
-            values: v.collect(),
-            ..Default::default()
-        }
+        Array::with_vec(v.collect())
     }
 }
 
@@ -423,9 +906,9 @@ impl IntoIterator for Array {
     type Item = Value;
     type IntoIter = ArrayIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.values
+            mem::take(&mut self.values)
                 .into_iter()
                 .filter(|v| v.is_value())
                 .map(|v| v.into_value().unwrap()),
@@ -460,3 +943,6 @@ fn decorate_array(array: &mut Array) {
     array.set_trailing_comma(false);
     array.set_trailing("");
 }
+
+// Indentation of values moved onto their own line in an array that was all on one line
+const DEFAULT_INDENT: &str = "    ";
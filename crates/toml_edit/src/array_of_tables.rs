@@ -1,9 +1,11 @@
+use std::cmp::Ordering;
 use std::iter::FromIterator;
+use std::ops::RangeBounds;
 
-use crate::{Array, Item, Table};
+use crate::{Array, Item, Table, Value};
 
 /// Type representing a TOML array of tables
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Default)]
 pub struct ArrayOfTables {
     // Always Vec<Item::Table>, just `Item` to make `Index` work
     pub(crate) span: Option<std::ops::Range<usize>>,
@@ -23,11 +25,14 @@ impl ArrayOfTables {
 /// Formatting
 impl ArrayOfTables {
     /// Convert to an inline array
+    ///
+    /// Comments on the table headers are dropped, see `From<ArrayOfTables> for Array` to keep
+    /// them.
     pub fn into_array(mut self) -> Array {
         for value in self.values.iter_mut() {
             value.make_value();
         }
-        let mut a = Array::with_vec(self.values);
+        let mut a = Array::with_vec(std::mem::take(&mut self.values));
         a.fmt();
         a
     }
@@ -89,11 +94,137 @@ impl ArrayOfTables {
         self.values.push(Item::Table(table));
     }
 
+    /// Inserts a table at position `index`, shifting all tables after it.
+    ///
+    /// The tables are renumbered so they are rendered in the order of the array, see
+    /// [`Table::position`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, table: Table) {
+        self.values.insert(index, Item::Table(table));
+        self.renumber();
+    }
+
     /// Removes a table with the given index.
     pub fn remove(&mut self, index: usize) {
         self.values.remove(index);
     }
 
+    /// Swaps two tables, including the tables nested within them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[[bin]]\nname = \"a\"\n[[bin]]\nname = \"b\"\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// let bins = doc["bin"].as_array_of_tables_mut().unwrap();
+    /// bins.swap(0, 1);
+    /// assert_eq!(doc.to_string(), "[[bin]]\nname = \"b\"\n[[bin]]\nname = \"a\"\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.values.swap(a, b);
+        self.renumber();
+    }
+
+    /// Sorts the tables with a comparator function.
+    ///
+    /// The sort is stable and the tables are renumbered so they are rendered in their new order.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Table, &Table) -> Ordering,
+    {
+        self.values
+            .sort_by(|a, b| match (a.as_table(), b.as_table()) {
+                (Some(a), Some(b)) => compare(a, b),
+                _ => Ordering::Equal,
+            });
+        self.renumber();
+    }
+
+    /// Sorts the tables with a key extraction function.
+    ///
+    /// The sort is stable and the tables are renumbered so they are rendered in their new order.
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&Table) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Removes the tables in `range`, returning them as an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn drain<R>(&mut self, range: R) -> ArrayOfTablesDrain<'_>
+    where
+        R: RangeBounds<usize>,
+    {
+        Box::new(self.values.drain(range).filter_map(|v| v.into_table().ok()))
+    }
+
+    /// Replaces the tables in `range` with `replace_with`, returning the removed tables.
+    ///
+    /// The tables are renumbered so they are rendered in the order of the array, see
+    /// [`Table::position`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> ArrayOfTablesIntoIter
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = Table>,
+    {
+        let removed = self
+            .values
+            .splice(range, replace_with.into_iter().map(Item::Table))
+            .collect::<Vec<_>>();
+        self.renumber();
+        Box::new(removed.into_iter().filter_map(|v| v.into_table().ok()))
+    }
+
+    /// Returns the first table where `key` holds `value`.
+    ///
+    /// Values are compared by their meaning, ignoring formatting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "[[bin]]\nname = \"a\"\n[[bin]]\nname = 'b'\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// let bins = doc["bin"].as_array_of_tables().unwrap();
+    /// assert!(bins.find_by("name", "b").is_some());
+    /// assert!(bins.find_by("name", "c").is_none());
+    /// # }
+    /// ```
+    pub fn find_by(&self, key: &str, value: impl Into<Value>) -> Option<&Table> {
+        let value = value.into();
+        self.iter()
+            .find(|t| t.get(key).and_then(Item::as_value) == Some(&value))
+    }
+
+    /// Returns the first table where `key` holds `value`, see [`ArrayOfTables::find_by`].
+    pub fn find_by_mut(&mut self, key: &str, value: impl Into<Value>) -> Option<&mut Table> {
+        let value = value.into();
+        self.iter_mut()
+            .find(|t| t.get(key).and_then(Item::as_value) == Some(&value))
+    }
+
     /// Retains only the elements specified by the `keep` predicate.
     ///
     /// In other words, remove all tables for which `keep(&table)` returns `false`.
@@ -109,12 +240,52 @@ impl ArrayOfTables {
     }
 }
 
+impl ArrayOfTables {
+    // Reassigns the document positions of the headers so the tables are rendered in array order
+    //
+    // Tables nested in a moved entry share its position so they stay right after it.  Tables
+    // without a position are left alone as they are rendered after the table before them.
+    pub(crate) fn renumber(&mut self) {
+        let mut anchors = self.iter().filter_map(Table::position).collect::<Vec<_>>();
+        anchors.sort_unstable();
+        let mut anchors = anchors.into_iter();
+        for table in self.iter_mut() {
+            if table.position().is_none() {
+                continue;
+            }
+            let anchor = anchors.next().expect("one anchor per position");
+            if table.position() != Some(anchor) {
+                reposition(table, anchor);
+            }
+        }
+    }
+}
+
+fn reposition(table: &mut Table, position: usize) {
+    if table.position().is_some() {
+        table.set_position(position);
+    }
+    for kv in table.items.values_mut() {
+        match &mut kv.value {
+            Item::Table(t) => reposition(t, position),
+            Item::ArrayOfTables(a) => {
+                for t in a.iter_mut() {
+                    reposition(t, position);
+                }
+            }
+            Item::None | Item::Value(_) => {}
+        }
+    }
+}
+
 /// An iterator type over `ArrayOfTables`'s values.
 pub type ArrayOfTablesIter<'a> = Box<dyn Iterator<Item = &'a Table> + 'a>;
 /// An iterator type over `ArrayOfTables`'s values.
 pub type ArrayOfTablesIterMut<'a> = Box<dyn Iterator<Item = &'a mut Table> + 'a>;
 /// An iterator type over `ArrayOfTables`'s values.
 pub type ArrayOfTablesIntoIter = Box<dyn Iterator<Item = Table>>;
+/// An iterator type over the tables removed by [`ArrayOfTables::drain`].
+pub type ArrayOfTablesDrain<'a> = Box<dyn Iterator<Item = Table> + 'a>;
 
 impl Extend<Table> for ArrayOfTables {
     fn extend<T: IntoIterator<Item = Table>>(&mut self, iter: T) {
@@ -137,13 +308,86 @@ impl FromIterator<Table> for ArrayOfTables {
     }
 }
 
+/// Converts to an array of inline tables
+///
+/// Unlike [`ArrayOfTables::into_array`], the comments on each table header are kept on the
+/// corresponding array entry, which moves the entries to their own lines.
+impl From<ArrayOfTables> for Array {
+    fn from(array: ArrayOfTables) -> Self {
+        let comments = array
+            .iter()
+            .map(|t| {
+                let leading = t
+                    .leading_comments()
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                (leading, t.trailing_comment().map(String::from))
+            })
+            .collect::<Vec<_>>();
+        let mut array = array.into_array();
+        for (index, (leading, trailing)) in comments.into_iter().enumerate() {
+            if !leading.is_empty() {
+                array.set_leading_comments(index, leading);
+            }
+            if trailing.is_some() {
+                array.set_trailing_comment(index, trailing.as_deref());
+            }
+        }
+        array
+    }
+}
+
+/// Converts an array of inline tables
+///
+/// The comments on each entry are moved to the corresponding table header.  The tables have no
+/// [`Table::position`], so they are rendered after the table before them.
+///
+/// Fails with the original array if any of the values are not inline tables.
+impl TryFrom<Array> for ArrayOfTables {
+    type Error = Array;
+
+    fn try_from(array: Array) -> Result<Self, Self::Error> {
+        if !array.iter().all(Value::is_inline_table) {
+            return Err(array);
+        }
+        let comments = (0..array.len())
+            .map(|index| {
+                let leading = array
+                    .leading_comments(index)
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                (leading, array.trailing_comment(index).map(String::from))
+            })
+            .collect::<Vec<_>>();
+        let tables = array
+            .into_iter()
+            .zip(comments)
+            .filter_map(|(value, (leading, trailing))| {
+                let Value::InlineTable(table) = value else {
+                    return None;
+                };
+                let mut table = table.into_table();
+                if !leading.is_empty() {
+                    table.set_leading_comments(leading);
+                }
+                if trailing.is_some() {
+                    table.set_trailing_comment(trailing.as_deref());
+                }
+                Some(table)
+            });
+        Ok(tables.collect())
+    }
+}
+
 impl IntoIterator for ArrayOfTables {
     type Item = Table;
     type IntoIter = ArrayOfTablesIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.values
+            std::mem::take(&mut self.values)
                 .into_iter()
                 .filter(|v| v.is_table())
                 .map(|v| v.into_table().unwrap()),
@@ -160,6 +404,28 @@ impl<'s> IntoIterator for &'s ArrayOfTables {
     }
 }
 
+impl ArrayOfTables {
+    // A copy of `self` holding `values`
+    pub(crate) fn clone_with(&self, values: Vec<Item>) -> Self {
+        Self {
+            span: self.span.clone(),
+            values,
+        }
+    }
+}
+
+impl Clone for ArrayOfTables {
+    fn clone(&self) -> Self {
+        self.clone_with(crate::item::clone_items(self.values.iter()))
+    }
+}
+
+impl Drop for ArrayOfTables {
+    fn drop(&mut self) {
+        crate::item::drop_items(std::mem::take(&mut self.values));
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for ArrayOfTables {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,267 @@
+use std::fmt::Write as _;
+
+use crate::encode::{to_string_repr, StringLayout};
+use crate::{DocumentMut, Item, Table, Value};
+
+impl DocumentMut {
+    /// Renders the document in a deterministic form, for hashing and signing
+    ///
+    /// The output only depends on the keys and values of the document, not on how it was
+    /// written: comments, whitespace, quoting, the order of keys and the choice between standard,
+    /// inline and dotted tables are all dropped.
+    ///
+    /// This encoding is stable: the same keys and values produce the same bytes in every release
+    /// of this crate.  It is:
+    ///
+    /// - One line per key/value pair and per table header, each ending with `\n`, without blank
+    ///   lines or comments
+    /// - Within each table, key/value pairs first, then its tables, each sorted by key, comparing
+    ///   the UTF-8 bytes of the unescaped keys
+    /// - Every table, whether standard, inline or dotted, as a standard table, with its full path
+    ///   as a `[header]`.  Headers are left out for non-empty tables that only hold other tables.
+    /// - Non-empty arrays of only tables, whether arrays of tables or arrays of inline tables, as
+    ///   `[[header]]` sections.  Tables within other arrays are written `{ a = 1, b = 2 }`.
+    /// - `key = value`, with keys as `bare-keys` when they are non-empty and made of ASCII
+    ///   letters, digits, `-` and `_`, and as `"basic strings"` otherwise.  Dotted keys are not
+    ///   used.
+    /// - Strings as `"basic strings"` on one line, escaping `"`, `\`, and control characters as
+    ///   `\b`, `\t`, `\n`, `\f`, `\r`, or `\uXXXX`.  Strings are not Unicode normalized.
+    /// - Integers in decimal
+    /// - Floats as the shortest decimal that reads back as the same value, with no exponent and
+    ///   at least one fractional digit, like `0.1`, `-0.0` and `1.0`, or as `inf`, `-inf` and
+    ///   `nan`.  The sign of `nan` is dropped.
+    /// - Datetimes with a `T` between the date and time, fractional seconds without trailing
+    ///   zeros, and the offset as `Z` or `+HH:MM`, like `1979-05-27T07:32:00.5-07:00`
+    /// - Arrays as `[1, 2, 3]`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let a = r#"
+    /// [package]
+    /// version = '1.0'   # bumped on release
+    /// name = "foo"
+    /// dependencies.serde = { version = "1", features = ["derive"] }
+    /// "#.parse::<DocumentMut>().unwrap();
+    /// let b = r#"
+    /// package.name = "foo"
+    /// package.version = "1.0"
+    /// [package.dependencies.serde]
+    /// features = [ "derive" ]
+    /// version = "1"
+    /// "#.parse::<DocumentMut>().unwrap();
+    ///
+    /// assert_eq!(a.to_canonical_string(), r#"[package]
+    /// name = "foo"
+    /// version = "1.0"
+    /// [package.dependencies.serde]
+    /// features = ["derive"]
+    /// version = "1"
+    /// "#);
+    /// assert_eq!(a.to_canonical_string(), b.to_canonical_string());
+    /// # }
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        let mut output = String::new();
+        let root = Node::table(self.as_table());
+        if let Node::Table(entries) = &root {
+            write_table(&mut output, &mut Vec::new(), entries, Header::None);
+        }
+        output
+    }
+}
+
+// The keys and values of a document, without their formatting
+enum Node<'n> {
+    Scalar(&'n Value),
+    Array(Vec<Node<'n>>),
+    // Sorted by key
+    Table(Vec<(&'n str, Node<'n>)>),
+}
+
+impl<'n> Node<'n> {
+    fn item(item: &'n Item) -> Option<Self> {
+        match item {
+            Item::None => None,
+            Item::Value(value) => Some(Self::value(value)),
+            Item::Table(table) => Some(Self::table(table)),
+            Item::ArrayOfTables(array) => {
+                Some(Self::Array(array.iter().map(Self::table).collect()))
+            }
+        }
+    }
+
+    fn value(value: &'n Value) -> Self {
+        match value {
+            Value::Array(array) => Self::Array(array.iter().map(Self::value).collect()),
+            Value::InlineTable(table) => Self::sorted(
+                table
+                    .iter()
+                    .map(|(key, value)| (key, Self::value(value)))
+                    .collect(),
+            ),
+            Value::String(_)
+            | Value::Integer(_)
+            | Value::Float(_)
+            | Value::Boolean(_)
+            | Value::Datetime(_) => Self::Scalar(value),
+        }
+    }
+
+    fn table(table: &'n Table) -> Self {
+        Self::sorted(
+            table
+                .iter()
+                .filter_map(|(key, item)| Some((key, Self::item(item)?)))
+                .collect(),
+        )
+    }
+
+    fn sorted(mut entries: Vec<(&'n str, Node<'n>)>) -> Self {
+        entries.sort_by_key(|(key, _)| *key);
+        Self::Table(entries)
+    }
+
+    // Written as a section of its own rather than after a key
+    fn is_section(&self) -> bool {
+        match self {
+            Self::Scalar(_) => false,
+            Self::Array(values) => {
+                !values.is_empty() && values.iter().all(|v| matches!(v, Self::Table(_)))
+            }
+            Self::Table(_) => true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Header {
+    None,
+    Table,
+    ArrayOfTables,
+}
+
+fn write_table<'n>(
+    output: &mut String,
+    path: &mut Vec<&'n str>,
+    entries: &'n [(&'n str, Node<'n>)],
+    header: Header,
+) {
+    let (sections, values): (Vec<_>, Vec<_>) =
+        entries.iter().partition(|(_, node)| node.is_section());
+
+    let is_implicit = header == Header::Table && values.is_empty() && !sections.is_empty();
+    if header != Header::None && !is_implicit {
+        let (open, close) = if header == Header::ArrayOfTables {
+            ("[[", "]]")
+        } else {
+            ("[", "]")
+        };
+        output.push_str(open);
+        for (i, key) in path.iter().enumerate() {
+            if i != 0 {
+                output.push('.');
+            }
+            write_key(output, key);
+        }
+        output.push_str(close);
+        output.push('\n');
+    }
+
+    for (key, node) in values {
+        write_key(output, key);
+        output.push_str(" = ");
+        write_value(output, node);
+        output.push('\n');
+    }
+
+    for (key, node) in sections {
+        path.push(key);
+        match node {
+            Node::Table(entries) => write_table(output, path, entries, Header::Table),
+            Node::Array(tables) => {
+                for table in tables {
+                    if let Node::Table(entries) = table {
+                        write_table(output, path, entries, Header::ArrayOfTables);
+                    }
+                }
+            }
+            Node::Scalar(_) => unreachable!("scalars are never sections"),
+        }
+        path.pop();
+    }
+}
+
+fn write_value(output: &mut String, node: &Node<'_>) {
+    match node {
+        Node::Scalar(value) => write_scalar(output, value),
+        Node::Array(values) => {
+            output.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i != 0 {
+                    output.push_str(", ");
+                }
+                write_value(output, value);
+            }
+            output.push(']');
+        }
+        Node::Table(entries) if entries.is_empty() => output.push_str("{}"),
+        Node::Table(entries) => {
+            output.push_str("{ ");
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i != 0 {
+                    output.push_str(", ");
+                }
+                write_key(output, key);
+                output.push_str(" = ");
+                write_value(output, value);
+            }
+            output.push_str(" }");
+        }
+    }
+}
+
+fn write_scalar(output: &mut String, value: &Value) {
+    match value {
+        Value::String(s) => write_string(output, s.value()),
+        Value::Integer(i) => write!(output, "{}", i.value()).unwrap(),
+        Value::Float(f) => write_float(output, *f.value()),
+        Value::Boolean(b) => write!(output, "{}", b.value()).unwrap(),
+        Value::Datetime(d) => write!(output, "{}", d.value()).unwrap(),
+        Value::Array(_) | Value::InlineTable(_) => unreachable!("not a scalar"),
+    }
+}
+
+fn write_key(output: &mut String, key: &str) {
+    let is_bare = !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if is_bare {
+        output.push_str(key);
+    } else {
+        write_string(output, key);
+    }
+}
+
+fn write_string(output: &mut String, value: &str) {
+    let repr = to_string_repr(value, Some(StringLayout::OnelineSingle), Some(false));
+    output.push_str(repr.as_raw().as_str().expect("always set"));
+}
+
+fn write_float(output: &mut String, value: f64) {
+    if value.is_nan() {
+        output.push_str("nan");
+        return;
+    }
+    // `Display` for `f64` writes the shortest round-tripping decimal without an exponent, and
+    // `inf` for infinities
+    let start = output.len();
+    write!(output, "{value}").unwrap();
+    if value.is_finite() && !output[start..].contains('.') {
+        output.push_str(".0");
+    }
+}
@@ -0,0 +1,151 @@
+// Comments live inside of `Decor` prefixes and suffixes (and a few other `RawString`s, like
+// `Array::trailing`).  These helpers operate on the raw text so each node type can expose typed
+// accessors over it.
+
+use crate::RawString;
+
+/// The text of a raw string, unless it is a span of the original document
+pub(crate) fn raw(raw: Option<&RawString>) -> Option<&str> {
+    raw.and_then(RawString::as_str)
+}
+
+/// Extract the text of a comment line, without the `#` and the conventional space after it
+pub(crate) fn comment_text(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches(is_ws).strip_prefix('#')?;
+    let text = text.strip_prefix(' ').unwrap_or(text);
+    Some(text.trim_end_matches(['\r', ' ', '\t']))
+}
+
+/// All comment lines in a prefix-like raw string
+pub(crate) fn leading_comments(raw: &str) -> Vec<&str> {
+    raw.split('\n').filter_map(comment_text).collect()
+}
+
+/// Replace the comment lines in a prefix-like raw string
+///
+/// Whitespace-only lines before the first comment are kept, as is the indentation on the last line
+/// which belongs to the decorated node.
+pub(crate) fn set_leading_comments<S: AsRef<str>>(raw: &str, comments: &[S]) -> String {
+    let newline = newline(raw);
+    let (head, indent) = match raw.rsplit_once('\n') {
+        Some((head, indent)) => (Some(head), indent),
+        None => (None, raw),
+    };
+
+    let mut output = String::with_capacity(raw.len());
+    if let Some(head) = head {
+        for line in head.split('\n') {
+            if comment_text(line).is_some() {
+                break;
+            }
+            output.push_str(line.trim_end_matches('\r'));
+            output.push_str(newline);
+        }
+    }
+    for comment in comments {
+        for line in comment.as_ref().split('\n') {
+            output.push_str(indent);
+            push_comment(&mut output, line);
+            output.push_str(newline);
+        }
+    }
+    output.push_str(indent);
+    output
+}
+
+/// The comment on the first line of a suffix-like raw string
+pub(crate) fn trailing_comment(raw: &str) -> Option<&str> {
+    let line = first_line(raw);
+    let start = line.find('#')?;
+    comment_text(&line[start..])
+}
+
+/// Replace the comment on the first line of a suffix-like raw string
+///
+/// Anything after the first line is left untouched.
+pub(crate) fn set_trailing_comment(raw: &str, comment: Option<&str>) -> String {
+    let line = first_line(raw);
+    let rest = &raw[line.len()..];
+    let ws = line.find('#').map(|start| &line[..start]).unwrap_or(line);
+
+    let mut output = String::with_capacity(raw.len());
+    if let Some(comment) = comment {
+        output.push_str(if ws.is_empty() { " " } else { ws });
+        push_comment(&mut output, comment);
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Whether a prefix-like raw string has an empty line before its first comment
+pub(crate) fn has_blank_line(raw: &str) -> bool {
+    let head = raw.find('#').map(|start| &raw[..start]).unwrap_or(raw);
+    head.contains('\n')
+}
+
+/// The newline sequence used by a raw string, defaulting to `\n`
+pub(crate) fn newline(raw: &str) -> &'static str {
+    if raw.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// The text before the first newline, excluding a `\r` of a CRLF
+pub(crate) fn first_line(raw: &str) -> &str {
+    let line = raw.split('\n').next().unwrap_or(raw);
+    if line.len() < raw.len() {
+        line.strip_suffix('\r').unwrap_or(line)
+    } else {
+        line
+    }
+}
+
+fn push_comment(output: &mut String, text: &str) {
+    output.push('#');
+    if !text.is_empty() {
+        output.push(' ');
+    }
+    // Control characters, including newlines, are not allowed within a comment
+    output.extend(text.chars().map(|c| {
+        if (c <= '\u{1f}' && c != '\t') || c == '\u{7f}' {
+            ' '
+        } else {
+            c
+        }
+    }));
+}
+
+fn is_ws(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn leading_roundtrip() {
+        let raw = "\n# one\n#two\n    ";
+        assert_eq!(leading_comments(raw), vec!["one", "two"]);
+        let raw = set_leading_comments(raw, &["three"]);
+        assert_eq!(raw, "\n    # three\n    ");
+        assert_eq!(leading_comments(&raw), vec!["three"]);
+        let raw = set_leading_comments(&raw, &[] as &[&str]);
+        assert_eq!(raw, "\n    ");
+    }
+
+    #[test]
+    fn trailing_roundtrip() {
+        assert_eq!(trailing_comment("  # hello  "), Some("hello"));
+        assert_eq!(trailing_comment(" "), None);
+        assert_eq!(set_trailing_comment("", Some("hi")), " # hi");
+        assert_eq!(
+            set_trailing_comment("  # old\n  ", Some("new")),
+            "  # new\n  "
+        );
+        assert_eq!(set_trailing_comment("  # old\r\n", None), "\r\n");
+        assert_eq!(set_trailing_comment("", Some("a\nb")), " # a b");
+    }
+}
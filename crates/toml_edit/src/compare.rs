@@ -0,0 +1,385 @@
+use std::hash::{Hash, Hasher};
+
+use crate::key::Key;
+use crate::table::KeyValuePairs;
+use crate::{Array, ArrayOfTables, Datetime, DocumentMut, InlineTable, Item, Table, Value};
+
+// Semantic equality
+//
+// Two nodes are equal when they hold the same data, regardless of how it is written: formatting,
+// comments, key quoting and order, and whether a table is standard, dotted or inline, or an array
+// of tables is an array of inline tables.  Floats are equal when they are the same number, with
+// `-0.0 == 0.0` and `nan == nan`.
+//
+// Hashes agree with equality.  They feed the hasher explicit tags and fixed-width integers rather
+// than relying on `std`'s hashing of enums and lengths, so the same data gives the same hash on any
+// build and platform for a deterministic hasher.
+
+trait Semantic {
+    fn view(&self) -> Option<View<'_>>;
+}
+
+macro_rules! semantic_eq {
+    ($($ty:ty),*) => {$(
+        /// Compares the data, regardless of how it is written
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.view() == other.view()
+            }
+        }
+
+        impl Eq for $ty {}
+
+        /// Hashes the data, consistently with `==`
+        ///
+        /// The hash is stable: the same data feeds a [`Hasher`] the same input on any build and
+        /// platform.
+        impl Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                match self.view() {
+                    Some(view) => view.hash(state),
+                    None => state.write_u8(tag::NONE),
+                }
+            }
+        }
+    )*};
+}
+
+semantic_eq!(
+    Item,
+    Value,
+    Table,
+    InlineTable,
+    Array,
+    ArrayOfTables,
+    DocumentMut
+);
+
+impl Semantic for Item {
+    fn view(&self) -> Option<View<'_>> {
+        View::item(self)
+    }
+}
+
+impl Semantic for Value {
+    fn view(&self) -> Option<View<'_>> {
+        Some(View::value(self))
+    }
+}
+
+impl Semantic for Table {
+    fn view(&self) -> Option<View<'_>> {
+        Some(View::Table(&self.items))
+    }
+}
+
+impl Semantic for InlineTable {
+    fn view(&self) -> Option<View<'_>> {
+        Some(View::Table(&self.items))
+    }
+}
+
+impl Semantic for Array {
+    fn view(&self) -> Option<View<'_>> {
+        Some(View::Array(&self.values))
+    }
+}
+
+impl Semantic for ArrayOfTables {
+    fn view(&self) -> Option<View<'_>> {
+        Some(View::Array(&self.values))
+    }
+}
+
+impl Semantic for DocumentMut {
+    fn view(&self) -> Option<View<'_>> {
+        View::item(&self.root)
+    }
+}
+
+#[derive(Copy, Clone)]
+enum View<'a> {
+    String(&'a str),
+    Integer(i64),
+    Float(u64),
+    Boolean(bool),
+    Datetime(&'a Datetime),
+    Array(&'a [Item]),
+    Table(&'a KeyValuePairs),
+}
+
+impl<'a> View<'a> {
+    fn item(item: &'a Item) -> Option<Self> {
+        match item {
+            Item::None => None,
+            Item::Value(v) => Some(Self::value(v)),
+            Item::Table(t) => Some(Self::Table(&t.items)),
+            Item::ArrayOfTables(a) => Some(Self::Array(&a.values)),
+        }
+    }
+
+    fn value(value: &'a Value) -> Self {
+        match value {
+            Value::String(v) => Self::String(v.value()),
+            Value::Integer(v) => Self::Integer(*v.value()),
+            Value::Float(v) => Self::Float(float_bits(*v.value())),
+            Value::Boolean(v) => Self::Boolean(*v.value()),
+            Value::Datetime(v) => Self::Datetime(v.value()),
+            Value::Array(v) => Self::Array(&v.values),
+            Value::InlineTable(v) => Self::Table(&v.items),
+        }
+    }
+}
+
+impl PartialEq for View<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a == b,
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            (Self::Datetime(a), Self::Datetime(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a == b)
+            }
+            (Self::Table(a), Self::Table(b)) => {
+                entries(a).count() == entries(b).count()
+                    && entries(a).all(|(key, a)| {
+                        b.get(key)
+                            .and_then(|kv| View::item(&kv.value))
+                            .map(|b| a == b)
+                            .unwrap_or(false)
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Hash for View<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::String(v) => {
+                state.write_u8(tag::STRING);
+                write_str(v, state);
+            }
+            Self::Integer(v) => {
+                state.write_u8(tag::INTEGER);
+                state.write_i64(*v);
+            }
+            Self::Float(v) => {
+                state.write_u8(tag::FLOAT);
+                state.write_u64(*v);
+            }
+            Self::Boolean(v) => {
+                state.write_u8(tag::BOOLEAN);
+                state.write_u8(u8::from(*v));
+            }
+            Self::Datetime(v) => {
+                state.write_u8(tag::DATETIME);
+                write_datetime(v, state);
+            }
+            Self::Array(v) => {
+                state.write_u8(tag::ARRAY);
+                state.write_u64(v.len() as u64);
+                for item in v.iter() {
+                    item.hash(state);
+                }
+            }
+            Self::Table(v) => {
+                let mut entries = entries(v).collect::<Vec<_>>();
+                entries.sort_unstable_by_key(|(key, _)| *key);
+                state.write_u8(tag::TABLE);
+                state.write_u64(entries.len() as u64);
+                for (key, value) in entries {
+                    write_str(key, state);
+                    value.hash(state);
+                }
+            }
+        }
+    }
+}
+
+// The first byte hashed for each kind of node
+mod tag {
+    pub(super) const NONE: u8 = 0;
+    pub(super) const STRING: u8 = 1;
+    pub(super) const INTEGER: u8 = 2;
+    pub(super) const FLOAT: u8 = 3;
+    pub(super) const BOOLEAN: u8 = 4;
+    pub(super) const DATETIME: u8 = 5;
+    pub(super) const ARRAY: u8 = 6;
+    pub(super) const TABLE: u8 = 7;
+}
+
+fn write_str<H: Hasher>(s: &str, state: &mut H) {
+    state.write_u64(s.len() as u64);
+    state.write(s.as_bytes());
+}
+
+// Each part is preceded by whether it is present, like `Datetime`'s equality compares them
+fn write_datetime<H: Hasher>(datetime: &Datetime, state: &mut H) {
+    match datetime.date {
+        Some(date) => {
+            state.write_u8(1);
+            state.write_u16(date.year);
+            state.write_u8(date.month);
+            state.write_u8(date.day);
+        }
+        None => state.write_u8(0),
+    }
+    match datetime.time {
+        Some(time) => {
+            state.write_u8(1);
+            state.write_u8(time.hour);
+            state.write_u8(time.minute);
+            state.write_u8(time.second);
+            state.write_u32(time.nanosecond);
+        }
+        None => state.write_u8(0),
+    }
+    match datetime.offset {
+        Some(toml_datetime::Offset::Z) => state.write_u8(1),
+        Some(toml_datetime::Offset::Custom { minutes }) => {
+            state.write_u8(2);
+            state.write_i16(minutes);
+        }
+        None => state.write_u8(0),
+    }
+}
+
+fn entries(items: &KeyValuePairs) -> impl Iterator<Item = (&str, View<'_>)> {
+    items
+        .iter()
+        .filter_map(|(key, kv)| Some((key.as_str(), View::item(&kv.value)?)))
+}
+
+fn float_bits(f: f64) -> u64 {
+    if f.is_nan() {
+        f64::NAN.to_bits()
+    } else if f == 0.0 {
+        0.0f64.to_bits()
+    } else {
+        f.to_bits()
+    }
+}
+
+/// Exact comparisons
+impl Item {
+    /// Compares both the data and the formatting of two items
+    ///
+    /// Unlike `==`, this also compares comments, whitespace, the representation of keys and values,
+    /// the order of keys and the kind of each table.  Only [spans][Item::span] are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    /// let a = "name = 'foo' # the name".parse::<DocumentMut>().unwrap();
+    /// let b = "name = \"foo\"".parse::<DocumentMut>().unwrap();
+    /// assert_eq!(a["name"], b["name"]);
+    /// assert!(!a["name"].exact_eq(&b["name"]));
+    /// # }
+    /// ```
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::None, Self::None) => true,
+            (Self::Value(a), Self::Value(b)) => a.exact_eq(b),
+            (Self::Table(a), Self::Table(b)) => a.exact_eq(b),
+            (Self::ArrayOfTables(a), Self::ArrayOfTables(b)) => a.exact_eq(b),
+            _ => false,
+        }
+    }
+}
+
+/// Exact comparisons
+impl Value {
+    /// Compares both the data and the formatting of two values, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => {
+                float_bits(*a.value()) == float_bits(*b.value())
+                    && a.as_repr() == b.as_repr()
+                    && a.decor() == b.decor()
+            }
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            (Self::Datetime(a), Self::Datetime(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => a.exact_eq(b),
+            (Self::InlineTable(a), Self::InlineTable(b)) => a.exact_eq(b),
+            _ => false,
+        }
+    }
+}
+
+/// Exact comparisons
+impl Table {
+    /// Compares both the data and the formatting of two tables, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        self.decor() == other.decor()
+            && self.trailing() == other.trailing()
+            && self.is_implicit() == other.is_implicit()
+            && self.is_dotted() == other.is_dotted()
+            && self.position() == other.position()
+            && items_exact_eq(&self.items, &other.items)
+    }
+}
+
+/// Exact comparisons
+impl InlineTable {
+    /// Compares both the data and the formatting of two tables, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        self.decor() == other.decor()
+            && self.preamble() == other.preamble()
+            && self.implicit == other.implicit
+            && self.is_dotted() == other.is_dotted()
+            && items_exact_eq(&self.items, &other.items)
+    }
+}
+
+/// Exact comparisons
+impl Array {
+    /// Compares both the data and the formatting of two arrays, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        self.decor() == other.decor()
+            && self.trailing() == other.trailing()
+            && self.trailing_comma() == other.trailing_comma()
+            && values_exact_eq(&self.values, &other.values)
+    }
+}
+
+/// Exact comparisons
+impl ArrayOfTables {
+    /// Compares both the data and the formatting of two arrays, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        values_exact_eq(&self.values, &other.values)
+    }
+}
+
+/// Exact comparisons
+impl DocumentMut {
+    /// Compares both the data and the formatting of two documents, see [`Item::exact_eq`]
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        self.trailing() == other.trailing() && self.root.exact_eq(&other.root)
+    }
+}
+
+fn items_exact_eq(a: &KeyValuePairs, b: &KeyValuePairs) -> bool {
+    a.len() == b.len()
+        && a.values()
+            .zip(b.values())
+            .all(|(a, b)| key_exact_eq(&a.key, &b.key) && a.value.exact_eq(&b.value))
+}
+
+fn values_exact_eq(a: &[Item], b: &[Item]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.exact_eq(b))
+}
+
+fn key_exact_eq(a: &Key, b: &Key) -> bool {
+    a.get() == b.get()
+        && a.as_repr() == b.as_repr()
+        && a.leaf_decor() == b.leaf_decor()
+        && a.dotted_decor() == b.dotted_decor()
+}
@@ -0,0 +1,418 @@
+use crate::table::TableKeyValue;
+use crate::{DocumentMut, InlineTable, Item, Table, Value};
+
+/// Options for converting tables between representations
+///
+/// See [`Table::make_inline`], [`Table::make_standard`] and [`DocumentMut::normalize_tables`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConvertOptions {
+    max_depth: Option<usize>,
+    max_inline_width: Option<usize>,
+}
+
+impl ConvertOptions {
+    /// Default options: no depth limit and no width limit
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// How many levels of nesting to keep as standard tables
+    ///
+    /// Tables nested deeper than this are made inline.  `None` means no limit.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// The widest a `key = { ... }` line may be for a table to be made inline, in characters
+    ///
+    /// Comments and indentation are not counted.  `None` means no limit.
+    pub fn max_inline_width(mut self, width: Option<usize>) -> Self {
+        self.max_inline_width = width;
+        self
+    }
+
+    fn is_standard_depth(&self, depth: usize) -> bool {
+        self.max_depth.map(|max| depth <= max).unwrap_or(true)
+    }
+}
+
+/// Representation conversions
+impl Table {
+    /// Converts the table or array of tables at `key` to an inline table or array of inline tables
+    ///
+    /// Nested tables are made inline as well.  Inline tables cannot hold comments, so any comments
+    /// within the converted tables are moved to before the `key = { ... }` line, except for the
+    /// comment after the header which stays on the same line.
+    ///
+    /// Returns `false`, leaving the table unchanged, when `key` is not a table or array of tables
+    /// or when the resulting line would be wider than [`ConvertOptions::max_inline_width`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{ConvertOptions, DocumentMut};
+    /// let mut doc = "[dep]\n# pinned\nversion = \"1.0\"\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// assert!(doc.as_table_mut().make_inline("dep", &ConvertOptions::new()));
+    /// assert_eq!(doc.to_string(), "# pinned\ndep = { version = \"1.0\" }\n");
+    /// # }
+    /// ```
+    pub fn make_inline(&mut self, key: &str, options: &ConvertOptions) -> bool {
+        self.items
+            .get_mut(key)
+            .map(|kv| inline(kv, options))
+            .unwrap_or(false)
+    }
+
+    /// Converts the inline table or dotted table at `key` to a standard table, e.g. `[key]`
+    ///
+    /// Inline tables and dotted tables nested within it are converted as well, down to
+    /// [`ConvertOptions::max_depth`] levels below this table.  Comments before and after the
+    /// `key = { ... }` line are carried over to the header.  Arrays of inline tables are left
+    /// as-is.
+    ///
+    /// Returns `false` when `key` is not a table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{ConvertOptions, DocumentMut};
+    /// let mut doc = "# pinned\ndep = { version = \"1.0\" } # stable\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// assert!(doc.as_table_mut().make_standard("dep", &ConvertOptions::new()));
+    /// assert_eq!(doc.to_string(), "# pinned\n[dep] # stable\nversion = \"1.0\"\n");
+    /// # }
+    /// ```
+    pub fn make_standard(&mut self, key: &str, options: &ConvertOptions) -> bool {
+        self.items
+            .get_mut(key)
+            .map(|kv| explode(kv, 1, options))
+            .unwrap_or(false)
+    }
+
+    /// Converts the standard or inline table at `key` to dotted keys, e.g. `key.a = 1`
+    ///
+    /// Nested standard and inline tables become dotted as well, while arrays of tables keep
+    /// their headers, and empty tables within are kept as `a = {}`.  Comments on the table headers
+    /// are moved before the first dotted key.
+    ///
+    /// Returns `false` when `key` is not a table or holds no values that could carry the dotted
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    /// let mut doc = "[package]\nname = \"foo\"\n\n[package.metadata]\nx = 1\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    ///
+    /// assert!(doc.as_table_mut().make_dotted("package"));
+    /// assert_eq!(doc.to_string(), "package.name = \"foo\"\npackage.metadata.x = 1\n");
+    /// # }
+    /// ```
+    pub fn make_dotted(&mut self, key: &str) -> bool {
+        self.items.get_mut(key).map(dot).unwrap_or(false)
+    }
+}
+
+/// Representation conversions
+impl DocumentMut {
+    /// Converts all tables to one consistent representation
+    ///
+    /// Tables within [`ConvertOptions::max_depth`] levels of the root, including dotted keys and
+    /// inline tables, become standard tables.  Deeper tables become inline tables, unless they
+    /// are wider than [`ConvertOptions::max_inline_width`], in which case they are standard tables
+    /// too.  Inline tables within arrays are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{ConvertOptions, DocumentMut};
+    /// let mut doc = r#"
+    /// dependencies.foo = "1.0"
+    /// dependencies.bar = { version = "1.0", features = ["std"] }
+    ///
+    /// [dependencies.baz]
+    /// version = "2.0"
+    /// "#.parse::<DocumentMut>().unwrap();
+    ///
+    /// doc.normalize_tables(&ConvertOptions::new().max_depth(Some(1)));
+    /// assert_eq!(doc.to_string(), r#"
+    /// [dependencies]
+    /// foo = "1.0"
+    /// bar = { version = "1.0", features = ["std"] }
+    /// baz = { version = "2.0" }
+    /// "#);
+    /// # }
+    /// ```
+    pub fn normalize_tables(&mut self, options: &ConvertOptions) {
+        normalize(self.as_table_mut(), 1, options);
+    }
+}
+
+fn normalize(table: &mut Table, depth: usize, options: &ConvertOptions) {
+    for kv in table.items.values_mut() {
+        if !options.is_standard_depth(depth) {
+            let is_inline = match &kv.value {
+                Item::Value(Value::InlineTable(_)) => fits(kv, options),
+                Item::Table(_) | Item::ArrayOfTables(_) => inline(kv, options),
+                _ => continue,
+            };
+            if is_inline {
+                continue;
+            }
+        }
+
+        explode_one(kv);
+        match &mut kv.value {
+            Item::Table(t) => normalize(t, depth + 1, options),
+            Item::ArrayOfTables(a) => {
+                for t in a.iter_mut() {
+                    normalize(t, depth + 1, options);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn inline(kv: &mut TableKeyValue, options: &ConvertOptions) -> bool {
+    let mut comments = Vec::new();
+    let suffix = match &kv.value {
+        Item::Table(t) => {
+            comments.extend(t.leading_comments().into_iter().map(String::from));
+            collect_body_comments(t, &mut comments);
+            t.trailing_comment().map(String::from)
+        }
+        Item::ArrayOfTables(a) => {
+            for t in a.iter() {
+                collect_comments(t, &mut comments);
+            }
+            None
+        }
+        _ => return false,
+    };
+    let prefix = crate::comment::set_leading_comments("", &comments);
+
+    let Ok(mut value) = kv.value.clone().into_value() else {
+        return false;
+    };
+    value.decor_mut().clear();
+    value.set_trailing_comment(suffix.as_deref());
+
+    let mut line = TableKeyValue::new(kv.key.clone(), Item::Value(value));
+    line.key.leaf_decor.clear();
+    line.key.dotted_decor.clear();
+    if !fits(&line, options) {
+        return false;
+    }
+    line.key.leaf_decor.set_prefix(prefix);
+    *kv = line;
+    true
+}
+
+// Whether a `key = value` line stays within `max_inline_width`
+fn fits(kv: &TableKeyValue, options: &ConvertOptions) -> bool {
+    let Some(max) = options.max_inline_width else {
+        return true;
+    };
+    let Some(value) = kv.value.as_value() else {
+        return true;
+    };
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    let width =
+        kv.key.display_repr().chars().count() + " = ".len() + value.to_string().chars().count();
+    width <= max
+}
+
+fn collect_comments(table: &Table, comments: &mut Vec<String>) {
+    comments.extend(table.leading_comments().into_iter().map(String::from));
+    comments.extend(table.trailing_comment().map(String::from));
+    collect_body_comments(table, comments);
+}
+
+fn collect_body_comments(table: &Table, comments: &mut Vec<String>) {
+    for kv in table.items.values() {
+        match &kv.value {
+            Item::None => {}
+            Item::Value(value) => {
+                comments.extend(kv.key.leading_comments().into_iter().map(String::from));
+                comments.extend(value.trailing_comment().map(String::from));
+            }
+            Item::Table(t) if t.is_dotted() => collect_body_comments(t, comments),
+            Item::Table(t) => collect_comments(t, comments),
+            Item::ArrayOfTables(a) => {
+                for t in a.iter() {
+                    collect_comments(t, comments);
+                }
+            }
+        }
+    }
+    comments.extend(table.dangling_comments().into_iter().map(String::from));
+}
+
+fn explode(kv: &mut TableKeyValue, depth: usize, options: &ConvertOptions) -> bool {
+    if !explode_one(kv) {
+        return false;
+    }
+    if options.is_standard_depth(depth + 1) {
+        let tables = match &mut kv.value {
+            Item::Table(t) => vec![t],
+            Item::ArrayOfTables(a) => a.iter_mut().collect(),
+            _ => unreachable!("`explode_one` only leaves tables"),
+        };
+        for table in tables {
+            for child in table.items.values_mut() {
+                if child.value.is_inline_table()
+                    || child
+                        .value
+                        .as_table()
+                        .map(Table::is_dotted)
+                        .unwrap_or(false)
+                {
+                    explode(child, depth + 1, options);
+                }
+            }
+        }
+    }
+    true
+}
+
+// Convert only `kv` to a standard table, leaving its children alone
+fn explode_one(kv: &mut TableKeyValue) -> bool {
+    match &mut kv.value {
+        Item::Value(Value::InlineTable(t)) => {
+            // The line's leading comments and blank lines now go before the header
+            let prefix = kv.key.leaf_decor.prefix().cloned();
+            let suffix = t.decor().suffix_comment().map(String::from);
+
+            let mut table = std::mem::take(t).into_table();
+            table.set_dotted(false);
+            if let Some(prefix) = prefix.filter(|p| !p.as_str().unwrap_or_default().is_empty()) {
+                table.decor.set_prefix(prefix);
+            }
+            table.set_trailing_comment(suffix.as_deref());
+            kv.value = Item::Table(table);
+        }
+        Item::Table(t) if t.is_dotted() => {
+            t.set_dotted(false);
+            // The first dotted line's leading comments and blank lines now go before the header
+            if let Some(first) = first_value_key(t) {
+                let prefix = first.leaf_decor.prefix().cloned();
+                first.leaf_decor.set_prefix("");
+                if let Some(prefix) = prefix {
+                    t.decor.set_prefix(prefix);
+                }
+            }
+        }
+        Item::Table(_) | Item::ArrayOfTables(_) => return true,
+        _ => return false,
+    }
+    kv.key.leaf_decor.clear();
+    kv.key.dotted_decor.clear();
+    true
+}
+
+fn dot(kv: &mut TableKeyValue) -> bool {
+    let has_values = match &kv.value {
+        Item::Table(t) => has_values(t.items.values()),
+        Item::Value(Value::InlineTable(t)) => has_values(t.items.values()),
+        _ => false,
+    };
+    if !has_values {
+        return false;
+    }
+
+    let mut comments = kv
+        .key
+        .leading_comments()
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    if let Item::Value(Value::InlineTable(t)) = &mut kv.value {
+        comments.extend(t.decor().suffix_comment().map(String::from));
+        kv.value = Item::Table(std::mem::take(t).into_table());
+    }
+    let Item::Table(table) = &mut kv.value else {
+        unreachable!("inline tables were converted above")
+    };
+    dot_table(table, &mut comments);
+    kv.key.leaf_decor.clear();
+    kv.key.dotted_decor.clear();
+
+    if let Some(first) = first_value_key(table) {
+        comments.extend(first.leading_comments().into_iter().map(String::from));
+        let prefix = crate::comment::set_leading_comments("", &comments);
+        first.leaf_decor.set_prefix(prefix);
+    }
+    true
+}
+
+fn dot_table(table: &mut Table, comments: &mut Vec<String>) {
+    if !table.is_dotted() {
+        comments.extend(table.leading_comments().into_iter().map(String::from));
+        comments.extend(table.trailing_comment().map(String::from));
+        comments.extend(table.dangling_comments().into_iter().map(String::from));
+    }
+    table.set_dotted(true);
+    table.set_implicit(false);
+    table.decor.clear();
+    table.set_trailing("");
+
+    for kv in table.items.values_mut() {
+        match &mut kv.value {
+            // Without keys to carry it, an empty table is kept as `key = {}`
+            Item::Table(t) if t.is_empty() => {
+                if !t.is_dotted() {
+                    comments.extend(t.leading_comments().into_iter().map(String::from));
+                    comments.extend(t.trailing_comment().map(String::from));
+                    comments.extend(t.dangling_comments().into_iter().map(String::from));
+                }
+                kv.value = Item::Value(Value::InlineTable(InlineTable::new()));
+                kv.key.leaf_decor.clear();
+                kv.key.dotted_decor.clear();
+            }
+            Item::Value(Value::InlineTable(t)) if !t.is_empty() => {
+                comments.extend(t.decor().suffix_comment().map(String::from));
+                kv.value = Item::Table(std::mem::take(t).into_table());
+            }
+            _ => {}
+        }
+        if let Item::Table(t) = &mut kv.value {
+            dot_table(t, comments);
+            kv.key.leaf_decor.clear();
+            kv.key.dotted_decor.clear();
+        }
+    }
+}
+
+fn first_value_key(table: &mut Table) -> Option<&mut crate::Key> {
+    for kv in table.items.values_mut() {
+        match &mut kv.value {
+            Item::Value(_) => return Some(&mut kv.key),
+            Item::Table(t) if t.is_dotted() => {
+                if let Some(key) = first_value_key(t) {
+                    return Some(key);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn has_values<'i>(mut items: impl Iterator<Item = &'i TableKeyValue>) -> bool {
+    items.any(|kv| match &kv.value {
+        Item::Value(Value::InlineTable(t)) => t.is_empty() || has_values(t.items.values()),
+        Item::Value(_) => true,
+        Item::Table(t) => t.is_empty() || has_values(t.items.values()),
+        _ => false,
+    })
+}
@@ -0,0 +1,366 @@
+use std::ops::Range;
+
+use crate::key::Key;
+use crate::table::KeyValuePairs;
+use crate::{ArrayOfTables, DocumentMut, ImDocument, Item, Table, Value};
+
+/// One step of a path through a document
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A key within a table
+    Key(String),
+    /// An index within an array or array of tables
+    Index(usize),
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        Self::Key(key.to_owned())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(key: String) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+/// A shared borrow of a node within a document
+#[derive(Copy, Clone, Debug)]
+pub enum Node<'d> {
+    /// A standard, implicit or dotted table, or an entry of an array of tables
+    Table(&'d Table),
+    /// An array of tables
+    ArrayOfTables(&'d ArrayOfTables),
+    /// A value, including arrays, inline tables and the values within them
+    Value(&'d Value),
+}
+
+impl<'d> Node<'d> {
+    /// The location within the original document
+    ///
+    /// This generally requires an [`ImDocument`][crate::ImDocument].
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::Table(t) => t.span(),
+            Self::ArrayOfTables(a) => a.span(),
+            Self::Value(v) => v.span(),
+        }
+    }
+
+    fn from_item(item: &'d Item) -> Option<Self> {
+        match item {
+            Item::None => None,
+            Item::Table(t) => Some(Self::Table(t)),
+            Item::ArrayOfTables(a) => Some(Self::ArrayOfTables(a)),
+            Item::Value(v) => Some(Self::Value(v)),
+        }
+    }
+
+    fn items(self) -> Option<&'d KeyValuePairs> {
+        match self {
+            Self::Table(t) => Some(&t.items),
+            Self::Value(Value::InlineTable(t)) => Some(&t.items),
+            _ => None,
+        }
+    }
+
+    fn child(self, segment: &PathSegment) -> Option<(Self, Option<&'d Key>)> {
+        match (self, segment) {
+            (Self::ArrayOfTables(a), PathSegment::Index(i)) => {
+                Some((Self::Table(a.get(*i)?), None))
+            }
+            (Self::Value(Value::Array(a)), PathSegment::Index(i)) => {
+                Some((Self::Value(a.get(*i)?), None))
+            }
+            (_, PathSegment::Key(key)) => {
+                let kv = self.items()?.get(key.as_str())?;
+                Some((Self::from_item(&kv.value)?, Some(&kv.key)))
+            }
+            _ => None,
+        }
+    }
+
+    fn children(self) -> Vec<(PathSegment, Self, Option<&'d Key>)> {
+        match self {
+            Self::ArrayOfTables(a) => a
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (PathSegment::Index(i), Self::Table(t), None))
+                .collect(),
+            Self::Value(Value::Array(a)) => a
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (PathSegment::Index(i), Self::Value(v), None))
+                .collect(),
+            _ => self
+                .items()
+                .into_iter()
+                .flatten()
+                .filter_map(|(key, kv)| {
+                    let node = Self::from_item(&kv.value)?;
+                    Some((
+                        PathSegment::Key(key.as_str().to_owned()),
+                        node,
+                        Some(&kv.key),
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// An exclusive borrow of a node within a document
+#[derive(Debug)]
+pub enum NodeMut<'d> {
+    /// A standard, implicit or dotted table, or an entry of an array of tables
+    Table(&'d mut Table),
+    /// An array of tables
+    ArrayOfTables(&'d mut ArrayOfTables),
+    /// A value, including arrays, inline tables and the values within them
+    Value(&'d mut Value),
+}
+
+impl<'d> NodeMut<'d> {
+    fn from_item(item: &'d mut Item) -> Option<Self> {
+        match item {
+            Item::None => None,
+            Item::Table(t) => Some(Self::Table(t)),
+            Item::ArrayOfTables(a) => Some(Self::ArrayOfTables(a)),
+            Item::Value(v) => Some(Self::Value(v)),
+        }
+    }
+
+    fn child(self, segment: &PathSegment) -> Option<Self> {
+        match (self, segment) {
+            (Self::ArrayOfTables(a), PathSegment::Index(i)) => a.get_mut(*i).map(Self::Table),
+            (Self::Value(Value::Array(a)), PathSegment::Index(i)) => a.get_mut(*i).map(Self::Value),
+            (Self::Table(t), PathSegment::Key(key)) => {
+                Self::from_item(&mut t.items.get_mut(key.as_str())?.value)
+            }
+            (Self::Value(Value::InlineTable(t)), PathSegment::Key(key)) => {
+                Self::from_item(&mut t.items.get_mut(key.as_str())?.value)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A position within a document that can move to the parent, children and siblings of the node it
+/// is on
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{DocumentMut, PathSegment};
+/// let doc = "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n".parse::<DocumentMut>().unwrap();
+///
+/// let name = doc.cursor().child("package").unwrap().child("name").unwrap();
+/// assert_eq!(name.key_path(), [PathSegment::from("package"), "name".into()]);
+///
+/// let version = name.next_sibling().unwrap();
+/// assert_eq!(version.key().unwrap().get(), "version");
+/// assert!(version.parent().unwrap().node().span().is_none());
+/// assert_eq!(version.ancestors().count(), 2);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cursor<'d> {
+    path: Vec<PathSegment>,
+    // The root, followed by the node at each segment of `path`
+    nodes: Vec<(Node<'d>, Option<&'d Key>)>,
+}
+
+impl<'d> Cursor<'d> {
+    /// A cursor on `root`
+    pub fn new(root: &'d Table) -> Self {
+        Self {
+            path: Vec::new(),
+            nodes: vec![(Node::Table(root), None)],
+        }
+    }
+
+    /// The node the cursor is on
+    pub fn node(&self) -> Node<'d> {
+        self.last().0
+    }
+
+    /// The key the node is stored under, if it is within a table
+    pub fn key(&self) -> Option<&'d Key> {
+        self.last().1
+    }
+
+    /// Keys and array indices leading to the node from the root table
+    pub fn key_path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// A cursor on the table, array or array of tables containing the node
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.clone();
+        parent.path.pop()?;
+        parent.nodes.pop();
+        Some(parent)
+    }
+
+    /// Cursors on each container of the node, innermost first and ending with the root table
+    pub fn ancestors(&self) -> impl Iterator<Item = Cursor<'d>> {
+        std::iter::successors(self.parent(), Self::parent)
+    }
+
+    /// A cursor on the child of the node at `segment`
+    pub fn child(&self, segment: impl Into<PathSegment>) -> Option<Self> {
+        let segment = segment.into();
+        let (node, key) = self.node().child(&segment)?;
+        Some(self.descend(segment, node, key))
+    }
+
+    /// Cursors on each child of the node, in order
+    pub fn children(&self) -> impl Iterator<Item = Cursor<'d>> {
+        let base = self.clone();
+        self.node()
+            .children()
+            .into_iter()
+            .map(move |(segment, node, key)| base.descend(segment, node, key))
+    }
+
+    /// A cursor on the node after this one within its parent
+    pub fn next_sibling(&self) -> Option<Self> {
+        self.sibling(1)
+    }
+
+    /// A cursor on the node before this one within its parent
+    pub fn prev_sibling(&self) -> Option<Self> {
+        self.sibling(-1)
+    }
+
+    fn last(&self) -> (Node<'d>, Option<&'d Key>) {
+        *self.nodes.last().expect("always contains the root")
+    }
+
+    fn descend(&self, segment: PathSegment, node: Node<'d>, key: Option<&'d Key>) -> Self {
+        let mut child = self.clone();
+        child.path.push(segment);
+        child.nodes.push((node, key));
+        child
+    }
+
+    // Looks the sibling up by position, skipping over table entries without a node
+    fn sibling(&self, step: isize) -> Option<Self> {
+        let parent = self.parent()?;
+        match self.path.last()? {
+            PathSegment::Index(index) => parent.child(index.checked_add_signed(step)?),
+            PathSegment::Key(key) => {
+                let items = parent.node().items()?;
+                let mut index = items.get_index_of(key.as_str())?;
+                loop {
+                    index = index.checked_add_signed(step)?;
+                    let (key, kv) = items.get_index(index)?;
+                    if let Some(node) = Node::from_item(&kv.value) {
+                        let segment = PathSegment::Key(key.as_str().to_owned());
+                        return Some(parent.descend(segment, node, Some(&kv.key)));
+                    }
+                }
+            }
+        }
+    }
+
+    fn contains(&self, offset: usize) -> bool {
+        let (node, key) = self.last();
+        [node.span(), key.and_then(|k| k.span())]
+            .into_iter()
+            .flatten()
+            .any(|span| span.contains(&offset))
+    }
+
+    // Implicit tables have no span, so every child is searched
+    fn deepest_at(&self, offset: usize) -> Option<Self> {
+        let mut deepest: Option<Self> = None;
+        for child in self.children() {
+            if let Some(found) = child.deepest_at(offset) {
+                if deepest
+                    .as_ref()
+                    .map(|d| d.path.len() < found.path.len())
+                    .unwrap_or(true)
+                {
+                    deepest = Some(found);
+                }
+            }
+        }
+        deepest.or_else(|| self.contains(offset).then(|| self.clone()))
+    }
+}
+
+/// Cursors
+impl DocumentMut {
+    /// A cursor on the root table
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor::new(self.as_table())
+    }
+
+    /// A cursor on the node at `path`, like one from [`Cursor::key_path`]
+    ///
+    /// A path isn't an identity kept by the node, it is looked up each time.  Entries of tables
+    /// are found by key, so a path keeps leading to the same node as other keys are inserted,
+    /// removed or reordered.  Array elements are found by index, so inserting or removing an
+    /// element of an array or array of tables changes where the paths through later elements
+    /// lead.
+    pub fn cursor_at(&self, path: &[PathSegment]) -> Option<Cursor<'_>> {
+        path.iter().try_fold(self.cursor(), |cursor, segment| {
+            cursor.child(segment.clone())
+        })
+    }
+
+    /// The node at `path`, see [`DocumentMut::cursor_at`]
+    pub fn node_mut(&mut self, path: &[PathSegment]) -> Option<NodeMut<'_>> {
+        path.iter()
+            .try_fold(NodeMut::Table(self.as_table_mut()), NodeMut::child)
+    }
+}
+
+/// Cursors
+impl<S> ImDocument<S> {
+    /// A cursor on the root table
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor::new(self.as_table())
+    }
+}
+
+/// Cursors
+impl<S: AsRef<str>> ImDocument<S> {
+    /// A cursor on the innermost node whose key or value contains the byte at `offset`
+    ///
+    /// Offsets outside of any key or value are on the root table.  Returns `None` if `offset` is
+    /// past the end of the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{ImDocument, PathSegment};
+    /// let raw = "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+    /// let doc = ImDocument::parse(raw).unwrap();
+    ///
+    /// let offset = raw.find("derive").unwrap();
+    /// let cursor = doc.node_at(offset).unwrap();
+    /// assert_eq!(
+    ///     cursor.key_path(),
+    ///     ["dependencies".into(), "serde".into(), "features".into(), PathSegment::Index(0)]
+    /// );
+    /// # }
+    /// ```
+    pub fn node_at(&self, offset: usize) -> Option<Cursor<'_>> {
+        if self.raw().len() <= offset {
+            return None;
+        }
+        let root = self.cursor();
+        Some(root.deepest_at(offset).unwrap_or(root))
+    }
+}
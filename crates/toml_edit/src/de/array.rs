@@ -57,14 +57,14 @@ impl<'de> serde::de::IntoDeserializer<'de, Error> for ArrayDeserializer {
 }
 
 impl crate::Array {
-    pub(crate) fn into_deserializer(self) -> ArrayDeserializer {
-        ArrayDeserializer::new(self.values, self.span)
+    pub(crate) fn into_deserializer(mut self) -> ArrayDeserializer {
+        ArrayDeserializer::new(std::mem::take(&mut self.values), self.span.take())
     }
 }
 
 impl crate::ArrayOfTables {
-    pub(crate) fn into_deserializer(self) -> ArrayDeserializer {
-        ArrayDeserializer::new(self.values, self.span)
+    pub(crate) fn into_deserializer(mut self) -> ArrayDeserializer {
+        ArrayDeserializer::new(std::mem::take(&mut self.values), self.span.take())
     }
 }
 
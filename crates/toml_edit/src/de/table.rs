@@ -98,19 +98,19 @@ impl<'de> IntoDeserializer<'de, Error> for TableDeserializer {
 }
 
 impl crate::Table {
-    pub(crate) fn into_deserializer(self) -> TableDeserializer {
+    pub(crate) fn into_deserializer(mut self) -> TableDeserializer {
         TableDeserializer {
             span: self.span(),
-            items: self.items,
+            items: std::mem::take(&mut self.items),
         }
     }
 }
 
 impl crate::InlineTable {
-    pub(crate) fn into_deserializer(self) -> TableDeserializer {
+    pub(crate) fn into_deserializer(mut self) -> TableDeserializer {
         TableDeserializer {
             span: self.span(),
-            items: self.items,
+            items: std::mem::take(&mut self.items),
         }
     }
 }
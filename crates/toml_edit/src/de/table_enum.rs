@@ -63,9 +63,9 @@ impl<'de> serde::de::VariantAccess<'de> for TableEnumDeserializer {
         V: serde::de::Visitor<'de>,
     {
         match self.value {
-            crate::Item::ArrayOfTables(values) => {
+            crate::Item::ArrayOfTables(mut values) => {
                 let values_span = values.span();
-                let tuple_values = values.values.into_iter().collect::<Vec<_>>();
+                let tuple_values = std::mem::take(&mut values.values);
 
                 if tuple_values.len() == len {
                     serde::de::Deserializer::deserialize_seq(
@@ -79,9 +79,9 @@ impl<'de> serde::de::VariantAccess<'de> for TableEnumDeserializer {
                     ))
                 }
             }
-            crate::Item::Value(crate::Value::Array(values)) => {
+            crate::Item::Value(crate::Value::Array(mut values)) => {
                 let values_span = values.span();
-                let tuple_values = values.values.into_iter().collect::<Vec<_>>();
+                let tuple_values = std::mem::take(&mut values.values);
 
                 if tuple_values.len() == len {
                     serde::de::Deserializer::deserialize_seq(
@@ -95,10 +95,9 @@ impl<'de> serde::de::VariantAccess<'de> for TableEnumDeserializer {
                     ))
                 }
             }
-            crate::Item::Table(values) => {
+            crate::Item::Table(mut values) => {
                 let values_span = values.span();
-                let tuple_values: Result<Vec<_>, _> = values
-                    .items
+                let tuple_values: Result<Vec<_>, _> = std::mem::take(&mut values.items)
                     .into_iter()
                     .enumerate()
                     .map(
@@ -129,10 +128,9 @@ impl<'de> serde::de::VariantAccess<'de> for TableEnumDeserializer {
                     ))
                 }
             }
-            crate::Item::Value(crate::Value::InlineTable(values)) => {
+            crate::Item::Value(crate::Value::InlineTable(mut values)) => {
                 let values_span = values.span();
-                let tuple_values: Result<Vec<_>, _> = values
-                    .items
+                let tuple_values: Result<Vec<_>, _> = std::mem::take(&mut values.items)
                     .into_iter()
                     .enumerate()
                     .map(
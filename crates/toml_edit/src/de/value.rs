@@ -216,10 +216,19 @@ impl<'de> serde::Deserializer<'de> for ValueDeserializer {
         })
     }
 
+    // Skipped values are dropped rather than visited, which doesn't recurse however deep they are
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        drop(self.input);
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string seq
         bytes byte_buf map unit
-        ignored_any unit_struct tuple_struct tuple identifier
+        unit_struct tuple_struct tuple identifier
     }
 }
 
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use crate::table::Iter;
-use crate::{Item, RawString, Table};
+use crate::{Item, RawString, Style, Table};
 
 /// Type representing a parsed TOML document
 #[derive(Debug, Clone)]
@@ -70,9 +70,16 @@ impl<S: AsRef<str>> ImDocument<S> {
     /// Allow editing of the [`DocumentMut`]
     pub fn into_mut(mut self) -> DocumentMut {
         self.despan();
+        // Most lines ending with `\r\n`
+        let lines = self.raw().matches('\n').count();
+        let crlf_lines = self.raw().matches("\r\n").count();
+        let is_crlf_source = 0 < lines && lines < 2 * crlf_lines;
+        let style = Style::infer(self.as_table(), is_crlf_source);
         DocumentMut {
             root: self.root,
             trailing: self.trailing,
+            style,
+            is_crlf_source,
         }
     }
 }
@@ -111,6 +118,9 @@ pub struct DocumentMut {
     pub(crate) root: Item,
     // Trailing comments and whitespaces
     pub(crate) trailing: RawString,
+    pub(crate) style: Style,
+    // Whether the document was parsed from a source with `\r\n` line endings
+    is_crlf_source: bool,
 }
 
 impl DocumentMut {
@@ -155,6 +165,49 @@ impl DocumentMut {
     pub fn trailing(&self) -> &RawString {
         &self.trailing
     }
+
+    /// The formatting of nodes that have none of their own
+    ///
+    /// For parsed documents, this is the style inferred from the source, see
+    /// [`DocumentMut::infer_style`].  Otherwise, it is the default [`Style`] unless set.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Changes the formatting of nodes that have none of their own, overriding the inferred one
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// The style the nodes of this document are written in
+    ///
+    /// Each setting goes with the most common choice among the nodes, or the default when
+    /// there is none.  Line endings are those of the source the document was parsed from.
+    ///
+    /// Parsing a document sets its [`DocumentMut::style`] to this, so new nodes are written the
+    /// way the source is.  After editing, this can differ from the style in use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{table, value, DocumentMut};
+    /// let mut doc = "[a]\r\n  x = {y = 'z'}\r\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// doc["a"]["w"] = value("v");
+    /// doc["b"] = table();
+    /// doc["b"]["c"] = value(1);
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "[a]\r\n  x = {y = 'z'}\r\n  w = 'v'\r\n\r\n[b]\r\n  c = 1\r\n"
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn infer_style(&self) -> Style {
+        Style::infer(self.as_table(), self.is_crlf_source)
+    }
 }
 
 impl Default for DocumentMut {
@@ -162,6 +215,8 @@ impl Default for DocumentMut {
         Self {
             root: Item::Table(Table::with_pos(Some(0))),
             trailing: Default::default(),
+            style: Default::default(),
+            is_crlf_source: false,
         }
     }
 }
@@ -1,25 +1,74 @@
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter, Result, Write};
+use std::ops::Range;
 
 use toml_datetime::Datetime;
 
 use crate::inline_table::DEFAULT_INLINE_KEY_DECOR;
 use crate::key::Key;
-use crate::repr::{Formatted, Repr, ValueRepr};
+use crate::raw_string::RawString;
+use crate::repr::{
+    Decor, FloatStyle, Formatted, IntegerStyle, Radix, Repr, StringStyle, ValueRepr,
+};
+use crate::source_map::NodeKind;
 use crate::table::{DEFAULT_KEY_DECOR, DEFAULT_KEY_PATH_DECOR, DEFAULT_TABLE_DECOR};
 use crate::value::{
     DEFAULT_LEADING_VALUE_DECOR, DEFAULT_TRAILING_VALUE_DECOR, DEFAULT_VALUE_DECOR,
 };
 use crate::DocumentMut;
-use crate::{Array, InlineTable, Item, Table, Value};
+use crate::{Array, InlineTable, Item, Style, Table, Value};
+
+/// Destination for encoding
+///
+/// Besides writing, this allows tracking which text was carried over unchanged from the source a
+/// document was parsed from.
+pub(crate) trait Output: Write {
+    /// Write `text`, an unmodified copy of `origin` within the source, as `rendered`
+    fn write_origin(&mut self, _origin: Range<usize>, _text: &str, rendered: &str) -> Result {
+        self.write_str(rendered)
+    }
+
+    /// The length of the output so far, when tracking where nodes are written
+    fn offset(&self) -> Option<usize> {
+        None
+    }
+
+    /// Descend to `segment` in the path of the nodes being written
+    fn push_path(&mut self, _segment: Segment<'_>) {}
+
+    /// Undo the last [`Output::push_path`]
+    fn pop_path(&mut self) {}
+
+    /// Note that the node at the current path was written from `start` up to the current offset
+    fn record(&mut self, _kind: NodeKind, _start: usize) {}
+}
+
+/// Part of the path to a node, see [`Output::push_path`]
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Segment<'k> {
+    Key(&'k str),
+    Index(usize),
+}
 
-pub(crate) fn encode_key(this: &Key, buf: &mut dyn Write, input: Option<&str>) -> Result {
+fn record(buf: &mut dyn Output, kind: NodeKind, start: Option<usize>) {
+    if let Some(start) = start {
+        buf.record(kind, start);
+    }
+}
+
+impl Output for Formatter<'_> {}
+
+impl Output for String {}
+
+pub(crate) fn encode_key(this: &Key, buf: &mut dyn Output, input: Option<&str>) -> Result {
     if let Some(input) = input {
         let repr = this
             .as_repr()
             .map(Cow::Borrowed)
             .unwrap_or_else(|| Cow::Owned(this.default_repr()));
         repr.encode(buf, input)?;
+    } else if let Some((origin, raw)) = this.as_repr().and_then(Repr::origin) {
+        buf.write_origin(origin, raw, raw)?;
     } else {
         let repr = this.display_repr();
         write!(buf, "{}", repr)?;
@@ -30,11 +79,12 @@ pub(crate) fn encode_key(this: &Key, buf: &mut dyn Write, input: Option<&str>) -
 
 fn encode_key_path(
     this: &[Key],
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     default_decor: (&str, &str),
 ) -> Result {
     let leaf_decor = this.last().expect("always at least one key").leaf_decor();
+    let mut start = None;
     for (i, key) in this.iter().enumerate() {
         let dotted_decor = key.dotted_decor();
 
@@ -43,6 +93,7 @@ fn encode_key_path(
 
         if first {
             leaf_decor.prefix_encode(buf, input, default_decor.0)?;
+            start = buf.offset();
         } else {
             write!(buf, ".")?;
             dotted_decor.prefix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.0)?;
@@ -51,6 +102,7 @@ fn encode_key_path(
         encode_key(key, buf, input)?;
 
         if last {
+            record(buf, NodeKind::Key, start);
             leaf_decor.suffix_encode(buf, input, default_decor.1)?;
         } else {
             dotted_decor.suffix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.1)?;
@@ -61,11 +113,12 @@ fn encode_key_path(
 
 pub(crate) fn encode_key_path_ref(
     this: &[&Key],
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     default_decor: (&str, &str),
 ) -> Result {
     let leaf_decor = this.last().expect("always at least one key").leaf_decor();
+    let mut start = None;
     for (i, key) in this.iter().enumerate() {
         let dotted_decor = key.dotted_decor();
 
@@ -74,6 +127,7 @@ pub(crate) fn encode_key_path_ref(
 
         if first {
             leaf_decor.prefix_encode(buf, input, default_decor.0)?;
+            start = buf.offset();
         } else {
             write!(buf, ".")?;
             dotted_decor.prefix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.0)?;
@@ -82,6 +136,7 @@ pub(crate) fn encode_key_path_ref(
         encode_key(key, buf, input)?;
 
         if last {
+            record(buf, NodeKind::Key, start);
             leaf_decor.suffix_encode(buf, input, default_decor.1)?;
         } else {
             dotted_decor.suffix_encode(buf, input, DEFAULT_KEY_PATH_DECOR.1)?;
@@ -92,12 +147,23 @@ pub(crate) fn encode_key_path_ref(
 
 pub(crate) fn encode_formatted<T: ValueRepr>(
     this: &Formatted<T>,
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     default_decor: (&str, &str),
+) -> Result {
+    encode_formatted_value(this, buf, input, default_decor, false)
+}
+
+fn encode_formatted_value<T: ValueRepr>(
+    this: &Formatted<T>,
+    buf: &mut dyn Output,
+    input: Option<&str>,
+    default_decor: (&str, &str),
+    comma: bool,
 ) -> Result {
     let decor = this.decor();
     decor.prefix_encode(buf, input, default_decor.0)?;
+    let start = buf.offset();
 
     if let Some(input) = input {
         let repr = this
@@ -105,161 +171,481 @@ pub(crate) fn encode_formatted<T: ValueRepr>(
             .map(Cow::Borrowed)
             .unwrap_or_else(|| Cow::Owned(this.default_repr()));
         repr.encode(buf, input)?;
+    } else if let Some((origin, raw)) = this.as_repr().and_then(Repr::origin) {
+        buf.write_origin(origin, raw, raw)?;
     } else {
         let repr = this.display_repr();
         write!(buf, "{}", repr)?;
     };
+    record(buf, NodeKind::Value, start);
 
-    decor.suffix_encode(buf, input, default_decor.1)?;
-    Ok(())
+    encode_value_suffix(decor, buf, input, default_decor.1, comma)
 }
 
-pub(crate) fn encode_array(
-    this: &Array,
-    buf: &mut dyn Write,
+// Writes the suffix of a value, with the comma that follows it when `comma` is set
+//
+// An array value can keep the comment on its line in its own suffix, see
+// `Array::set_trailing_comment`.  Nothing can follow a comment on the same line, so the comma is
+// written before it.
+fn encode_value_suffix(
+    decor: &Decor,
+    buf: &mut dyn Output,
     input: Option<&str>,
-    default_decor: (&str, &str),
+    default: &str,
+    comma: bool,
 ) -> Result {
-    let decor = this.decor();
-    decor.prefix_encode(buf, input, default_decor.0)?;
-    write!(buf, "[")?;
-
-    for (i, elem) in this.iter().enumerate() {
-        let inner_decor;
-        if i == 0 {
-            inner_decor = DEFAULT_LEADING_VALUE_DECOR;
-        } else {
-            inner_decor = DEFAULT_VALUE_DECOR;
-            write!(buf, ",")?;
-        }
-        encode_value(elem, buf, input, inner_decor)?;
+    if !comma {
+        return decor.suffix_encode(buf, input, default);
     }
-    if this.trailing_comma() && !this.is_empty() {
-        write!(buf, ",")?;
+    let suffix = decor
+        .suffix()
+        .and_then(RawString::as_str)
+        .unwrap_or(default);
+    if let Some(at) = comma_before_comment(suffix) {
+        write!(buf, "{},{}", &suffix[..at], &suffix[at..])
+    } else {
+        decor.suffix_encode(buf, input, default)?;
+        write!(buf, ",")
     }
+}
 
-    this.trailing().encode_with_default(buf, input, "")?;
-    write!(buf, "]")?;
-    decor.suffix_encode(buf, input, default_decor.1)?;
+// Where the comma goes in a suffix that ends with a comment on the value's line
+fn comma_before_comment(suffix: &str) -> Option<usize> {
+    if suffix.contains('\n') {
+        return None;
+    }
+    let start = suffix.find('#')?;
+    Some(suffix[..start].trim_end_matches([' ', '\t']).len())
+}
 
-    Ok(())
+fn ends_with_comment(value: &Value) -> bool {
+    value
+        .decor()
+        .suffix()
+        .and_then(RawString::as_str)
+        .and_then(comma_before_comment)
+        .is_some()
+}
+
+fn starts_with_newline(raw: Option<&RawString>) -> bool {
+    raw.and_then(RawString::as_str)
+        .map(|raw| {
+            raw.trim_start_matches([' ', '\t'])
+                .starts_with(['\r', '\n'])
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn encode_array(
+    this: &Array,
+    buf: &mut dyn Output,
+    input: Option<&str>,
+    default_decor: (&str, &str),
+    style: &Style,
+) -> Result {
+    encode_steps(
+        vec![Step::Array(this, default_decor, false)],
+        buf,
+        input,
+        style,
+    )
 }
 
 pub(crate) fn encode_table(
     this: &InlineTable,
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     default_decor: (&str, &str),
+    style: &Style,
 ) -> Result {
-    let decor = this.decor();
-    decor.prefix_encode(buf, input, default_decor.0)?;
-    write!(buf, "{{")?;
-    this.preamble().encode_with_default(buf, input, "")?;
-
-    let children = this.get_values();
-    let len = children.len();
-    for (i, (key_path, value)) in children.into_iter().enumerate() {
-        if i != 0 {
-            write!(buf, ",")?;
-        }
-        let inner_decor = if i == len - 1 {
-            DEFAULT_TRAILING_VALUE_DECOR
-        } else {
-            DEFAULT_VALUE_DECOR
-        };
-        encode_key_path_ref(&key_path, buf, input, DEFAULT_INLINE_KEY_DECOR)?;
-        write!(buf, "=")?;
-        encode_value(value, buf, input, inner_decor)?;
-    }
+    encode_steps(
+        vec![Step::InlineTable(this, default_decor, false)],
+        buf,
+        input,
+        style,
+    )
+}
+
+pub(crate) fn encode_value(
+    this: &Value,
+    buf: &mut dyn Output,
+    input: Option<&str>,
+    default_decor: (&str, &str),
+    style: &Style,
+) -> Result {
+    encode_steps(
+        vec![Step::Value(this, default_decor, false)],
+        buf,
+        input,
+        style,
+    )
+}
 
-    write!(buf, "}}")?;
-    decor.suffix_encode(buf, input, default_decor.1)?;
+// Part of writing a value
+//
+// Nested arrays and inline tables are written through a stack of steps rather than by recursion,
+// so the depth of a value is only limited by memory.
+//
+// The `bool` of a value is whether the comma after it is written with its suffix.
+enum Step<'v, 'd> {
+    Value(&'v Value, (&'d str, &'d str), bool),
+    Array(&'v Array, (&'d str, &'d str), bool),
+    InlineTable(&'v InlineTable, (&'d str, &'d str), bool),
+    Comma,
+    Newline,
+    PushIndex(usize),
+    PopIndex,
+    // The keys before the `=` of an entry of an inline table
+    Keys(Vec<&'v Key>, (&'d str, &'d str)),
+    PopKeys(usize),
+    // The end of an array, from after its values
+    CloseArray {
+        array: &'v Array,
+        default_decor: (&'d str, &'d str),
+        start: Option<usize>,
+        is_padded: bool,
+        // Whether the last value ends with a comment, which the comma was written before
+        ends_with_comment: bool,
+        comma: bool,
+    },
+    // The end of an inline table, from after its values
+    CloseInlineTable {
+        table: &'v InlineTable,
+        default_decor: (&'d str, &'d str),
+        start: Option<usize>,
+        comma: bool,
+    },
+}
 
+fn encode_steps<'v, 'd>(
+    mut steps: Vec<Step<'v, 'd>>,
+    buf: &mut dyn Output,
+    input: Option<&str>,
+    style: &Style,
+) -> Result {
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Value(value, default_decor, comma) => match value {
+                Value::Array(array) => steps.push(Step::Array(array, default_decor, comma)),
+                Value::InlineTable(table) => {
+                    steps.push(Step::InlineTable(table, default_decor, comma));
+                }
+                Value::String(_)
+                | Value::Integer(_)
+                | Value::Float(_)
+                | Value::Boolean(_)
+                | Value::Datetime(_) => {
+                    encode_scalar(value, buf, input, default_decor, style, comma)?;
+                }
+            },
+            Step::Array(array, default_decor, comma) => {
+                array.decor().prefix_encode(buf, input, default_decor.0)?;
+                let start = buf.offset();
+                write!(buf, "[")?;
+
+                // Only pad arrays whose layout was never set
+                let is_padded = style.has_array_padding()
+                    && array
+                        .get(0)
+                        .map(|v| v.decor().prefix().is_none())
+                        .unwrap_or(false);
+                let elems = array.iter().collect::<Vec<_>>();
+                let ends_with_comment = elems
+                    .iter()
+                    .map(|v| ends_with_comment(v))
+                    .collect::<Vec<_>>();
+                steps.push(Step::CloseArray {
+                    array,
+                    default_decor,
+                    start,
+                    is_padded,
+                    ends_with_comment: ends_with_comment.last().copied().unwrap_or(false),
+                    comma,
+                });
+                let len = elems.len();
+                for (i, elem) in elems.into_iter().enumerate().rev() {
+                    let inner_decor = if i == 0 && !is_padded {
+                        DEFAULT_LEADING_VALUE_DECOR
+                    } else {
+                        DEFAULT_VALUE_DECOR
+                    };
+                    let inner_comma =
+                        ends_with_comment[i] && (i + 1 < len || array.trailing_comma());
+                    steps.push(Step::PopIndex);
+                    steps.push(Step::Value(elem, inner_decor, inner_comma));
+                    steps.push(Step::PushIndex(i));
+                    if i != 0 {
+                        if !ends_with_comment[i - 1] {
+                            steps.push(Step::Comma);
+                        } else if !starts_with_newline(elem.decor().prefix()) {
+                            steps.push(Step::Newline);
+                        }
+                    }
+                }
+            }
+            Step::InlineTable(table, default_decor, comma) => {
+                table.decor().prefix_encode(buf, input, default_decor.0)?;
+                let start = buf.offset();
+                write!(buf, "{{")?;
+                table.preamble().encode_with_default(buf, input, "")?;
+
+                steps.push(Step::CloseInlineTable {
+                    table,
+                    default_decor,
+                    start,
+                    comma,
+                });
+                let children = table.get_values();
+                let len = children.len();
+                for (i, (key_path, value)) in children.into_iter().enumerate().rev() {
+                    let mut inner_decor = if i == len - 1 {
+                        DEFAULT_TRAILING_VALUE_DECOR
+                    } else {
+                        DEFAULT_VALUE_DECOR
+                    };
+                    let mut key_decor = DEFAULT_INLINE_KEY_DECOR;
+                    if !style.has_inline_table_padding() {
+                        if i == 0 {
+                            key_decor.0 = "";
+                        }
+                        if i == len - 1 {
+                            inner_decor.1 = "";
+                        }
+                    }
+                    steps.push(Step::PopKeys(key_path.len()));
+                    steps.push(Step::Value(value, inner_decor, false));
+                    steps.push(Step::Keys(key_path, key_decor));
+                    if i != 0 {
+                        steps.push(Step::Comma);
+                    }
+                }
+            }
+            Step::Comma => write!(buf, ",")?,
+            Step::Newline => writeln!(buf)?,
+            Step::PushIndex(i) => buf.push_path(Segment::Index(i)),
+            Step::PopIndex => buf.pop_path(),
+            Step::Keys(key_path, key_decor) => {
+                push_key_path(buf, &key_path);
+                encode_key_path_ref(&key_path, buf, input, key_decor)?;
+                write!(buf, "=")?;
+            }
+            Step::PopKeys(len) => {
+                for _ in 0..len {
+                    buf.pop_path();
+                }
+            }
+            Step::CloseArray {
+                array,
+                default_decor,
+                start,
+                is_padded,
+                ends_with_comment,
+                comma,
+            } => {
+                if ends_with_comment {
+                    if !starts_with_newline(Some(array.trailing())) {
+                        writeln!(buf)?;
+                    }
+                } else if array.trailing_comma() && !array.is_empty() {
+                    write!(buf, ",")?;
+                }
+                if is_padded && !ends_with_comment && array.trailing().as_str() == Some("") {
+                    write!(buf, " ")?;
+                } else {
+                    array.trailing().encode_with_default(buf, input, "")?;
+                }
+                write!(buf, "]")?;
+                record(buf, NodeKind::Value, start);
+                encode_value_suffix(array.decor(), buf, input, default_decor.1, comma)?;
+            }
+            Step::CloseInlineTable {
+                table,
+                default_decor,
+                start,
+                comma,
+            } => {
+                write!(buf, "}}")?;
+                record(buf, NodeKind::Value, start);
+                encode_value_suffix(table.decor(), buf, input, default_decor.1, comma)?;
+            }
+        }
+    }
     Ok(())
 }
 
-pub(crate) fn encode_value(
+fn encode_scalar(
     this: &Value,
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     default_decor: (&str, &str),
+    style: &Style,
+    comma: bool,
 ) -> Result {
     match this {
-        Value::String(repr) => encode_formatted(repr, buf, input, default_decor),
-        Value::Integer(repr) => encode_formatted(repr, buf, input, default_decor),
-        Value::Float(repr) => encode_formatted(repr, buf, input, default_decor),
-        Value::Boolean(repr) => encode_formatted(repr, buf, input, default_decor),
-        Value::Datetime(repr) => encode_formatted(repr, buf, input, default_decor),
-        Value::Array(array) => encode_array(array, buf, input, default_decor),
-        Value::InlineTable(table) => encode_table(table, buf, input, default_decor),
+        Value::String(repr) if repr.as_repr().is_none() && style.prefers_literal_strings() => {
+            match to_string_repr_with_style(repr.value(), StringStyle::Literal) {
+                Some(literal_repr) => {
+                    let mut literal = repr.clone();
+                    literal.set_repr_unchecked(literal_repr);
+                    encode_formatted_value(&literal, buf, input, default_decor, comma)
+                }
+                None => encode_formatted_value(repr, buf, input, default_decor, comma),
+            }
+        }
+        Value::String(repr) => encode_formatted_value(repr, buf, input, default_decor, comma),
+        Value::Integer(repr) => encode_formatted_value(repr, buf, input, default_decor, comma),
+        Value::Float(repr) => encode_formatted_value(repr, buf, input, default_decor, comma),
+        Value::Boolean(repr) => encode_formatted_value(repr, buf, input, default_decor, comma),
+        Value::Datetime(repr) => encode_formatted_value(repr, buf, input, default_decor, comma),
+        Value::Array(_) | Value::InlineTable(_) => unreachable!("written through steps"),
     }
 }
 
 impl Display for DocumentMut {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let mut path = Vec::new();
-        let mut last_position = 0;
-        let mut tables = Vec::new();
-        visit_nested_tables(self.as_table(), &mut path, false, &mut |t, p, is_array| {
-            if let Some(pos) = t.position() {
-                last_position = pos;
+        if self.style().is_crlf() {
+            encode_document(self, &mut Crlf(f))
+        } else {
+            encode_document(self, f)
+        }
+    }
+}
+
+/// Writes newlines as `\r\n`
+///
+/// Decor has its `\r` dropped by `RawString::encode`, but the reprs of values parsed from a
+/// CRLF source, like multi-line strings, keep their `\r\n` which is written as is.
+pub(crate) struct Crlf<'b>(pub(crate) &'b mut dyn Output);
+
+impl<'b> Write for Crlf<'b> {
+    fn write_str(&mut self, s: &str) -> Result {
+        let mut lines = s.split('\n').peekable();
+        while let Some(line) = lines.next() {
+            if lines.peek().is_some() {
+                self.0.write_str(line.strip_suffix('\r').unwrap_or(line))?;
+                self.0.write_str("\r\n")?;
+            } else {
+                self.0.write_str(line)?;
             }
-            tables.push((last_position, t, p.clone(), is_array));
-            Ok(())
-        })
-        .unwrap();
+        }
+        Ok(())
+    }
+}
+
+impl<'b> Output for Crlf<'b> {
+    fn write_origin(&mut self, origin: Range<usize>, text: &str, rendered: &str) -> Result {
+        let rendered = rendered.replace("\r\n", "\n").replace('\n', "\r\n");
+        self.0.write_origin(origin, text, &rendered)
+    }
+
+    fn offset(&self) -> Option<usize> {
+        self.0.offset()
+    }
+
+    fn push_path(&mut self, segment: Segment<'_>) {
+        self.0.push_path(segment);
+    }
+
+    fn pop_path(&mut self) {
+        self.0.pop_path();
+    }
+
+    fn record(&mut self, kind: NodeKind, start: usize) {
+        self.0.record(kind, start);
+    }
+}
 
-        tables.sort_by_key(|&(id, _, _, _)| id);
-        let mut first_table = true;
-        for (_, table, path, is_array) in tables {
-            visit_table(f, None, table, &path, is_array, &mut first_table)?;
+pub(crate) fn encode_document(this: &DocumentMut, buf: &mut dyn Output) -> Result {
+    let mut last_position = 0;
+    let mut tables = Vec::new();
+    visit_nested_tables(this.as_table(), |t, p, s, is_array| {
+        if let Some(pos) = t.position() {
+            last_position = pos;
+        }
+        tables.push((last_position, t, p, s, is_array));
+    });
+
+    tables.sort_by_key(|&(id, _, _, _, _)| id);
+    let mut first_table = true;
+    for (_, table, path, segments, is_array) in tables {
+        for segment in &segments {
+            buf.push_path(*segment);
+        }
+        visit_table(
+            buf,
+            None,
+            table,
+            &path,
+            is_array,
+            &mut first_table,
+            this.style(),
+        )?;
+        for _ in &segments {
+            buf.pop_path();
         }
-        self.trailing().encode_with_default(f, None, "")
     }
+    this.trailing().encode_with_default(buf, None, "")
 }
 
-fn visit_nested_tables<'t, F>(
-    table: &'t Table,
-    path: &mut Vec<Key>,
-    is_array_of_tables: bool,
-    callback: &mut F,
-) -> Result
+// Walks `table` and the tables under it in document order, through a stack so any depth of
+// tables can be written
+fn visit_nested_tables<'t, F>(table: &'t Table, mut callback: F)
 where
-    F: FnMut(&'t Table, &Vec<Key>, bool) -> Result,
+    F: FnMut(&'t Table, Vec<Key>, Vec<Segment<'t>>, bool),
 {
-    if !table.is_dotted() {
-        callback(table, path, is_array_of_tables)?;
-    }
-
-    for kv in table.items.values() {
-        match kv.value {
-            Item::Table(ref t) => {
-                let key = kv.key.clone();
-                path.push(key);
-                visit_nested_tables(t, path, false, callback)?;
-                path.pop();
-            }
-            Item::ArrayOfTables(ref a) => {
-                for t in a.iter() {
-                    let key = kv.key.clone();
-                    path.push(key);
-                    visit_nested_tables(t, path, true, callback)?;
-                    path.pop();
+    let mut pending = vec![(table, Vec::new(), Vec::new(), false)];
+    while let Some((table, path, segments, is_array_of_tables)) = pending.pop() {
+        let mut children = Vec::new();
+        for kv in table.items.values() {
+            match kv.value {
+                Item::Table(ref t) => {
+                    let mut path = path.clone();
+                    path.push(kv.key.clone());
+                    let mut segments = segments.clone();
+                    segments.push(Segment::Key(kv.key.get()));
+                    children.push((t, path, segments, false));
+                }
+                Item::ArrayOfTables(ref a) => {
+                    for (i, t) in a.iter().enumerate() {
+                        let mut path = path.clone();
+                        path.push(kv.key.clone());
+                        let mut segments = segments.clone();
+                        segments.push(Segment::Key(kv.key.get()));
+                        segments.push(Segment::Index(i));
+                        children.push((t, path, segments, true));
+                    }
                 }
+                _ => {}
             }
-            _ => {}
+        }
+        pending.extend(children.into_iter().rev());
+
+        if !table.is_dotted() {
+            callback(table, path, segments, is_array_of_tables);
         }
     }
-    Ok(())
+}
+
+fn push_key_path(buf: &mut dyn Output, key_path: &[&Key]) {
+    for key in key_path {
+        buf.push_path(Segment::Key(key.get()));
+    }
+}
+
+fn pop_key_path(buf: &mut dyn Output, key_path: &[&Key]) {
+    for _ in key_path {
+        buf.pop_path();
+    }
 }
 
 fn visit_table(
-    buf: &mut dyn Write,
+    buf: &mut dyn Output,
     input: Option<&str>,
     table: &Table,
     path: &[Key],
     is_array_of_tables: bool,
     first_table: &mut bool,
+    style: &Style,
 ) -> Result {
     let children = table.get_values();
     // We are intentionally hiding implicit tables without any tables nested under them (ie
@@ -279,39 +665,44 @@ fn visit_table(
             *first_table = false;
         }
     } else if is_array_of_tables {
-        let default_decor = if *first_table {
-            *first_table = false;
-            ("", DEFAULT_TABLE_DECOR.1)
-        } else {
-            DEFAULT_TABLE_DECOR
-        };
-        table.decor.prefix_encode(buf, input, default_decor.0)?;
+        let default_prefix = style.header_prefix(path.len(), *first_table);
+        *first_table = false;
+        table.decor.prefix_encode(buf, input, &default_prefix)?;
+        let start = buf.offset();
         write!(buf, "[[")?;
         encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
         write!(buf, "]]")?;
-        table.decor.suffix_encode(buf, input, default_decor.1)?;
+        record(buf, NodeKind::Header, start);
+        table
+            .decor
+            .suffix_encode(buf, input, DEFAULT_TABLE_DECOR.1)?;
         writeln!(buf)?;
     } else if is_visible_std_table {
-        let default_decor = if *first_table {
-            *first_table = false;
-            ("", DEFAULT_TABLE_DECOR.1)
-        } else {
-            DEFAULT_TABLE_DECOR
-        };
-        table.decor.prefix_encode(buf, input, default_decor.0)?;
+        let default_prefix = style.header_prefix(path.len(), *first_table);
+        *first_table = false;
+        table.decor.prefix_encode(buf, input, &default_prefix)?;
+        let start = buf.offset();
         write!(buf, "[")?;
         encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
         write!(buf, "]")?;
-        table.decor.suffix_encode(buf, input, default_decor.1)?;
+        record(buf, NodeKind::Header, start);
+        table
+            .decor
+            .suffix_encode(buf, input, DEFAULT_TABLE_DECOR.1)?;
         writeln!(buf)?;
     }
     // print table body
+    let indent = style.body_indent_at(path.len());
+    let key_decor = (indent.as_str(), DEFAULT_KEY_DECOR.1);
     for (key_path, value) in children {
-        encode_key_path_ref(&key_path, buf, input, DEFAULT_KEY_DECOR)?;
+        push_key_path(buf, &key_path);
+        encode_key_path_ref(&key_path, buf, input, key_decor)?;
         write!(buf, "=")?;
-        encode_value(value, buf, input, DEFAULT_VALUE_DECOR)?;
+        encode_value(value, buf, input, DEFAULT_VALUE_DECOR, style)?;
+        pop_key_path(buf, &key_path);
         writeln!(buf)?;
     }
+    table.trailing.encode_with_default(buf, input, "")?;
     Ok(())
 }
 
@@ -323,7 +714,7 @@ impl ValueRepr for String {
 
 pub(crate) fn to_string_repr(
     value: &str,
-    style: Option<StringStyle>,
+    style: Option<StringLayout>,
     literal: Option<bool>,
 ) -> Repr {
     let (style, literal) = match (style, literal) {
@@ -345,9 +736,9 @@ pub(crate) fn to_string_repr(
                 '\u{8}' => output.push_str("\\b"),
                 '\u{9}' => output.push_str("\\t"),
                 '\u{a}' => match style {
-                    StringStyle::NewlineTriple => output.push('\n'),
-                    StringStyle::OnelineSingle => output.push_str("\\n"),
-                    StringStyle::OnelineTriple => unreachable!(),
+                    StringLayout::NewlineTriple => output.push('\n'),
+                    StringLayout::OnelineSingle => output.push_str("\\n"),
+                    StringLayout::OnelineTriple => unreachable!(),
                 },
                 '\u{c}' => output.push_str("\\f"),
                 '\u{d}' => output.push_str("\\r"),
@@ -365,14 +756,51 @@ pub(crate) fn to_string_repr(
     Repr::new_unchecked(output)
 }
 
+/// The representation of `value` in `style`, if it can be written that way
+pub(crate) fn to_string_repr_with_style(value: &str, style: StringStyle) -> Option<Repr> {
+    let (layout, literal) = match style {
+        StringStyle::Basic => (StringLayout::OnelineSingle, false),
+        StringStyle::MultilineBasic => (StringLayout::NewlineTriple, false),
+        StringStyle::Literal => {
+            if value
+                .chars()
+                .any(|c| c == '\'' || !is_literal_char(c, false))
+            {
+                return None;
+            }
+            (StringLayout::OnelineSingle, true)
+        }
+        StringStyle::MultilineLiteral => {
+            // `'''` would end the string early and a trailing `'` would run into the closing quotes
+            if value.contains("'''")
+                || value.ends_with('\'')
+                || value.chars().any(|c| !is_literal_char(c, true))
+            {
+                return None;
+            }
+            (StringLayout::NewlineTriple, true)
+        }
+    };
+    Some(to_string_repr(value, Some(layout), Some(literal)))
+}
+
+// Control characters can't be escaped in literal strings
+fn is_literal_char(c: char, multiline: bool) -> bool {
+    match c {
+        '\t' => true,
+        '\n' => multiline,
+        c => '\u{1f}' < c && c != '\u{7f}',
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub(crate) enum StringStyle {
+pub(crate) enum StringLayout {
     NewlineTriple,
     OnelineTriple,
     OnelineSingle,
 }
 
-impl StringStyle {
+impl StringLayout {
     fn literal_start(self) -> &'static str {
         match self {
             Self::NewlineTriple => "'''\n",
@@ -409,7 +837,7 @@ impl StringStyle {
     }
 }
 
-fn infer_style(value: &str) -> (StringStyle, bool) {
+fn infer_style(value: &str) -> (StringLayout, bool) {
     // We need to determine:
     // - if we are a "multi-line" pretty (if there are \n)
     // - if ['''] appears if multi or ['] if single
@@ -417,7 +845,7 @@ fn infer_style(value: &str) -> (StringStyle, bool) {
     //
     // Doing it any other way would require multiple passes
     // to determine if a pretty string works or not.
-    let mut ty = StringStyle::OnelineSingle;
+    let mut ty = StringLayout::OnelineSingle;
     // found consecutive single quotes
     let mut max_found_singles = 0;
     let mut found_singles = 0;
@@ -442,7 +870,7 @@ fn infer_style(value: &str) -> (StringStyle, bool) {
                 '\\' => {
                     prefer_literal = true;
                 }
-                '\n' => ty = StringStyle::NewlineTriple,
+                '\n' => ty = StringLayout::NewlineTriple,
                 // Escape codes are needed if any ascii control
                 // characters are present, including \b \f \r.
                 c if c <= '\u{1f}' || c == '\u{7f}' => can_be_pretty = false,
@@ -452,7 +880,7 @@ fn infer_style(value: &str) -> (StringStyle, bool) {
             // the string cannot be represented as pretty,
             // still check if it should be multiline
             if ch == '\n' {
-                ty = StringStyle::NewlineTriple;
+                ty = StringLayout::NewlineTriple;
             }
         }
     }
@@ -464,16 +892,16 @@ fn infer_style(value: &str) -> (StringStyle, bool) {
         can_be_pretty = false;
     }
     if !can_be_pretty {
-        debug_assert!(ty != StringStyle::OnelineTriple);
+        debug_assert!(ty != StringLayout::OnelineTriple);
         return (ty, false);
     }
     if found_singles > max_found_singles {
         max_found_singles = found_singles;
     }
     debug_assert!(max_found_singles < 3);
-    if ty == StringStyle::OnelineSingle && max_found_singles >= 1 {
+    if ty == StringLayout::OnelineSingle && max_found_singles >= 1 {
         // no newlines, but must use ''' because it has ' in it
-        ty = StringStyle::OnelineTriple;
+        ty = StringLayout::OnelineTriple;
     }
     (ty, true)
 }
@@ -507,6 +935,63 @@ fn to_f64_repr(f: f64) -> Repr {
     Repr::new_unchecked(repr)
 }
 
+pub(crate) fn to_i64_repr_with_style(value: i64, style: IntegerStyle) -> Repr {
+    let radix = if value < 0 {
+        Radix::Decimal
+    } else {
+        style.radix()
+    };
+    let (prefix, digits) = match (radix, style.is_uppercase()) {
+        (Radix::Decimal, _) => ("", value.unsigned_abs().to_string()),
+        (Radix::Hexadecimal, false) => ("0x", format!("{value:x}")),
+        (Radix::Hexadecimal, true) => ("0x", format!("{value:X}")),
+        (Radix::Octal, _) => ("0o", format!("{value:o}")),
+        (Radix::Binary, _) => ("0b", format!("{value:b}")),
+    };
+
+    let mut repr = String::new();
+    if value < 0 {
+        repr.push('-');
+    }
+    repr.push_str(prefix);
+    for (i, digit) in digits.chars().enumerate() {
+        let remaining = digits.len() - i;
+        if let (true, Some(size)) = (i != 0, style.group_size()) {
+            if remaining % size == 0 {
+                repr.push('_');
+            }
+        }
+        repr.push(digit);
+    }
+    Repr::new_unchecked(repr)
+}
+
+pub(crate) fn to_f64_repr_with_style(value: f64, style: FloatStyle) -> Repr {
+    if !value.is_finite() {
+        return to_f64_repr(value);
+    }
+
+    let repr = if style.has_exponent() {
+        format!("{value:e}")
+    } else {
+        to_f64_repr(value).as_raw().as_str().unwrap().to_owned()
+    };
+    let Some(precision) = style.min_precision() else {
+        return Repr::new_unchecked(repr);
+    };
+    let (mantissa, exponent) = repr.split_at(repr.find('e').unwrap_or(repr.len()));
+    let fraction = mantissa.split_once('.').map(|(_, f)| f.len()).unwrap_or(0);
+    let mut padded = mantissa.to_owned();
+    if fraction < precision {
+        if fraction == 0 {
+            padded.push('.');
+        }
+        padded.push_str(&"0".repeat(precision - fraction));
+    }
+    padded.push_str(exponent);
+    Repr::new_unchecked(padded)
+}
+
 impl ValueRepr for bool {
     fn to_repr(&self) -> Repr {
         Repr::new_unchecked(self.to_string())
@@ -1,6 +1,8 @@
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result};
 
+use crate::StringStyle;
+
 /// Type representing a TOML parse error
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct TomlError {
@@ -157,6 +159,57 @@ impl StdError for TomlError {
     }
 }
 
+/// Type representing a failed edit of a [`DocumentMut`][crate::DocumentMut] or of its values
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EditError {
+    /// An empty key path was given
+    EmptyPath,
+    /// Nothing exists at the key path
+    NotFound(Vec<String>),
+    /// Something already exists at the key path
+    AlreadyExists(Vec<String>),
+    /// The item at the key path cannot hold other items
+    NotATable(Vec<String>),
+    /// The destination key path is within the item being moved
+    MoveIntoSelf(Vec<String>),
+    /// The string can't be written in the requested style
+    UnrepresentableString(StringStyle),
+}
+
+impl EditError {
+    pub(crate) fn path(path: &[&str]) -> Vec<String> {
+        path.iter().map(|k| (*k).to_owned()).collect()
+    }
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::EmptyPath => "key path is empty".fmt(f),
+            Self::NotFound(path) => write!(f, "no item at `{}`", path.join(".")),
+            Self::AlreadyExists(path) => {
+                write!(f, "an item already exists at `{}`", path.join("."))
+            }
+            Self::NotATable(path) => write!(f, "item at `{}` is not a table", path.join(".")),
+            Self::MoveIntoSelf(path) => {
+                write!(f, "cannot move an item into itself at `{}`", path.join("."))
+            }
+            Self::UnrepresentableString(style) => {
+                let style = match style {
+                    StringStyle::Basic => "basic",
+                    StringStyle::Literal => "literal",
+                    StringStyle::MultilineBasic => "multi-line basic",
+                    StringStyle::MultilineLiteral => "multi-line literal",
+                };
+                write!(f, "string cannot be written as a {style} string")
+            }
+        }
+    }
+}
+
+impl StdError for EditError {}
+
 fn translate_position(input: &[u8], index: usize) -> (usize, usize) {
     if input.is_empty() {
         return (0, index);
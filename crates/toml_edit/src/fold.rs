@@ -0,0 +1,324 @@
+#![allow(missing_docs)]
+
+//! Document tree traversal to rewrite an owned document tree.
+//!
+//! Each method of the [`Fold`] trait is a hook that can be overridden to customize how the
+//! corresponding type of node is rewritten.  By default, every method rebuilds the node from the
+//! result of folding each of its children, by invoking the right folder method for each of them.
+//!
+//! Unlike [`VisitMut`](crate::visit_mut::VisitMut), a hook can return a different kind of node,
+//! like turning an [`Item::Table`] into an [`Item::Value`].
+//!
+//! ```
+//! # use toml_edit::{Item, ArrayOfTables, Table, Value};
+//!
+//! pub trait Fold {
+//!     /* ... */
+//!
+//!     fn fold_item(&mut self, i: Item) -> Item {
+//!         fold_item(self, i)
+//!     }
+//!
+//!     /* ... */
+//!     # fn fold_value(&mut self, i: Value) -> Value;
+//!     # fn fold_table(&mut self, i: Table) -> Table;
+//!     # fn fold_array_of_tables(&mut self, i: ArrayOfTables) -> ArrayOfTables;
+//! }
+//!
+//! pub fn fold_item<F>(f: &mut F, node: Item) -> Item
+//! where
+//!     F: Fold + ?Sized,
+//! {
+//!     match node {
+//!         Item::None => Item::None,
+//!         Item::Value(value) => Item::Value(f.fold_value(value)),
+//!         Item::Table(table) => Item::Table(f.fold_table(table)),
+//!         Item::ArrayOfTables(array) => Item::ArrayOfTables(f.fold_array_of_tables(array)),
+//!     }
+//! }
+//! ```
+//!
+//! The API is modeled after [`syn::fold`](https://docs.rs/syn/1/syn/fold).
+//!
+//! # Examples
+//!
+//! This folder turns every array of tables into an array of inline tables and spells out every
+//! version requirement as a table.
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! # use toml_edit::*;
+//! use toml_edit::fold::*;
+//!
+//! struct Inline;
+//!
+//! impl Fold for Inline {
+//!     fn fold_table_kv(&mut self, key: Key, node: Item) -> (Key, Item) {
+//!         // Fold the children first, so nested arrays are inlined from the inside out
+//!         let (mut key, node) = fold_table_kv(self, key, node);
+//!         let node = match node {
+//!             Item::ArrayOfTables(array) => {
+//!                 // Drop the header formatting
+//!                 key.fmt();
+//!                 Item::Value(Value::Array(array.into_array()))
+//!             }
+//!             Item::Value(Value::String(version)) if key.get() == "version" => {
+//!                 let mut table = InlineTable::new();
+//!                 table.insert("req", Value::String(version));
+//!                 table.fmt();
+//!                 Item::Value(Value::InlineTable(table))
+//!             }
+//!             node => node,
+//!         };
+//!         (key, node)
+//!     }
+//! }
+//!
+//! let input = r#"
+//! version = "1"
+//!
+//! [[bin]]
+//! name = "a"
+//!
+//! [[bin]]
+//! name = "b"
+//! "#;
+//!
+//! let document: DocumentMut = input.parse().unwrap();
+//! let document = Inline.fold_document(document);
+//!
+//! let output = r#"
+//! version = { req = "1" }
+//! bin = [{ name = "a" }, { name = "b" }]
+//! "#;
+//!
+//! assert_eq!(document.to_string(), output);
+//! # }
+//! # }
+//! ```
+
+use crate::table::TableKeyValue;
+use crate::{
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, Formatted, InlineTable, Item, Key, Table,
+    Value,
+};
+
+/// Document tree traversal to rewrite an owned document tree.
+///
+/// See the [module documentation](self) for details.
+pub trait Fold {
+    fn fold_document(&mut self, node: DocumentMut) -> DocumentMut {
+        fold_document(self, node)
+    }
+
+    fn fold_item(&mut self, node: Item) -> Item {
+        fold_item(self, node)
+    }
+
+    fn fold_table(&mut self, node: Table) -> Table {
+        fold_table(self, node)
+    }
+
+    fn fold_inline_table(&mut self, node: InlineTable) -> InlineTable {
+        fold_inline_table(self, node)
+    }
+
+    /// Called for each entry of a [`Table`]
+    ///
+    /// Returning [`Item::None`] removes the entry, like returning `None` from
+    /// [`Fold::fold_inline_table_kv`].  If the key changes to one already in the table, the entry
+    /// replaces the earlier one.
+    fn fold_table_kv(&mut self, key: Key, node: Item) -> (Key, Item) {
+        fold_table_kv(self, key, node)
+    }
+
+    /// Called for each entry of an [`InlineTable`]
+    ///
+    /// Returning `None` removes the entry, like returning [`Item::None`] from
+    /// [`Fold::fold_table_kv`].  If the key changes to one already in the table, the entry
+    /// replaces the earlier one.
+    fn fold_inline_table_kv(&mut self, key: Key, node: Value) -> Option<(Key, Value)> {
+        fold_inline_table_kv(self, key, node)
+    }
+
+    fn fold_key(&mut self, node: Key) -> Key {
+        fold_key(self, node)
+    }
+
+    fn fold_array(&mut self, node: Array) -> Array {
+        fold_array(self, node)
+    }
+
+    fn fold_array_of_tables(&mut self, node: ArrayOfTables) -> ArrayOfTables {
+        fold_array_of_tables(self, node)
+    }
+
+    fn fold_value(&mut self, node: Value) -> Value {
+        fold_value(self, node)
+    }
+
+    fn fold_boolean(&mut self, node: Formatted<bool>) -> Formatted<bool> {
+        fold_boolean(self, node)
+    }
+
+    fn fold_datetime(&mut self, node: Formatted<Datetime>) -> Formatted<Datetime> {
+        fold_datetime(self, node)
+    }
+
+    fn fold_float(&mut self, node: Formatted<f64>) -> Formatted<f64> {
+        fold_float(self, node)
+    }
+
+    fn fold_integer(&mut self, node: Formatted<i64>) -> Formatted<i64> {
+        fold_integer(self, node)
+    }
+
+    fn fold_string(&mut self, node: Formatted<String>) -> Formatted<String> {
+        fold_string(self, node)
+    }
+
+    /// Called for the decor of each key, value and table
+    fn fold_decor(&mut self, node: Decor) -> Decor {
+        fold_decor(self, node)
+    }
+}
+
+pub fn fold_document<F>(f: &mut F, mut node: DocumentMut) -> DocumentMut
+where
+    F: Fold + ?Sized,
+{
+    let root = std::mem::take(node.as_table_mut());
+    *node.as_table_mut() = f.fold_table(root);
+    node
+}
+
+pub fn fold_item<F>(f: &mut F, node: Item) -> Item
+where
+    F: Fold + ?Sized,
+{
+    match node {
+        Item::None => Item::None,
+        Item::Value(value) => Item::Value(f.fold_value(value)),
+        Item::Table(table) => Item::Table(f.fold_table(table)),
+        Item::ArrayOfTables(array) => Item::ArrayOfTables(f.fold_array_of_tables(array)),
+    }
+}
+
+pub fn fold_table<F>(f: &mut F, mut node: Table) -> Table
+where
+    F: Fold + ?Sized,
+{
+    *node.decor_mut() = f.fold_decor(std::mem::take(node.decor_mut()));
+    let items = std::mem::take(&mut node.items);
+    for (_, kv) in items {
+        let (key, value) = f.fold_table_kv(kv.key, kv.value);
+        if !value.is_none() {
+            node.items
+                .insert(key.get().into(), TableKeyValue::new(key, value));
+        }
+    }
+    node
+}
+
+pub fn fold_inline_table<F>(f: &mut F, mut node: InlineTable) -> InlineTable
+where
+    F: Fold + ?Sized,
+{
+    let items = std::mem::take(&mut node.items);
+    for (_, kv) in items {
+        let value = match kv.value {
+            Item::Value(value) => value,
+            // Only values are allowed in inline tables
+            _ => continue,
+        };
+        if let Some((key, value)) = f.fold_inline_table_kv(kv.key, value) {
+            node.items.insert(
+                key.get().into(),
+                TableKeyValue::new(key, Item::Value(value)),
+            );
+        }
+    }
+    node
+}
+
+pub fn fold_table_kv<F>(f: &mut F, key: Key, node: Item) -> (Key, Item)
+where
+    F: Fold + ?Sized,
+{
+    (f.fold_key(key), f.fold_item(node))
+}
+
+pub fn fold_inline_table_kv<F>(f: &mut F, key: Key, node: Value) -> Option<(Key, Value)>
+where
+    F: Fold + ?Sized,
+{
+    Some((f.fold_key(key), f.fold_value(node)))
+}
+
+pub fn fold_key<F>(f: &mut F, mut node: Key) -> Key
+where
+    F: Fold + ?Sized,
+{
+    *node.leaf_decor_mut() = f.fold_decor(std::mem::take(node.leaf_decor_mut()));
+    *node.dotted_decor_mut() = f.fold_decor(std::mem::take(node.dotted_decor_mut()));
+    node
+}
+
+pub fn fold_array<F>(f: &mut F, mut node: Array) -> Array
+where
+    F: Fold + ?Sized,
+{
+    node.values = std::mem::take(&mut node.values)
+        .into_iter()
+        .filter_map(|item| item.into_value().ok())
+        .map(|value| Item::Value(f.fold_value(value)))
+        .collect();
+    node
+}
+
+pub fn fold_array_of_tables<F>(f: &mut F, mut node: ArrayOfTables) -> ArrayOfTables
+where
+    F: Fold + ?Sized,
+{
+    node.values = std::mem::take(&mut node.values)
+        .into_iter()
+        .filter_map(|item| item.into_table().ok())
+        .map(|table| Item::Table(f.fold_table(table)))
+        .collect();
+    node
+}
+
+pub fn fold_value<F>(f: &mut F, mut node: Value) -> Value
+where
+    F: Fold + ?Sized,
+{
+    *node.decor_mut() = f.fold_decor(std::mem::take(node.decor_mut()));
+    match node {
+        Value::String(s) => Value::String(f.fold_string(s)),
+        Value::Integer(i) => Value::Integer(f.fold_integer(i)),
+        Value::Float(v) => Value::Float(f.fold_float(v)),
+        Value::Boolean(b) => Value::Boolean(f.fold_boolean(b)),
+        Value::Datetime(dt) => Value::Datetime(f.fold_datetime(dt)),
+        Value::Array(array) => Value::Array(f.fold_array(array)),
+        Value::InlineTable(table) => Value::InlineTable(f.fold_inline_table(table)),
+    }
+}
+
+macro_rules! identity_fold {
+    ($name: ident, $t: ty) => {
+        fn $name<F>(_f: &mut F, node: $t) -> $t
+        where
+            F: Fold + ?Sized,
+        {
+            node
+        }
+    };
+}
+
+identity_fold!(fold_boolean, Formatted<bool>);
+identity_fold!(fold_datetime, Formatted<Datetime>);
+identity_fold!(fold_float, Formatted<f64>);
+identity_fold!(fold_integer, Formatted<i64>);
+identity_fold!(fold_string, Formatted<String>);
+identity_fold!(fold_decor, Decor);
@@ -0,0 +1,565 @@
+use crate::comment::{has_blank_line, raw};
+use crate::visit_mut::VisitMut;
+use crate::{Array, ConvertOptions, DocumentMut, InlineTable, Item, Style, Table, Value};
+
+/// Reformats a [`DocumentMut`] in place, keeping its comments
+///
+/// Only whitespace, comments and key quoting are rewritten; keys, values and the order of tables
+/// are left alone.  Blank lines within a table's body are collapsed to at most one.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// use toml_edit::{ArrayWrap, DocumentMut, Formatter};
+///
+/// let mut doc = r#"
+///   name="toml"   # the name
+/// features=[ "a",  "b" ]
+/// [dependencies]   # runtime only
+/// serde={version="1"}
+/// "#.parse::<DocumentMut>().unwrap();
+///
+/// Formatter::new()
+///     .indent_width(2)
+///     .array_wrap(ArrayWrap::Always)
+///     .format(&mut doc);
+///
+/// assert_eq!(doc.to_string(), r#"name = "toml" # the name
+/// features = [
+///   "a",
+///   "b",
+/// ]
+///
+/// [dependencies] # runtime only
+/// serde = { version = "1" }
+/// "#);
+/// # }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Formatter {
+    indent_width: usize,
+    array_wrap: ArrayWrap,
+    trailing_comma: bool,
+    blank_lines_between_tables: usize,
+    space_around_eq: bool,
+    preserve_comments: bool,
+    key_quoting: KeyQuoting,
+    max_width: Option<usize>,
+    expand_inline_tables: bool,
+    align_entries: bool,
+    align_comments: bool,
+}
+
+/// When a [`Formatter`] puts each value of an array on its own line
+///
+/// Arrays holding comments are always put on multiple lines.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ArrayWrap {
+    /// Keep arrays on one line or on multiple lines, as they are
+    ///
+    /// Arrays on one line that would go past [`Formatter::max_width`] are put on multiple lines.
+    #[default]
+    Preserve,
+    /// Put every array on one line
+    Never,
+    /// Put every non-empty array on multiple lines
+    Always,
+}
+
+/// How a [`Formatter`] writes keys
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyQuoting {
+    /// Keep keys as they are written
+    #[default]
+    Preserve,
+    /// Only quote keys that can't be written bare
+    Minimal,
+}
+
+impl Formatter {
+    /// Default options: 4-space indents, arrays wrapped as they are, trailing commas, a blank line
+    /// between tables, spaces around `=`, comments kept and keys quoted as they are
+    pub fn new() -> Self {
+        Self {
+            indent_width: 4,
+            array_wrap: ArrayWrap::Preserve,
+            trailing_comma: true,
+            blank_lines_between_tables: 1,
+            space_around_eq: true,
+            preserve_comments: true,
+            key_quoting: KeyQuoting::Preserve,
+            max_width: None,
+            expand_inline_tables: false,
+            align_entries: false,
+            align_comments: false,
+        }
+    }
+
+    /// How many spaces to indent each level of a multi-line array by
+    pub fn indent_width(mut self, width: usize) -> Self {
+        self.indent_width = width;
+        self
+    }
+
+    /// When to put each value of an array on its own line
+    pub fn array_wrap(mut self, wrap: ArrayWrap) -> Self {
+        self.array_wrap = wrap;
+        self
+    }
+
+    /// Whether to end multi-line arrays with a comma
+    ///
+    /// Arrays on one line never get a trailing comma.
+    pub fn trailing_comma(mut self, yes: bool) -> Self {
+        self.trailing_comma = yes;
+        self
+    }
+
+    /// How many blank lines to put before each table header
+    ///
+    /// The first header of a document without any top-level key/value pairs gets none.
+    pub fn blank_lines_between_tables(mut self, count: usize) -> Self {
+        self.blank_lines_between_tables = count;
+        self
+    }
+
+    /// Whether to put spaces around the `=` of key/value pairs
+    pub fn space_around_eq(mut self, yes: bool) -> Self {
+        self.space_around_eq = yes;
+        self
+    }
+
+    /// Whether to keep comments, or remove all of them
+    ///
+    /// Kept comments are written with a single space after the `#`.
+    pub fn preserve_comments(mut self, yes: bool) -> Self {
+        self.preserve_comments = yes;
+        self
+    }
+
+    /// How to quote keys
+    pub fn key_quoting(mut self, quoting: KeyQuoting) -> Self {
+        self.key_quoting = quoting;
+        self
+    }
+
+    /// The column lines should end by, in characters
+    ///
+    /// Arrays going past it are put on multiple lines, unless arrays are never wrapped, see
+    /// [`ArrayWrap`].  Comments are not counted.  `None` means no limit.
+    pub fn max_width(mut self, width: Option<usize>) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Whether to convert inline tables going past [`Formatter::max_width`] to standard tables
+    ///
+    /// Only inline tables directly under a header, or at the top of the document, are converted,
+    /// see [`Table::make_standard`].
+    pub fn expand_inline_tables(mut self, yes: bool) -> Self {
+        self.expand_inline_tables = yes;
+        self
+    }
+
+    /// Whether to pad keys so the `=` of each block of key/value lines line up
+    ///
+    /// A blank line ends a block, see [`Table::align_entries`].
+    pub fn align_entries(mut self, yes: bool) -> Self {
+        self.align_entries = yes;
+        self
+    }
+
+    /// Whether to pad values so the comments after them line up within each block
+    ///
+    /// See [`Table::align_comments`].
+    pub fn align_comments(mut self, yes: bool) -> Self {
+        self.align_comments = yes;
+        self
+    }
+
+    /// Reformats `doc` in place
+    ///
+    /// The [`Style`] of `doc` is reset too, only keeping its line endings.
+    pub fn format(&self, doc: &mut DocumentMut) {
+        let style = Style::new().crlf(doc.style().is_crlf());
+        doc.set_style(style);
+
+        let mut visitor = Format::new(self);
+        visitor.visit_document_mut(doc);
+
+        strip_first_header(doc);
+
+        let trailing = raw(Some(doc.trailing())).unwrap_or_default();
+        let mut comments = self.comment_lines(trailing);
+        if !comments.is_empty() && has_blank_line(trailing) {
+            comments.insert(0, '\n');
+        }
+        doc.set_trailing(comments);
+    }
+
+    // The comments of a prefix-like raw string
+    fn comments(&self, raw: &str) -> Vec<String> {
+        if !self.preserve_comments {
+            return Vec::new();
+        }
+        crate::comment::leading_comments(raw)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    // The comment lines of a prefix-like raw string, each ending with a newline
+    fn comment_lines(&self, raw: &str) -> String {
+        crate::comment::set_leading_comments("", &self.comments(raw))
+    }
+
+    // The comment on the first line of a suffix-like raw string, with a space before it
+    fn line_comment(&self, raw: &str) -> String {
+        let comment = crate::comment::trailing_comment(raw).filter(|_| self.preserve_comments);
+        crate::comment::set_trailing_comment("", comment)
+    }
+
+    fn eq_decor(&self) -> &'static str {
+        if self.space_around_eq {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    // The width of ` = ` or `=`
+    fn eq_width(&self) -> usize {
+        1 + 2 * self.eq_decor().len()
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Format<'f> {
+    options: &'f Formatter,
+    // Number of enclosing multi-line arrays
+    level: usize,
+    // Whether no key/value pair of the current table's body has been formatted yet
+    first_line: bool,
+    // The column the next value starts at, if it is known
+    column: Option<usize>,
+}
+
+impl<'f> Format<'f> {
+    fn new(options: &'f Formatter) -> Self {
+        Self {
+            options,
+            level: 0,
+            first_line: true,
+            column: None,
+        }
+    }
+
+    // Key/value pairs of a table, including those of dotted tables which are on the same lines
+    //
+    // `indent` is the width of the dotted keys before the table's keys
+    fn format_body(&mut self, node: &mut Table, indent: usize) {
+        let options = self.options;
+        for (mut key, item) in node.iter_mut() {
+            // Comments are kept in the key's decor, which formatting the key clears
+            let prefix = raw(key.leaf_decor().prefix()).unwrap_or_default();
+            let mut lines = options.comment_lines(prefix);
+            if !self.first_line && has_blank_line(prefix) {
+                lines.insert(0, '\n');
+            }
+            if options.key_quoting == KeyQuoting::Minimal {
+                key.fmt();
+            }
+            key.dotted_decor_mut().clear();
+            let key_width = indent + key.display_repr().chars().count();
+            match item {
+                Item::Value(value) => {
+                    let leaf = key.leaf_decor_mut();
+                    leaf.set_prefix(lines);
+                    leaf.set_suffix(options.eq_decor());
+                    self.first_line = false;
+
+                    let suffix =
+                        options.line_comment(raw(value.decor().suffix()).unwrap_or_default());
+                    value.decor_mut().set_prefix(options.eq_decor());
+                    value.decor_mut().set_suffix(suffix);
+                    self.column = Some(key_width + options.eq_width());
+                    self.visit_value_mut(value);
+                }
+                Item::Table(table) if table.is_dotted() => {
+                    key.leaf_decor_mut().clear();
+                    self.format_body(table, key_width + ".".len());
+                }
+                Item::Table(_) | Item::ArrayOfTables(_) => {
+                    key.leaf_decor_mut().clear();
+                }
+                Item::None => {}
+            }
+        }
+    }
+
+    fn format_inline_body(&mut self, node: &mut InlineTable, last: bool) {
+        let options = self.options;
+        let len = node.len();
+        for (i, (mut key, value)) in node.iter_mut().enumerate() {
+            let last = last && i + 1 == len;
+            if options.key_quoting == KeyQuoting::Minimal {
+                key.fmt();
+            }
+            key.dotted_decor_mut().clear();
+            match value {
+                Value::InlineTable(table) if table.is_dotted() => {
+                    key.leaf_decor_mut().clear();
+                    self.format_inline_body(table, last);
+                }
+                value => {
+                    if options.space_around_eq {
+                        key.leaf_decor_mut().clear();
+                        value.decor_mut().clear();
+                    } else {
+                        *key.leaf_decor_mut() = crate::Decor::new(" ", "");
+                        *value.decor_mut() = crate::Decor::new("", if last { " " } else { "" });
+                    }
+                    self.column = None;
+                    self.visit_value_mut(value);
+                }
+            }
+        }
+    }
+
+    fn is_multiline(&self, node: &Array, has_comments: bool, column: Option<usize>) -> bool {
+        match self.options.array_wrap {
+            _ if has_comments => true,
+            ArrayWrap::Never => false,
+            ArrayWrap::Always => !node.is_empty(),
+            ArrayWrap::Preserve => {
+                let has_newline = |raw: Option<&str>| raw.unwrap_or_default().contains('\n');
+                has_newline(raw(Some(node.trailing())))
+                    || node.iter().any(|value| {
+                        has_newline(raw(value.decor().prefix()))
+                            || has_newline(raw(value.decor().suffix()))
+                    })
+                    || self.is_too_wide(column, || Value::Array(node.clone()))
+            }
+        }
+    }
+
+    // Whether a value starting at `column` would go past `max_width` when put on one line
+    fn is_too_wide(&self, column: Option<usize>, value: impl FnOnce() -> Value) -> bool {
+        let (Some(max), Some(column)) = (self.options.max_width, column) else {
+            return false;
+        };
+        let options = Formatter {
+            array_wrap: ArrayWrap::Never,
+            preserve_comments: false,
+            max_width: None,
+            expand_inline_tables: false,
+            ..self.options.clone()
+        };
+        let mut value = value();
+        value.decor_mut().clear();
+        Format::new(&options).visit_value_mut(&mut value);
+        max < column + value.to_string().chars().count()
+    }
+
+    // Convert the inline tables going past `max_width` to standard tables
+    fn expand_inline_tables(&self, node: &mut Table) {
+        if !self.options.expand_inline_tables {
+            return;
+        }
+        let wide = node
+            .items
+            .values()
+            .filter(|kv| {
+                kv.value.is_inline_table() && {
+                    let column = kv.key.display_repr().chars().count() + self.options.eq_width();
+                    let value = kv.value.as_value().expect("inline table");
+                    self.is_too_wide(Some(column), || value.clone())
+                }
+            })
+            .map(|kv| kv.key.get().to_owned())
+            .collect::<Vec<_>>();
+        let options = ConvertOptions::new().max_depth(Some(1));
+        for key in wide {
+            node.make_standard(&key, &options);
+        }
+    }
+}
+
+impl<'f> VisitMut for Format<'f> {
+    fn visit_table_mut(&mut self, node: &mut Table) {
+        let options = self.options;
+        if !node.is_dotted() {
+            let prefix = raw(node.decor().prefix()).unwrap_or_default();
+            let mut header = "\n".repeat(options.blank_lines_between_tables);
+            header.push_str(&options.comment_lines(prefix));
+            let suffix = options.line_comment(raw(node.decor().suffix()).unwrap_or_default());
+            node.decor_mut().set_prefix(header);
+            node.decor_mut().set_suffix(suffix);
+
+            let trailing = options.comment_lines(raw(Some(node.trailing())).unwrap_or_default());
+            node.set_trailing(trailing);
+
+            self.expand_inline_tables(node);
+            self.first_line = true;
+            self.format_body(node, 0);
+            if options.align_entries {
+                crate::align::align_entries(node, options.eq_decor().len());
+            }
+            if options.align_comments {
+                crate::align::align_comments(node);
+            }
+        }
+
+        for (_, item) in node.iter_mut() {
+            match item {
+                Item::Table(table) => self.visit_table_mut(table),
+                Item::ArrayOfTables(array) => self.visit_array_of_tables_mut(array),
+                Item::None | Item::Value(_) => {}
+            }
+        }
+    }
+
+    fn visit_inline_table_mut(&mut self, node: &mut InlineTable) {
+        node.set_preamble("");
+        self.format_inline_body(node, true);
+    }
+
+    fn visit_array_mut(&mut self, node: &mut Array) {
+        let options = self.options;
+        let len = node.len();
+        let column = self.column.take();
+
+        // Comments before each value, on the line of each value and before the closing bracket
+        let mut leading = vec![Vec::new(); len];
+        let mut trailing = vec![String::new(); len];
+        let mut carry = Vec::new();
+        for (i, value) in node.iter().enumerate() {
+            let prefix = raw(value.decor().prefix()).unwrap_or_default();
+            let rest = if i == 0 {
+                prefix
+            } else {
+                if trailing[i - 1].is_empty() {
+                    trailing[i - 1] = options.line_comment(prefix);
+                }
+                &prefix[crate::comment::first_line(prefix).len()..]
+            };
+            leading[i] = std::mem::take(&mut carry);
+            leading[i].extend(options.comments(rest));
+
+            let suffix = raw(value.decor().suffix()).unwrap_or_default();
+            trailing[i] = options.line_comment(suffix);
+            carry = options.comments(&suffix[crate::comment::first_line(suffix).len()..]);
+        }
+        let node_trailing = raw(Some(node.trailing())).unwrap_or_default();
+        let rest = if len == 0 {
+            node_trailing
+        } else {
+            if trailing[len - 1].is_empty() {
+                trailing[len - 1] = options.line_comment(node_trailing);
+            }
+            &node_trailing[crate::comment::first_line(node_trailing).len()..]
+        };
+        let mut end = carry;
+        end.extend(options.comments(rest));
+
+        let has_comments = !end.is_empty()
+            || leading.iter().any(|c| !c.is_empty())
+            || trailing.iter().any(|c| !c.is_empty());
+        let multiline = self.is_multiline(node, has_comments, column);
+
+        self.level += usize::from(multiline);
+        for value in node.iter_mut() {
+            self.column = multiline.then(|| options.indent_width * self.level);
+            self.visit_value_mut(value);
+        }
+        self.level -= usize::from(multiline);
+
+        if multiline {
+            let indent = " ".repeat(options.indent_width * (self.level + 1));
+            let outer = " ".repeat(options.indent_width * self.level);
+            for (i, value) in node.iter_mut().enumerate() {
+                let mut prefix = if i == 0 {
+                    String::new()
+                } else {
+                    std::mem::take(&mut trailing[i - 1])
+                };
+                prefix.push('\n');
+                prefix.push_str(&crate::comment::set_leading_comments(&indent, &leading[i]));
+                value.decor_mut().set_prefix(prefix);
+                value.decor_mut().set_suffix("");
+            }
+
+            let mut closing = String::new();
+            if let Some(comment) = trailing.last_mut() {
+                if options.trailing_comma {
+                    closing = std::mem::take(comment);
+                } else if let Some(value) = node.get_mut(len - 1) {
+                    value.decor_mut().set_suffix(std::mem::take(comment));
+                }
+            }
+            closing.push('\n');
+            closing.push_str(&crate::comment::set_leading_comments(&indent, &end));
+            closing.truncate(closing.len() - indent.len());
+            closing.push_str(&outer);
+            node.set_trailing(closing);
+            node.set_trailing_comma(options.trailing_comma && len != 0);
+        } else {
+            for (i, value) in node.iter_mut().enumerate() {
+                value.decor_mut().set_prefix(if i == 0 { "" } else { " " });
+                value.decor_mut().set_suffix("");
+            }
+            node.set_trailing("");
+            node.set_trailing_comma(false);
+        }
+    }
+}
+
+// Drops the blank lines before the first header when nothing is written before it
+fn strip_first_header(doc: &mut DocumentMut) {
+    if !doc.as_table().get_values().is_empty() {
+        return;
+    }
+
+    // Mirror the order tables are written in, see `DocumentMut::renumber_positions`
+    let mut index = 0;
+    let mut last_position = 0;
+    let mut first = None;
+    crate::restructure::for_each_table(doc.as_table_mut(), &mut |table| {
+        if let Some(position) = table.position() {
+            last_position = position;
+        }
+        let is_visible = !(table.is_implicit() && table.get_values().is_empty());
+        if index != 0
+            && is_visible
+            && first
+                .map(|(position, _)| last_position < position)
+                .unwrap_or(true)
+        {
+            first = Some((last_position, index));
+        }
+        index += 1;
+    });
+
+    let Some((_, first)) = first else {
+        return;
+    };
+    let mut index = 0;
+    crate::restructure::for_each_table(doc.as_table_mut(), &mut |table| {
+        if index == first {
+            let prefix = raw(table.decor().prefix()).unwrap_or_default();
+            let prefix = prefix.trim_start().to_owned();
+            table.decor_mut().set_prefix(prefix);
+        }
+        index += 1;
+    });
+}
@@ -7,10 +7,10 @@ use crate::{InternalString, Item, KeyMut, RawString, Table, Value};
 
 /// Type representing a TOML inline table,
 /// payload of the `Value::InlineTable` variant
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default)]
 pub struct InlineTable {
     // `preamble` represents whitespaces in an empty table
-    preamble: RawString,
+    pub(crate) preamble: RawString,
     // Whether to hide an empty table
     pub(crate) implicit: bool,
     // prefix before `{` and suffix after `}`
@@ -31,15 +31,14 @@ impl InlineTable {
     }
 
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
-        Self {
-            items,
-            ..Default::default()
-        }
+        let mut table = Self::new();
+        table.items = items;
+        table
     }
 
     /// Convert to a table
-    pub fn into_table(self) -> Table {
-        let mut t = Table::with_pairs(self.items);
+    pub fn into_table(mut self) -> Table {
+        let mut t = Table::with_pairs(std::mem::take(&mut self.items));
         t.fmt();
         t
     }
@@ -431,10 +430,39 @@ impl InlineTable {
     }
 }
 
+impl InlineTable {
+    // A copy of `self` holding `values`, in the order of `self.items`
+    pub(crate) fn clone_with(&self, values: Vec<Item>) -> Self {
+        Self {
+            preamble: self.preamble.clone(),
+            implicit: self.implicit,
+            decor: self.decor.clone(),
+            span: self.span.clone(),
+            dotted: self.dotted,
+            items: crate::table::clone_pairs(&self.items, values),
+        }
+    }
+}
+
+impl Clone for InlineTable {
+    fn clone(&self) -> Self {
+        self.clone_with(crate::item::clone_items(
+            self.items.values().map(|kv| &kv.value),
+        ))
+    }
+}
+
+impl Drop for InlineTable {
+    fn drop(&mut self) {
+        let items = std::mem::take(&mut self.items);
+        crate::item::drop_items(items.into_values().map(|kv| kv.value).collect());
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for InlineTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_table(self, f, None, ("", ""))
+        crate::encode::encode_table(self, f, None, ("", ""), &Default::default())
     }
 }
 
@@ -465,9 +493,9 @@ impl IntoIterator for InlineTable {
     type Item = (InternalString, Value);
     type IntoIter = InlineTableIntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
         Box::new(
-            self.items
+            std::mem::take(&mut self.items)
                 .into_iter()
                 .filter(|(_, kv)| kv.value.is_value())
                 .map(|(k, kv)| (k, kv.value.into_value().unwrap())),
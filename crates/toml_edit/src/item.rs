@@ -147,12 +147,12 @@ impl Item {
     pub fn into_array_of_tables(self) -> Result<ArrayOfTables, Self> {
         match self {
             Item::ArrayOfTables(a) => Ok(a),
-            Item::Value(Value::Array(a)) => {
+            Item::Value(Value::Array(mut a)) => {
                 if a.is_empty() {
                     Err(Item::Value(Value::Array(a)))
                 } else if a.iter().all(|v| v.is_inline_table()) {
                     let mut aot = ArrayOfTables::new();
-                    aot.values = a.values;
+                    aot.values = std::mem::take(&mut a.values);
                     for value in aot.values.iter_mut() {
                         value.make_item();
                     }
@@ -331,6 +331,83 @@ impl Clone for Item {
     }
 }
 
+// Nested items are copied and freed through a stack rather than by recursion, so the depth of a
+// document is only limited by memory
+
+/// Copies `items`, see the `Clone` of [`Array`], [`InlineTable`], [`Table`] and [`ArrayOfTables`]
+pub(crate) fn clone_items<'i>(items: impl Iterator<Item = &'i Item>) -> Vec<Item> {
+    enum Step<'i> {
+        Enter(&'i Item),
+        // Put together a container from the copies of its children
+        Exit(&'i Item, usize),
+    }
+
+    let mut steps = items.map(Step::Enter).collect::<Vec<_>>();
+    steps.reverse();
+    let mut copies = Vec::with_capacity(steps.len());
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Enter(item) => match children(item) {
+                Some(children) => {
+                    steps.push(Step::Exit(item, children.len()));
+                    steps.extend(children.into_iter().rev().map(Step::Enter));
+                }
+                None => copies.push(match item {
+                    Item::Value(value) => Item::Value(value.clone()),
+                    _ => Item::None,
+                }),
+            },
+            Step::Exit(item, len) => {
+                let children = copies.split_off(copies.len() - len);
+                copies.push(match item {
+                    Item::Value(Value::Array(array)) => {
+                        Item::Value(Value::Array(array.clone_with(children)))
+                    }
+                    Item::Value(Value::InlineTable(table)) => {
+                        Item::Value(Value::InlineTable(table.clone_with(children)))
+                    }
+                    Item::Table(table) => Item::Table(table.clone_with(children)),
+                    Item::ArrayOfTables(array) => Item::ArrayOfTables(array.clone_with(children)),
+                    Item::None | Item::Value(_) => unreachable!("only containers have children"),
+                });
+            }
+        }
+    }
+    copies
+}
+
+// The children of containers
+fn children(item: &Item) -> Option<Vec<&Item>> {
+    match item {
+        Item::Value(Value::Array(array)) => Some(array.values.iter().collect()),
+        Item::Value(Value::InlineTable(table)) => {
+            Some(table.items.values().map(|kv| &kv.value).collect())
+        }
+        Item::Table(table) => Some(table.items.values().map(|kv| &kv.value).collect()),
+        Item::ArrayOfTables(array) => Some(array.values.iter().collect()),
+        Item::None | Item::Value(_) => None,
+    }
+}
+
+/// Frees `items`, see the `Drop` of [`Array`], [`InlineTable`], [`Table`] and [`ArrayOfTables`]
+pub(crate) fn drop_items(mut items: Vec<Item>) {
+    while let Some(mut item) = items.pop() {
+        match &mut item {
+            Item::Value(Value::Array(array)) => items.append(&mut array.values),
+            Item::Value(Value::InlineTable(table)) => {
+                let children = std::mem::take(&mut table.items);
+                items.extend(children.into_values().map(|kv| kv.value));
+            }
+            Item::Table(table) => {
+                let children = std::mem::take(&mut table.items);
+                items.extend(children.into_values().map(|kv| kv.value));
+            }
+            Item::ArrayOfTables(array) => items.append(&mut array.values),
+            Item::None | Item::Value(_) => {}
+        }
+    }
+}
+
 #[cfg(feature = "parse")]
 impl FromStr for Item {
     type Err = crate::TomlError;
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::str::FromStr;
 
 use crate::repr::{Decor, Repr};
+use crate::table::DEFAULT_KEY_DECOR;
 use crate::InternalString;
 
 /// Key as part of a Key/Value Pair or a table header.
@@ -147,6 +148,27 @@ impl Key {
         &self.dotted_decor
     }
 
+    /// Returns the comment lines before the line entry, without the leading `#`
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn leading_comments(&self) -> Vec<&str> {
+        self.leaf_decor.prefix_comments()
+    }
+
+    /// Replaces the comment lines before the line entry
+    ///
+    /// Blank lines before the comments and the indentation of the line entry are preserved.  Each
+    /// line of a comment becomes its own `#` comment line.
+    pub fn set_leading_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let comments = comments.into_iter().collect::<Vec<_>>();
+        self.leaf_decor
+            .set_prefix_comments(&comments, DEFAULT_KEY_DECOR.0);
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -288,7 +310,7 @@ fn to_key_repr(key: &str) -> Repr {
         } else {
             crate::encode::to_string_repr(
                 key,
-                Some(crate::encode::StringStyle::OnelineSingle),
+                Some(crate::encode::StringLayout::OnelineSingle),
                 Some(false),
             )
         }
@@ -297,7 +319,7 @@ fn to_key_repr(key: &str) -> Repr {
     {
         crate::encode::to_string_repr(
             key,
-            Some(crate::encode::StringStyle::OnelineSingle),
+            Some(crate::encode::StringLayout::OnelineSingle),
             Some(false),
         )
     }
@@ -400,6 +422,20 @@ impl<'k> KeyMut<'k> {
         self.key.dotted_decor()
     }
 
+    /// Returns the comment lines before the line entry, without the leading `#`
+    pub fn leading_comments(&self) -> Vec<&str> {
+        self.key.leading_comments()
+    }
+
+    /// Replaces the comment lines before the line entry
+    pub fn set_leading_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.key.set_leading_comments(comments);
+    }
+
     /// Auto formats the key.
     pub fn fmt(&mut self) {
         self.key.fmt();
@@ -0,0 +1,316 @@
+use crate::{Array, ArrayOfTables, InlineTable, Item, Table, TableLike, Value};
+
+/// A view over an [`ArrayOfTables`] or an [`Array`] of inline tables, as a map keyed by one of
+/// the fields of each table
+///
+/// Created with [`ArrayOfTables::keyed_by`] or [`Array::keyed_by`].  Tables without the field are
+/// skipped by lookups.  Only the entries being changed are touched, so the formatting of the rest
+/// is preserved.
+///
+/// The field's values are compared by their meaning, ignoring formatting.  If several tables share
+/// a value, lookups find the first of them, see [`Keyed::duplicates`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// # #[cfg(feature = "display")] {
+/// use toml_edit::{value, DocumentMut};
+///
+/// let mut doc = r#"
+/// [[package]]
+/// name = "a"
+/// version = "1.0.0"
+///
+/// [[package]]
+/// name = "b"
+/// version = "2.0.0"
+/// "#.parse::<DocumentMut>().unwrap();
+///
+/// let packages = doc["package"].as_array_of_tables_mut().unwrap();
+/// let mut packages = packages.keyed_by("name");
+/// packages.get_mut("b").unwrap()["version"] = value("2.1.0");
+/// packages.entry("c").or_default()["version"] = value("0.1.0");
+/// packages.remove("a");
+///
+/// assert_eq!(doc.to_string(), r#"
+/// [[package]]
+/// name = "b"
+/// version = "2.1.0"
+///
+/// [[package]]
+/// name = "c"
+/// version = "0.1.0"
+/// "#);
+/// # }
+/// # }
+/// ```
+pub struct Keyed<'a, T> {
+    values: &'a mut Vec<Item>,
+    key: String,
+    access: Access<T>,
+}
+
+// How tables are stored in the `Item`s of the underlying array
+struct Access<T> {
+    get: fn(&Item) -> Option<&T>,
+    get_mut: fn(&mut Item) -> Option<&mut T>,
+    wrap: fn(T) -> Item,
+    unwrap: fn(Item) -> Option<T>,
+    // Carries the formatting of a replaced table over to its replacement
+    keep_format: fn(&T, &mut T),
+}
+
+impl<T> Clone for Access<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Access<T> {}
+
+impl<T> std::fmt::Debug for Keyed<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keyed")
+            .field("key", &self.key)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl ArrayOfTables {
+    /// A view of the tables as a map keyed by the value of their `key` field
+    ///
+    /// See [`Keyed`] for details.
+    pub fn keyed_by(&mut self, key: &str) -> Keyed<'_, Table> {
+        Keyed {
+            values: &mut self.values,
+            key: key.to_owned(),
+            access: Access {
+                get: Item::as_table,
+                get_mut: Item::as_table_mut,
+                wrap: Item::Table,
+                unwrap: |item| item.into_table().ok(),
+                keep_format: |old, new| {
+                    new.decor = old.decor.clone();
+                    if let Some(position) = old.position() {
+                        new.set_position(position);
+                    }
+                    if new.trailing.as_str() == Some("") {
+                        new.trailing = old.trailing.clone();
+                    }
+                },
+            },
+        }
+    }
+}
+
+impl Array {
+    /// A view of the inline tables as a map keyed by the value of their `key` field
+    ///
+    /// Values that are not inline tables are skipped.  See [`Keyed`] for details.
+    pub fn keyed_by(&mut self, key: &str) -> Keyed<'_, InlineTable> {
+        Keyed {
+            values: &mut self.values,
+            key: key.to_owned(),
+            access: Access {
+                get: |item| item.as_value().and_then(Value::as_inline_table),
+                get_mut: |item| item.as_value_mut().and_then(Value::as_inline_table_mut),
+                wrap: |table| Item::Value(Value::InlineTable(table)),
+                unwrap: |item| {
+                    if let Item::Value(Value::InlineTable(table)) = item {
+                        Some(table)
+                    } else {
+                        None
+                    }
+                },
+                keep_format: |old, new| *new.decor_mut() = old.decor().clone(),
+            },
+        }
+    }
+}
+
+impl<'a, T> Keyed<'a, T>
+where
+    T: TableLike + Default,
+{
+    /// The field identifying each table
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the table whose field is `id`
+    pub fn get(&self, id: impl Into<Value>) -> Option<&T> {
+        let index = self.position(&id.into())?;
+        (self.access.get)(&self.values[index])
+    }
+
+    /// Returns the table whose field is `id`
+    pub fn get_mut(&mut self, id: impl Into<Value>) -> Option<&mut T> {
+        let index = self.position(&id.into())?;
+        (self.access.get_mut)(&mut self.values[index])
+    }
+
+    /// Returns true if a table's field is `id`
+    pub fn contains(&self, id: impl Into<Value>) -> bool {
+        self.position(&id.into()).is_some()
+    }
+
+    /// Gets the entry for the table whose field is `id`, for in-place manipulation
+    pub fn entry(&mut self, id: impl Into<Value>) -> KeyedEntry<'_, T> {
+        let id = id.into();
+        match self.position(&id) {
+            Some(index) => KeyedEntry::Occupied(
+                (self.access.get_mut)(&mut self.values[index]).expect("found by position"),
+            ),
+            None => KeyedEntry::Vacant(VacantKeyedEntry {
+                values: self.values,
+                key: &self.key,
+                access: self.access,
+                id: Box::new(id),
+            }),
+        }
+    }
+
+    /// Inserts `table` or replaces the table with the same field in place, returning it
+    ///
+    /// A replacement takes over the comments and whitespace around the old table, and for
+    /// [`Table`]s its position in the document.
+    ///
+    /// Returns `Err(table)` when `table` doesn't have the field.
+    pub fn upsert(&mut self, mut table: T) -> Result<Option<T>, T> {
+        let Some(id) = field(&table, &self.key).cloned() else {
+            return Err(table);
+        };
+        if let Some(index) = self.position(&id) {
+            if let Some(old) = (self.access.get)(&self.values[index]) {
+                (self.access.keep_format)(old, &mut table);
+            }
+            let old = std::mem::replace(&mut self.values[index], (self.access.wrap)(table));
+            Ok((self.access.unwrap)(old))
+        } else {
+            self.values.push((self.access.wrap)(table));
+            Ok(None)
+        }
+    }
+
+    /// Removes the table whose field is `id`, returning it
+    pub fn remove(&mut self, id: impl Into<Value>) -> Option<T> {
+        let index = self.position(&id.into())?;
+        (self.access.unwrap)(self.values.remove(index))
+    }
+
+    /// Returns the values of the field that are shared by more than one table, in the order they
+    /// first appear
+    pub fn duplicates(&self) -> Vec<&Value> {
+        let mut seen = Vec::new();
+        let mut duplicates = Vec::new();
+        for id in self.ids() {
+            if seen.contains(&id) {
+                if !duplicates.contains(&id) {
+                    duplicates.push(id);
+                }
+            } else {
+                seen.push(id);
+            }
+        }
+        duplicates
+    }
+
+    fn ids(&self) -> impl Iterator<Item = &Value> + '_ {
+        let get = self.access.get;
+        self.values
+            .iter()
+            .filter_map(move |item| get(item).and_then(|t| field(t, &self.key)))
+    }
+
+    fn position(&self, id: &Value) -> Option<usize> {
+        self.values
+            .iter()
+            .position(|item| (self.access.get)(item).and_then(|t| field(t, &self.key)) == Some(id))
+    }
+}
+
+fn field<'t, T: TableLike>(table: &'t T, key: &str) -> Option<&'t Value> {
+    table.get(key).and_then(Item::as_value)
+}
+
+/// A view into a single table of a [`Keyed`] view, which may either be vacant or occupied
+#[derive(Debug)]
+pub enum KeyedEntry<'a, T> {
+    /// The table with the field
+    Occupied(&'a mut T),
+    /// No table has the field yet
+    Vacant(VacantKeyedEntry<'a, T>),
+}
+
+impl<'a, T> KeyedEntry<'a, T>
+where
+    T: TableLike + Default,
+{
+    /// Ensures a table is present by inserting `default` if empty, and returns it
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            KeyedEntry::Occupied(table) => table,
+            KeyedEntry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a table is present by inserting the result of `default` if empty, and returns it
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            KeyedEntry::Occupied(table) => table,
+            KeyedEntry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a table is present by inserting an empty one if empty, and returns it
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+
+    /// Modifies the table, if present
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let KeyedEntry::Occupied(table) = &mut self {
+            f(table);
+        }
+        self
+    }
+}
+
+/// A view into a vacant entry of a [`Keyed`] view
+pub struct VacantKeyedEntry<'a, T> {
+    values: &'a mut Vec<Item>,
+    key: &'a str,
+    access: Access<T>,
+    id: Box<Value>,
+}
+
+impl<T> std::fmt::Debug for VacantKeyedEntry<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VacantKeyedEntry")
+            .field("key", &self.key)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<'a, T> VacantKeyedEntry<'a, T>
+where
+    T: TableLike + Default,
+{
+    /// The value the field of the table will have
+    pub fn id(&self) -> &Value {
+        &self.id
+    }
+
+    /// Appends `table` with its field set to the entry's value, and returns it
+    pub fn insert(self, mut table: T) -> &'a mut T {
+        let mut id = *self.id;
+        id.decor_mut().clear();
+        table.insert(self.key, Item::Value(id));
+        self.values.push((self.access.wrap)(table));
+        let last = self.values.last_mut().expect("just pushed");
+        (self.access.get_mut)(last).expect("just pushed")
+    }
+}
@@ -21,12 +21,12 @@
 //! assert_eq!(doc.to_string(), toml);
 //! // let's add a new key/value pair inside a.b: c = {d = "hello"}
 //! doc["a"]["b"]["c"]["d"] = value("hello");
-//! // autoformat inline table a.b.c: { d = "hello" }
+//! // autoformat inline table a.b.c: { d = 'hello' }, following the quotes of the document
 //! doc["a"]["b"]["c"].as_inline_table_mut().map(|t| t.fmt());
 //! let expected = r#"
 //! "hello" = 'toml!' # comment
 //! ['a'.b]
-//! c = { d = "hello" }
+//! c = { d = 'hello' }
 //! "#;
 //! assert_eq!(doc.to_string(), expected);
 //! # }
@@ -76,22 +76,40 @@
 #![warn(clippy::print_stderr)]
 #![warn(clippy::print_stdout)]
 
+#[cfg(feature = "display")]
+mod align;
 mod array;
 mod array_of_tables;
+#[cfg(feature = "display")]
+mod canonical;
+mod comment;
+mod compare;
+#[cfg(feature = "display")]
+mod convert;
+mod cursor;
 mod document;
 #[cfg(feature = "display")]
 mod encode;
 mod error;
+#[cfg(feature = "display")]
+mod format;
 mod index;
 mod inline_table;
 mod internal_string;
 mod item;
 mod key;
+mod keyed;
 #[cfg(feature = "parse")]
 mod parser;
 mod raw_string;
 mod repr;
+mod restructure;
+#[cfg(feature = "display")]
+mod source_map;
+mod style;
 mod table;
+#[cfg(feature = "display")]
+mod text_edit;
 mod value;
 
 #[cfg(feature = "serde")]
@@ -99,19 +117,28 @@ pub mod de;
 #[cfg(feature = "serde")]
 pub mod ser;
 
+pub mod fold;
+pub mod try_visit;
+pub mod try_visit_mut;
 pub mod visit;
 pub mod visit_mut;
 
-pub use crate::array::{Array, ArrayIntoIter, ArrayIter, ArrayIterMut};
+pub use crate::array::{Array, ArrayDrain, ArrayIntoIter, ArrayIter, ArrayIterMut, FixedLayout};
 pub use crate::array_of_tables::{
-    ArrayOfTables, ArrayOfTablesIntoIter, ArrayOfTablesIter, ArrayOfTablesIterMut,
+    ArrayOfTables, ArrayOfTablesDrain, ArrayOfTablesIntoIter, ArrayOfTablesIter,
+    ArrayOfTablesIterMut,
 };
 /// Deprecated, replaced with [`DocumentMut`]
 #[deprecated(since = "0.22.6", note = "Replaced with `DocumentMut`")]
 pub type Document = DocumentMut;
+#[cfg(feature = "display")]
+pub use crate::convert::ConvertOptions;
+pub use crate::cursor::{Cursor, Node, NodeMut, PathSegment};
 pub use crate::document::DocumentMut;
 pub use crate::document::ImDocument;
-pub use crate::error::TomlError;
+pub use crate::error::{EditError, TomlError};
+#[cfg(feature = "display")]
+pub use crate::format::{ArrayWrap, Formatter, KeyQuoting};
 pub use crate::inline_table::{
     InlineEntry, InlineOccupiedEntry, InlineTable, InlineTableIntoIter, InlineTableIter,
     InlineTableIterMut, InlineVacantEntry,
@@ -119,8 +146,12 @@ pub use crate::inline_table::{
 pub use crate::internal_string::InternalString;
 pub use crate::item::{array, table, value, Item};
 pub use crate::key::{Key, KeyMut};
+pub use crate::keyed::{Keyed, KeyedEntry, VacantKeyedEntry};
 pub use crate::raw_string::RawString;
-pub use crate::repr::{Decor, Formatted, Repr};
+pub use crate::repr::{Decor, FloatStyle, Formatted, IntegerStyle, Radix, Repr, StringStyle};
+#[cfg(feature = "display")]
+pub use crate::source_map::{NodeKind, NodeSpan, SourceMap};
+pub use crate::style::Style;
 pub use crate::table::{
     Entry, IntoIter, Iter, IterMut, OccupiedEntry, Table, TableLike, VacantEntry,
 };
@@ -1,14 +1,18 @@
 use crate::InternalString;
 
 /// Opaque string storage for raw TOML; internal to `toml_edit`
-#[derive(PartialEq, Eq, Clone, Hash)]
+#[derive(Clone)]
 pub struct RawString(RawStringInner);
 
-#[derive(PartialEq, Eq, Clone, Hash)]
+#[derive(Clone)]
 enum RawStringInner {
     Empty,
     Explicit(InternalString),
     Spanned(std::ops::Range<usize>),
+    /// Text copied out of the source at the given offset, unmodified since
+    ///
+    /// The offset is kept small so this doesn't grow every node
+    Despanned(InternalString, u32),
 }
 
 impl RawString {
@@ -28,6 +32,7 @@ impl RawString {
             RawStringInner::Empty => Some(""),
             RawStringInner::Explicit(s) => Some(s.as_str()),
             RawStringInner::Spanned(_) => None,
+            RawStringInner::Despanned(s, _) => Some(s.as_str()),
         }
     }
 
@@ -39,13 +44,25 @@ impl RawString {
             RawStringInner::Empty => None,
             RawStringInner::Explicit(_) => None,
             RawStringInner::Spanned(span) => Some(span.clone()),
+            RawStringInner::Despanned(_, _) => None,
+        }
+    }
+
+    /// Where the text was copied from in the source, if it was parsed and has not been replaced
+    pub(crate) fn origin(&self) -> Option<std::ops::Range<usize>> {
+        match &self.0 {
+            RawStringInner::Despanned(s, start) => {
+                let start = *start as usize;
+                Some(start..(start + s.len()))
+            }
+            _ => None,
         }
     }
 
     pub(crate) fn to_str<'s>(&'s self, input: &'s str) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
-            RawStringInner::Explicit(s) => s.as_str(),
+            RawStringInner::Explicit(s) | RawStringInner::Despanned(s, _) => s.as_str(),
             RawStringInner::Spanned(span) => input.get(span.clone()).unwrap_or_else(|| {
                 panic!("span {:?} should be in input:\n```\n{}\n```", span, input)
             }),
@@ -59,7 +76,7 @@ impl RawString {
     ) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
-            RawStringInner::Explicit(s) => s.as_str(),
+            RawStringInner::Explicit(s) | RawStringInner::Despanned(s, _) => s.as_str(),
             RawStringInner::Spanned(span) => {
                 if let Some(input) = input {
                     input.get(span.clone()).unwrap_or_else(|| {
@@ -77,34 +94,86 @@ impl RawString {
             RawStringInner::Empty => {}
             RawStringInner::Explicit(_) => {}
             RawStringInner::Spanned(span) => {
-                *self = Self::from(input.get(span.clone()).unwrap_or_else(|| {
+                let raw = input.get(span.clone()).unwrap_or_else(|| {
                     panic!("span {:?} should be in input:\n```\n{}\n```", span, input)
-                }));
+                });
+                *self = match u32::try_from(span.start) {
+                    Ok(start) => Self(RawStringInner::Despanned(raw.into(), start)),
+                    Err(_) => Self::from(raw),
+                };
             }
+            RawStringInner::Despanned(_, _) => {}
         }
     }
 
     #[cfg(feature = "display")]
-    pub(crate) fn encode(&self, buf: &mut dyn std::fmt::Write, input: &str) -> std::fmt::Result {
+    pub(crate) fn encode(
+        &self,
+        buf: &mut dyn crate::encode::Output,
+        input: &str,
+    ) -> std::fmt::Result {
         let raw = self.to_str(input);
-        for part in raw.split('\r') {
-            write!(buf, "{}", part)?;
-        }
-        Ok(())
+        self.encode_raw(buf, raw)
     }
 
     #[cfg(feature = "display")]
     pub(crate) fn encode_with_default(
         &self,
-        buf: &mut dyn std::fmt::Write,
+        buf: &mut dyn crate::encode::Output,
         input: Option<&str>,
         default: &str,
     ) -> std::fmt::Result {
         let raw = self.to_str_with_default(input, default);
-        for part in raw.split('\r') {
-            write!(buf, "{}", part)?;
+        self.encode_raw(buf, raw)
+    }
+
+    #[cfg(feature = "display")]
+    fn encode_raw(&self, buf: &mut dyn crate::encode::Output, raw: &str) -> std::fmt::Result {
+        if let Some(origin) = self.origin() {
+            let rendered = if raw.contains('\r') {
+                std::borrow::Cow::Owned(raw.replace('\r', ""))
+            } else {
+                std::borrow::Cow::Borrowed(raw)
+            };
+            buf.write_origin(origin, raw, &rendered)
+        } else {
+            for part in raw.split('\r') {
+                write!(buf, "{}", part)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn comparable(&self) -> Comparable<'_> {
+        match &self.0 {
+            RawStringInner::Empty => Comparable::Empty,
+            RawStringInner::Explicit(s) | RawStringInner::Despanned(s, _) => {
+                Comparable::Explicit(s.as_str())
+            }
+            RawStringInner::Spanned(span) => Comparable::Spanned(span),
         }
-        Ok(())
+    }
+}
+
+// Where the text of a despanned string came from does not affect its identity
+#[derive(PartialEq, Eq, Hash)]
+enum Comparable<'s> {
+    Empty,
+    Explicit(&'s str),
+    Spanned(&'s std::ops::Range<usize>),
+}
+
+impl PartialEq for RawString {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparable() == other.comparable()
+    }
+}
+
+impl Eq for RawString {}
+
+impl std::hash::Hash for RawString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.comparable().hash(state);
     }
 }
 
@@ -119,7 +188,9 @@ impl std::fmt::Debug for RawString {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match &self.0 {
             RawStringInner::Empty => write!(formatter, "empty"),
-            RawStringInner::Explicit(s) => write!(formatter, "{:?}", s),
+            RawStringInner::Explicit(s) | RawStringInner::Despanned(s, _) => {
+                write!(formatter, "{:?}", s)
+            }
             RawStringInner::Spanned(s) => write!(formatter, "{:?}", s),
         }
     }
@@ -90,6 +90,193 @@ where
     }
 }
 
+impl Formatted<String> {
+    /// How the string is quoted, if it has an explicit representation
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut] or a string written with
+    /// [`Formatted::set_style`].
+    pub fn style(&self) -> Option<StringStyle> {
+        let raw = self.repr.as_ref()?.as_raw().as_str()?;
+        if raw.starts_with("\"\"\"") {
+            Some(StringStyle::MultilineBasic)
+        } else if raw.starts_with("'''") {
+            Some(StringStyle::MultilineLiteral)
+        } else if raw.starts_with('"') {
+            Some(StringStyle::Basic)
+        } else if raw.starts_with('\'') {
+            Some(StringStyle::Literal)
+        } else {
+            None
+        }
+    }
+
+    /// Quotes the string in `style`
+    ///
+    /// Fails when the string can't be written in `style`, leaving its representation alone.  Only
+    /// literal strings can fail, as they can't hold escapes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{Formatted, StringStyle};
+    ///
+    /// let mut path = Formatted::new(String::from(r"C:\Users\it's me"));
+    /// assert!(path.set_style(StringStyle::Literal).is_err());
+    /// path.set_style(StringStyle::MultilineLiteral).unwrap();
+    /// assert_eq!(path.to_string(), "'''\nC:\\Users\\it's me'''");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn set_style(&mut self, style: StringStyle) -> Result<(), crate::EditError> {
+        let repr = crate::encode::to_string_repr_with_style(&self.value, style)
+            .ok_or(crate::EditError::UnrepresentableString(style))?;
+        self.repr = Some(repr);
+        Ok(())
+    }
+}
+
+impl Formatted<i64> {
+    /// How the integer is written, if it has an explicit representation
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn style(&self) -> Option<IntegerStyle> {
+        let raw = self.repr.as_ref()?.as_raw().as_str()?;
+        let raw = raw.trim_start_matches(['+', '-']);
+        let (radix, digits) = match raw.get(..2) {
+            Some("0x") => (Radix::Hexadecimal, &raw[2..]),
+            Some("0o") => (Radix::Octal, &raw[2..]),
+            Some("0b") => (Radix::Binary, &raw[2..]),
+            _ => (Radix::Decimal, raw),
+        };
+        let group = digits
+            .contains('_')
+            .then(|| digits.rsplit('_').next().map(str::len))
+            .flatten();
+        let uppercase = digits.bytes().any(|b| b.is_ascii_uppercase());
+        Some(IntegerStyle::new(radix).group(group).uppercase(uppercase))
+    }
+
+    /// Writes the integer in `style`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{Formatted, IntegerStyle, Radix};
+    ///
+    /// let mut mask = Formatted::new(0xffff);
+    /// mask.set_style(IntegerStyle::new(Radix::Hexadecimal).group(Some(2)).uppercase(true));
+    /// assert_eq!(mask.to_string(), "0xFF_FF");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn set_style(&mut self, style: IntegerStyle) {
+        self.repr = Some(crate::encode::to_i64_repr_with_style(self.value, style));
+    }
+
+    /// Replaces the integer, keeping how it is written
+    ///
+    /// Without an explicit representation, the new integer gets the default one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, Value};
+    ///
+    /// let mut doc = "mode = 0o755\nbig = 1_000_000\n".parse::<DocumentMut>().unwrap();
+    /// if let Some(Value::Integer(mode)) = doc["mode"].as_value_mut() {
+    ///     mode.set_value(0o644);
+    /// }
+    /// if let Some(Value::Integer(big)) = doc["big"].as_value_mut() {
+    ///     big.set_value(2_500_000);
+    /// }
+    /// assert_eq!(doc.to_string(), "mode = 0o644\nbig = 2_500_000\n");
+    /// # }
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn set_value(&mut self, value: i64) {
+        let style = self.style();
+        self.value = value;
+        match style {
+            Some(style) => self.set_style(style),
+            None => self.repr = None,
+        }
+    }
+}
+
+impl Formatted<f64> {
+    /// How the float is written, if it has an explicit representation
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn style(&self) -> Option<FloatStyle> {
+        let raw = self.repr.as_ref()?.as_raw().as_str()?;
+        let mut parts = raw.split(['e', 'E']);
+        let mantissa = parts.next().unwrap_or(raw);
+        let exponent = parts.next().is_some();
+        let precision = mantissa
+            .split_once('.')
+            .map(|(_, fraction)| fraction.bytes().filter(|b| *b != b'_').count());
+        Some(FloatStyle::new().exponent(exponent).precision(precision))
+    }
+
+    /// Writes the float in `style`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{FloatStyle, Formatted};
+    ///
+    /// let mut f = Formatted::new(1500.0);
+    /// f.set_style(FloatStyle::new().exponent(true).precision(Some(2)));
+    /// assert_eq!(f.to_string(), "1.50e3");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn set_style(&mut self, style: FloatStyle) {
+        self.repr = Some(crate::encode::to_f64_repr_with_style(self.value, style));
+    }
+
+    /// Replaces the float, keeping whether it is written in scientific notation
+    ///
+    /// The new float is written with as few digits as it needs, rather than padded or cut to the
+    /// old number of fraction digits; call [`Formatted::set_style`] afterwards for a fixed
+    /// precision. Without an explicit representation, the new float gets the default one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, FloatStyle, Value};
+    ///
+    /// let mut doc = "h = 6.626e-34\nratio = 2.5000\n".parse::<DocumentMut>().unwrap();
+    /// if let Some(Value::Float(h)) = doc["h"].as_value_mut() {
+    ///     h.set_value(1.25e10);
+    /// }
+    /// if let Some(Value::Float(ratio)) = doc["ratio"].as_value_mut() {
+    ///     ratio.set_value(0.75);
+    ///     ratio.set_style(FloatStyle::new().precision(Some(4)));
+    /// }
+    /// assert_eq!(doc.to_string(), "h = 1.25e10\nratio = 0.7500\n");
+    /// # }
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn set_value(&mut self, value: f64) {
+        let style = self.style();
+        self.value = value;
+        match style {
+            Some(style) => self.set_style(FloatStyle::new().exponent(style.has_exponent())),
+            None => self.repr = None,
+        }
+    }
+}
+
 impl<T> std::fmt::Debug for Formatted<T>
 where
     T: std::fmt::Debug,
@@ -117,6 +304,127 @@ where
     }
 }
 
+/// How a string value is quoted
+///
+/// See [`Formatted::set_style`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StringStyle {
+    /// `"basic string"`, which can hold anything through escapes
+    Basic,
+    /// `'literal string'`, without escapes
+    Literal,
+    /// `"""multi-line basic string"""`, which can hold anything through escapes
+    MultilineBasic,
+    /// `'''multi-line literal string'''`, without escapes
+    MultilineLiteral,
+}
+
+/// How an integer value is written
+///
+/// See the `set_style` method of [`Formatted<i64>`][Formatted].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntegerStyle {
+    radix: Radix,
+    group: Option<usize>,
+    uppercase: bool,
+}
+
+impl IntegerStyle {
+    /// Digits in `radix`, not grouped, in lowercase
+    pub fn new(radix: Radix) -> Self {
+        Self {
+            radix,
+            group: None,
+            uppercase: false,
+        }
+    }
+
+    /// How many digits to put between `_` separators, counting from the last digit
+    pub fn group(mut self, size: Option<usize>) -> Self {
+        self.group = size.filter(|size| *size != 0);
+        self
+    }
+
+    /// Whether to write hexadecimal digits in uppercase
+    pub fn uppercase(mut self, yes: bool) -> Self {
+        self.uppercase = yes;
+        self
+    }
+
+    /// The base of the digits
+    ///
+    /// Negative integers can only be written in decimal and fall back to it.
+    pub fn radix(&self) -> Radix {
+        self.radix
+    }
+
+    /// How many digits are between `_` separators
+    pub fn group_size(&self) -> Option<usize> {
+        self.group
+    }
+
+    /// Whether hexadecimal digits are in uppercase
+    pub fn is_uppercase(&self) -> bool {
+        self.uppercase
+    }
+}
+
+/// The base an integer is written in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Radix {
+    /// `1234`
+    #[default]
+    Decimal,
+    /// `0x4d2`
+    Hexadecimal,
+    /// `0o2322`
+    Octal,
+    /// `0b10011010010`
+    Binary,
+}
+
+/// How a float value is written
+///
+/// Floats are always written with enough digits to read back as the same value.
+///
+/// See the `set_style` method of [`Formatted<f64>`][Formatted].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FloatStyle {
+    exponent: bool,
+    precision: Option<usize>,
+}
+
+impl FloatStyle {
+    /// Decimal notation with as few digits as needed, like `1500.0`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to use scientific notation, like `1.5e3`
+    pub fn exponent(mut self, yes: bool) -> Self {
+        self.exponent = yes;
+        self
+    }
+
+    /// The least number of digits to write after the decimal point, padding with zeros
+    pub fn precision(mut self, digits: Option<usize>) -> Self {
+        self.precision = digits;
+        self
+    }
+
+    /// Whether scientific notation is used
+    pub fn has_exponent(&self) -> bool {
+        self.exponent
+    }
+
+    /// The least number of digits after the decimal point
+    pub fn min_precision(&self) -> Option<usize> {
+        self.precision
+    }
+}
+
 pub trait ValueRepr: crate::private::Sealed {
     /// The TOML representation of the value
     #[cfg(feature = "display")]
@@ -163,8 +471,19 @@ impl Repr {
         self.raw_value.despan(input);
     }
 
+    /// The unmodified source text and where it came from, see [`RawString::origin`]
+    pub(crate) fn origin(&self) -> Option<(std::ops::Range<usize>, &str)> {
+        let origin = self.raw_value.origin()?;
+        let raw = self.raw_value.as_str()?;
+        Some((origin, raw))
+    }
+
     #[cfg(feature = "display")]
-    pub(crate) fn encode(&self, buf: &mut dyn std::fmt::Write, input: &str) -> std::fmt::Result {
+    pub(crate) fn encode(
+        &self,
+        buf: &mut dyn crate::encode::Output,
+        input: &str,
+    ) -> std::fmt::Result {
         self.as_raw().encode(buf, input)
     }
 }
@@ -208,7 +527,7 @@ impl Decor {
     #[cfg(feature = "display")]
     pub(crate) fn prefix_encode(
         &self,
-        buf: &mut dyn std::fmt::Write,
+        buf: &mut dyn crate::encode::Output,
         input: Option<&str>,
         default: &str,
     ) -> std::fmt::Result {
@@ -232,7 +551,7 @@ impl Decor {
     #[cfg(feature = "display")]
     pub(crate) fn suffix_encode(
         &self,
-        buf: &mut dyn std::fmt::Write,
+        buf: &mut dyn crate::encode::Output,
         input: Option<&str>,
         default: &str,
     ) -> std::fmt::Result {
@@ -248,6 +567,31 @@ impl Decor {
         self.suffix = Some(suffix.into());
     }
 
+    pub(crate) fn prefix_comments(&self) -> Vec<&str> {
+        self.prefix()
+            .and_then(RawString::as_str)
+            .map(crate::comment::leading_comments)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_prefix_comments<S: AsRef<str>>(&mut self, comments: &[S], default: &str) {
+        let prefix = self.prefix().and_then(RawString::as_str).unwrap_or(default);
+        let prefix = crate::comment::set_leading_comments(prefix, comments);
+        self.set_prefix(prefix);
+    }
+
+    pub(crate) fn suffix_comment(&self) -> Option<&str> {
+        self.suffix()
+            .and_then(RawString::as_str)
+            .and_then(crate::comment::trailing_comment)
+    }
+
+    pub(crate) fn set_suffix_comment(&mut self, comment: Option<&str>, default: &str) {
+        let suffix = self.suffix().and_then(RawString::as_str).unwrap_or(default);
+        let suffix = crate::comment::set_trailing_comment(suffix, comment);
+        self.set_suffix(suffix);
+    }
+
     pub(crate) fn despan(&mut self, input: &str) {
         if let Some(prefix) = &mut self.prefix {
             prefix.despan(input);
@@ -0,0 +1,471 @@
+use crate::key::Key;
+use crate::table::{KeyValuePairs, TableKeyValue};
+use crate::{DocumentMut, EditError, InlineTable, Item, Table, Value};
+
+/// Restructuring
+impl DocumentMut {
+    /// Renames the item at the key `path`, keeping its place among its siblings and its formatting
+    ///
+    /// The headers of any tables and arrays of tables under the item follow the new key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::DocumentMut;
+    /// let mut doc = "[foo.bar]\na = 1\n\n[[foo.bar.baz]]\nb = 2\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// doc.rename_key(&["foo", "bar"], "qux").unwrap();
+    /// assert_eq!(doc.to_string(), "[foo.qux]\na = 1\n\n[[foo.qux.baz]]\nb = 2\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn rename_key(&mut self, path: &[&str], new_key: &str) -> Result<(), EditError> {
+        let (parent, key) = split_last(path)?;
+        get_item(self.as_table(), path)?;
+        if key == new_key {
+            return Ok(());
+        }
+
+        let items = items_mut(self.as_table_mut(), parent).into_items();
+        if items
+            .get(new_key)
+            .map(|kv| !kv.value.is_none())
+            .unwrap_or(false)
+        {
+            let mut new_path = EditError::path(parent);
+            new_path.push(new_key.to_owned());
+            return Err(EditError::AlreadyExists(new_path));
+        }
+        // Clear out any placeholder
+        items.shift_remove(new_key);
+
+        let (index, _, kv) = items.shift_remove_full(key).expect("validated above");
+        let kv = TableKeyValue::new(rekey(kv.key, new_key), kv.value);
+        items.insert(new_key.into(), kv);
+        items.move_index(items.len() - 1, index);
+        Ok(())
+    }
+
+    /// Moves the item at the key path `from` to the key path `to`
+    ///
+    /// Tables along `to` are created as needed.  The item keeps its formatting, including comments,
+    /// and tables keep their [position][Table::position] within the document.  Implicit tables left
+    /// empty by the move are removed.
+    ///
+    /// On error, the document is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::DocumentMut;
+    /// let mut doc = "[a]\n# important\nx = 1\n\n[b]\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// doc.move_item(&["a", "x"], &["b", "y"]).unwrap();
+    /// assert_eq!(doc.to_string(), "[a]\n\n[b]\n# important\ny = 1\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn move_item(&mut self, from: &[&str], to: &[&str]) -> Result<(), EditError> {
+        let (from_parent, from_key) = split_last(from)?;
+        let (to_parent, to_key) = split_last(to)?;
+
+        // Validate everything up front so a failure leaves the document untouched
+        get_item(self.as_table(), from)?;
+        if from == to {
+            return Ok(());
+        }
+        if to.starts_with(from) {
+            return Err(EditError::MoveIntoSelf(EditError::path(to)));
+        }
+        check_vacant(self.as_table(), to)?;
+
+        let root = self.as_table_mut();
+        let source = items_mut(root, from_parent);
+        let from_inline = source.is_inline();
+        let kv = source
+            .into_items()
+            .shift_remove(from_key)
+            .expect("validated above");
+
+        let destination = create_parents(root, to_parent);
+        let to_inline = destination.is_inline();
+        let TableKeyValue { mut key, mut value } = kv;
+        if to_key != key.get() {
+            key = rekey(key, to_key);
+        }
+        if to_inline {
+            value.make_value();
+        }
+        if from_inline != to_inline {
+            // Line-oriented and inline formatting are not interchangeable
+            key.leaf_decor.clear();
+            key.dotted_decor.clear();
+            if let Some(value) = value.as_value_mut() {
+                value.decor_mut().clear();
+            }
+        }
+        destination
+            .into_items()
+            .insert(to_key.into(), TableKeyValue::new(key, value));
+
+        prune_implicit(root, from_parent);
+        Ok(())
+    }
+
+    /// Moves the table or array of tables at the key path `from` to the key path `to`
+    ///
+    /// This rewrites the header of the table and the headers of all tables and arrays of tables
+    /// beneath it.  When only the last key changes, the table keeps its place among its siblings,
+    /// see [`DocumentMut::rename_key`], otherwise see [`DocumentMut::move_item`].
+    ///
+    /// On error, the document is left unchanged.
+    pub fn rename_table_path(&mut self, from: &[&str], to: &[&str]) -> Result<(), EditError> {
+        let (from_parent, _) = split_last(from)?;
+        let (to_parent, to_key) = split_last(to)?;
+        let item = get_item(self.as_table(), from)?;
+        if !(item.is_table() || item.is_array_of_tables()) {
+            return Err(EditError::NotATable(EditError::path(from)));
+        }
+
+        if from_parent == to_parent {
+            self.rename_key(from, to_key)
+        } else {
+            self.move_item(from, to)
+        }
+    }
+}
+
+/// Placement
+impl DocumentMut {
+    /// Inserts `table` at the key path `path`, right after the table or array of tables `sibling`
+    ///
+    /// `sibling` is the key of a table in the same parent as `path`.  The new table, and any tables
+    /// nested in it, are rendered after the sibling and the tables nested in it.  Positions are
+    /// renumbered, see [`DocumentMut::renumber_positions`].
+    ///
+    /// On error, the document is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{value, DocumentMut, Table};
+    /// let mut doc = "[profile.dev]\nopt-level = 1\n\n[[bin]]\nname = \"a\"\n"
+    ///     .parse::<DocumentMut>()
+    ///     .unwrap();
+    ///
+    /// let mut release = Table::new();
+    /// release["lto"] = value(true);
+    /// doc.insert_table_after(&["profile", "release"], "dev", release).unwrap();
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "[profile.dev]\nopt-level = 1\n\n[profile.release]\nlto = true\n\n[[bin]]\nname = \"a\"\n"
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn insert_table_after(
+        &mut self,
+        path: &[&str],
+        sibling: &str,
+        table: Table,
+    ) -> Result<(), EditError> {
+        self.insert_table_next_to(path, sibling, table, true)
+    }
+
+    /// Inserts `table` at the key path `path`, right before the table or array of tables `sibling`
+    ///
+    /// See [`DocumentMut::insert_table_after`].
+    pub fn insert_table_before(
+        &mut self,
+        path: &[&str],
+        sibling: &str,
+        table: Table,
+    ) -> Result<(), EditError> {
+        self.insert_table_next_to(path, sibling, table, false)
+    }
+
+    /// Moves the table or array of tables at the key path `path` to the end of the document
+    ///
+    /// Tables nested in it move along with it.  Positions are renumbered, see
+    /// [`DocumentMut::renumber_positions`].
+    ///
+    /// On error, the document is left unchanged.
+    pub fn move_to_end(&mut self, path: &[&str]) -> Result<(), EditError> {
+        let item = get_item(self.as_table(), path)?;
+        if !(item.is_table() || item.is_array_of_tables()) {
+            return Err(EditError::NotATable(EditError::path(path)));
+        }
+
+        let (parent, key) = split_last(path)?;
+        let end = count_tables(self.as_table());
+        let Parent::Table(parent) = items_mut(self.as_table_mut(), parent) else {
+            unreachable!("tables can't be nested in inline tables");
+        };
+        let item = &mut parent.items.get_mut(key).expect("validated above").value;
+        let mut next = end;
+        for_each_table_in_item(item, &mut |t| {
+            t.set_position(next);
+            next += 1;
+        });
+        self.renumber_positions();
+        Ok(())
+    }
+
+    /// Sets the [position][Table::position] of every table to consecutive values, in the order
+    /// they are rendered
+    ///
+    /// Tables without a position are rendered after the table before them, which this makes
+    /// explicit.  The rendered document doesn't change.
+    pub fn renumber_positions(&mut self) {
+        let mut last_position = 0;
+        let mut positions = Vec::new();
+        for_each_table(self.as_table_mut(), &mut |t| {
+            if let Some(position) = t.position() {
+                last_position = position;
+            }
+            positions.push(last_position);
+        });
+
+        let mut order = (0..positions.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| positions[i]);
+        let mut ranks = vec![0; order.len()];
+        for (rank, i) in order.into_iter().enumerate() {
+            ranks[i] = rank;
+        }
+
+        let mut ranks = ranks.into_iter();
+        for_each_table(self.as_table_mut(), &mut |t| {
+            t.set_position(ranks.next().expect("same traversal"));
+        });
+    }
+
+    fn insert_table_next_to(
+        &mut self,
+        path: &[&str],
+        sibling: &str,
+        mut table: Table,
+        after: bool,
+    ) -> Result<(), EditError> {
+        let (parent_path, key) = split_last(path)?;
+        check_vacant(self.as_table(), path)?;
+        let mut sibling_path = parent_path.to_vec();
+        sibling_path.push(sibling);
+        let item = get_item(self.as_table(), &sibling_path)?;
+        if !(item.is_table() || item.is_array_of_tables()) {
+            return Err(EditError::NotATable(EditError::path(&sibling_path)));
+        }
+
+        self.renumber_positions();
+        let Parent::Table(parent) = items_mut(self.as_table_mut(), parent_path) else {
+            unreachable!("tables can't be nested in inline tables");
+        };
+        let mut sibling_positions = Vec::new();
+        let sibling_item = &mut parent
+            .items
+            .get_mut(sibling)
+            .expect("validated above")
+            .value;
+        for_each_table_in_item(sibling_item, &mut |t| {
+            sibling_positions.extend(t.position());
+        });
+        let start = if after {
+            sibling_positions.iter().max().map(|p| p + 1)
+        } else {
+            sibling_positions.iter().min().copied()
+        }
+        .unwrap_or_default();
+
+        // Make room for the new tables
+        let added = count_tables(&table);
+        for_each_table(self.as_table_mut(), &mut |t| {
+            if let Some(position) = t.position().filter(|p| start <= *p) {
+                t.set_position(position + added);
+            }
+        });
+        let mut next = start;
+        for_each_table(&mut table, &mut |t| {
+            t.set_position(next);
+            next += 1;
+        });
+
+        let Parent::Table(parent) = items_mut(self.as_table_mut(), parent_path) else {
+            unreachable!("tables can't be nested in inline tables");
+        };
+        // Clear out any placeholder
+        parent.items.shift_remove(key);
+        let index = parent.items.get_index_of(sibling).expect("validated above");
+        let index = if after { index + 1 } else { index };
+        parent.items.insert(
+            key.into(),
+            TableKeyValue::new(Key::new(key), Item::Table(table)),
+        );
+        parent.items.move_index(parent.items.len() - 1, index);
+        Ok(())
+    }
+}
+
+// Visits the tables that are rendered with a header, in the order the encoder walks them
+pub(crate) fn for_each_table(table: &mut Table, f: &mut dyn FnMut(&mut Table)) {
+    if !table.is_dotted() {
+        f(table);
+    }
+    for kv in table.items.values_mut() {
+        for_each_table_in_item(&mut kv.value, f);
+    }
+}
+
+fn for_each_table_in_item(item: &mut Item, f: &mut dyn FnMut(&mut Table)) {
+    match item {
+        Item::Table(t) => for_each_table(t, f),
+        Item::ArrayOfTables(a) => {
+            for t in a.iter_mut() {
+                for_each_table(t, f);
+            }
+        }
+        Item::None | Item::Value(_) => {}
+    }
+}
+
+fn count_tables(table: &Table) -> usize {
+    let mut count = usize::from(!table.is_dotted());
+    for kv in table.items.values() {
+        count += match &kv.value {
+            Item::Table(t) => count_tables(t),
+            Item::ArrayOfTables(a) => a.iter().map(count_tables).sum(),
+            Item::None | Item::Value(_) => 0,
+        };
+    }
+    count
+}
+
+enum Parent<'t> {
+    Table(&'t mut Table),
+    Inline(&'t mut InlineTable),
+}
+
+impl<'t> Parent<'t> {
+    fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline(_))
+    }
+
+    fn into_items(self) -> &'t mut KeyValuePairs {
+        match self {
+            Self::Table(t) => &mut t.items,
+            Self::Inline(t) => &mut t.items,
+        }
+    }
+}
+
+fn split_last<'p>(path: &'p [&'p str]) -> Result<(&'p [&'p str], &'p str), EditError> {
+    path.split_last()
+        .map(|(last, parent)| (parent, *last))
+        .ok_or(EditError::EmptyPath)
+}
+
+fn get_item<'t>(root: &'t Table, path: &[&str]) -> Result<&'t Item, EditError> {
+    let mut items = &root.items;
+    let mut found: Option<&'t Item> = None;
+    for (i, key) in path.iter().enumerate() {
+        if let Some(item) = found {
+            items = match item {
+                Item::Table(t) => &t.items,
+                Item::Value(Value::InlineTable(t)) => &t.items,
+                _ => return Err(EditError::NotATable(EditError::path(&path[..i]))),
+            };
+        }
+        let item = items
+            .get(*key)
+            .map(|kv| &kv.value)
+            .filter(|item| !item.is_none())
+            .ok_or_else(|| EditError::NotFound(EditError::path(&path[..=i])))?;
+        found = Some(item);
+    }
+    found.ok_or(EditError::EmptyPath)
+}
+
+// Ensure `path` is available for inserting, creating any missing parents
+fn check_vacant(root: &Table, path: &[&str]) -> Result<(), EditError> {
+    match get_item(root, path) {
+        Ok(_) => Err(EditError::AlreadyExists(EditError::path(path))),
+        Err(EditError::NotFound(_)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+// Assumes `path` was validated with `get_item`
+fn items_mut<'t>(root: &'t mut Table, path: &[&str]) -> Parent<'t> {
+    let mut parent = Parent::Table(root);
+    for key in path {
+        let item = &mut parent
+            .into_items()
+            .get_mut(*key)
+            .expect("validated by caller")
+            .value;
+        parent = match item {
+            Item::Table(t) => Parent::Table(t),
+            Item::Value(Value::InlineTable(t)) => Parent::Inline(t),
+            _ => unreachable!("validated by caller"),
+        };
+    }
+    parent
+}
+
+// Assumes `path` was validated with `check_vacant`
+fn create_parents<'t>(root: &'t mut Table, path: &[&str]) -> Parent<'t> {
+    let mut parent = Parent::Table(root);
+    for key in path {
+        let inline = parent.is_inline();
+        let dotted = match &parent {
+            Parent::Table(t) => t.is_dotted(),
+            Parent::Inline(_) => false,
+        };
+        let kv = parent
+            .into_items()
+            .entry((*key).into())
+            .or_insert_with(|| TableKeyValue::new(Key::new(*key), Item::None));
+        if kv.value.is_none() {
+            kv.value = if inline {
+                Item::Value(Value::InlineTable(InlineTable::new()))
+            } else {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                table.set_dotted(dotted);
+                Item::Table(table)
+            };
+        }
+        parent = match &mut kv.value {
+            Item::Table(t) => Parent::Table(t),
+            Item::Value(Value::InlineTable(t)) => Parent::Inline(t),
+            _ => unreachable!("validated by caller"),
+        };
+    }
+    parent
+}
+
+// Remove implicit tables along `path` that no longer have any children
+fn prune_implicit(root: &mut Table, path: &[&str]) {
+    for depth in (1..=path.len()).rev() {
+        let (parent, key) = path[..depth].split_last().map(|(k, p)| (p, *k)).unwrap();
+        let is_empty_implicit = match get_item(root, &path[..depth]) {
+            Ok(Item::Table(t)) => t.is_implicit() && t.is_empty(),
+            _ => false,
+        };
+        if !is_empty_implicit {
+            break;
+        }
+        items_mut(root, parent).into_items().shift_remove(key);
+    }
+}
+
+fn rekey(key: Key, new_key: &str) -> Key {
+    let mut renamed = Key::new(new_key);
+    renamed.leaf_decor = key.leaf_decor;
+    renamed.dotted_decor = key.dotted_decor;
+    renamed
+}
@@ -109,7 +109,16 @@ impl serde::ser::Serializer for ValueSerializer {
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_f64(v as f64)
+        // Widen through the shortest digits of `v`, so `0.1f32` is stored and written as `0.1`
+        // rather than `0.10000000149011612`
+        let v = if v.is_finite() {
+            v.to_string()
+                .parse::<f64>()
+                .expect("`f32` digits are a valid `f64`")
+        } else {
+            f64::from(v)
+        };
+        self.serialize_f64(v)
     }
 
     fn serialize_f64(self, mut v: f64) -> Result<Self::Ok, Self::Error> {
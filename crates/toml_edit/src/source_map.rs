@@ -0,0 +1,163 @@
+use std::fmt::{Result, Write};
+use std::ops::Range;
+
+use crate::encode::{Output, Segment};
+use crate::{DocumentMut, PathSegment};
+
+/// Where nodes were written in a rendered document
+///
+/// See [`DocumentMut::to_string_with_spans`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    spans: Vec<NodeSpan>,
+}
+
+impl SourceMap {
+    /// All recorded nodes, in the order they were written
+    pub fn iter(&self) -> std::slice::Iter<'_, NodeSpan> {
+        self.spans.iter()
+    }
+
+    /// The location of the first `kind` of node written for `path`
+    pub fn find(&self, kind: NodeKind, path: &[PathSegment]) -> Option<Range<usize>> {
+        self.spans
+            .iter()
+            .find(|s| s.kind == kind && s.path == path)
+            .map(|s| s.span.clone())
+    }
+
+    /// The location of the key for `path`, excluding surrounding whitespace
+    ///
+    /// For a dotted key, this covers all of its parts.
+    pub fn key(&self, path: &[PathSegment]) -> Option<Range<usize>> {
+        self.find(NodeKind::Key, path)
+    }
+
+    /// The location of the value at `path`, excluding surrounding whitespace and comments
+    pub fn value(&self, path: &[PathSegment]) -> Option<Range<usize>> {
+        self.find(NodeKind::Value, path)
+    }
+
+    /// The location of the header for the table at `path`, including the brackets
+    pub fn header(&self, path: &[PathSegment]) -> Option<Range<usize>> {
+        self.find(NodeKind::Header, path)
+    }
+}
+
+impl<'s> IntoIterator for &'s SourceMap {
+    type Item = &'s NodeSpan;
+    type IntoIter = std::slice::Iter<'s, NodeSpan>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The location of a node within rendered output
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeSpan {
+    kind: NodeKind,
+    path: Vec<PathSegment>,
+    span: Range<usize>,
+}
+
+impl NodeSpan {
+    /// What part of the document was written
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Keys and array indices leading to the node from the root table
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Byte range within the rendered output
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+/// What part of the document a [`NodeSpan`] refers to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// A key, whether in a key/value pair or a header
+    Key,
+    /// A value, including arrays and inline tables and the values within them
+    Value,
+    /// A table header, `[table]`, or the header of an array of tables entry, `[[array]]`
+    Header,
+}
+
+/// Source maps
+impl DocumentMut {
+    /// Renders the document like [`to_string`][ToString::to_string], recording where each key,
+    /// value and table header was written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{value, DocumentMut, PathSegment};
+    /// let mut doc = "[[bin]]\nname = \"foo\"\n".parse::<DocumentMut>().unwrap();
+    /// doc["bin"][0]["path"] = value("src/main.rs");
+    ///
+    /// let (output, map) = doc.to_string_with_spans();
+    /// let path = ["bin".into(), 0.into(), "path".into()];
+    /// let span = map.value(&path).unwrap();
+    /// assert_eq!(&output[span], "\"src/main.rs\"");
+    /// let span = map.header(&[PathSegment::from("bin"), 0.into()]).unwrap();
+    /// assert_eq!(&output[span], "[[bin]]");
+    /// # }
+    /// ```
+    pub fn to_string_with_spans(&self) -> (String, SourceMap) {
+        let mut recorder = Recorder::default();
+        if self.style().is_crlf() {
+            crate::encode::encode_document(self, &mut crate::encode::Crlf(&mut recorder))
+        } else {
+            crate::encode::encode_document(self, &mut recorder)
+        }
+        .expect("recording cannot fail");
+        (recorder.output, recorder.map)
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    output: String,
+    path: Vec<PathSegment>,
+    map: SourceMap,
+}
+
+impl Write for Recorder {
+    fn write_str(&mut self, s: &str) -> Result {
+        self.output.push_str(s);
+        Ok(())
+    }
+}
+
+impl Output for Recorder {
+    fn offset(&self) -> Option<usize> {
+        Some(self.output.len())
+    }
+
+    fn push_path(&mut self, segment: Segment<'_>) {
+        self.path.push(match segment {
+            Segment::Key(key) => PathSegment::Key(key.to_owned()),
+            Segment::Index(index) => PathSegment::Index(index),
+        });
+    }
+
+    fn pop_path(&mut self) {
+        self.path.pop();
+    }
+
+    fn record(&mut self, kind: NodeKind, start: usize) {
+        self.map.spans.push(NodeSpan {
+            kind,
+            path: self.path.clone(),
+            span: start..self.output.len(),
+        });
+    }
+}
@@ -0,0 +1,317 @@
+use crate::comment::raw;
+use crate::{Item, Table, Value};
+
+/// The formatting of the nodes of a [`DocumentMut`][crate::DocumentMut] that have none of their
+/// own
+///
+/// Keys, values, tables and array values created without explicit formatting, like those added
+/// with [`Table::insert`], [`Array::push`][crate::Array::push] and
+/// [`ArrayOfTables::push`][crate::ArrayOfTables::push], are written in this style.  Nodes with
+/// explicit formatting, like parsed ones, are written as they are.
+///
+/// When parsing a document, its style is inferred from the source, going with the most common
+/// choice for each setting, see [`DocumentMut::infer_style`][crate::DocumentMut::infer_style].
+/// Other documents start out in the default style.  Either can be overridden with
+/// [`DocumentMut::set_style`][crate::DocumentMut::set_style].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Style {
+    crlf: bool,
+    body_indents: Vec<String>,
+    header_indents: Vec<String>,
+    blank_lines: usize,
+    inline_table_padding: bool,
+    array_padding: bool,
+    literal_strings: bool,
+}
+
+impl Style {
+    /// Default style: `\n` newlines, no indentation, a blank line before each table header,
+    /// `{ a = 1 }`, `[1, 2]` and `"basic strings"`
+    pub fn new() -> Self {
+        Self {
+            crlf: false,
+            body_indents: Vec::new(),
+            header_indents: Vec::new(),
+            blank_lines: 1,
+            inline_table_padding: true,
+            array_padding: false,
+            literal_strings: false,
+        }
+    }
+
+    /// Whether to end lines with `\r\n` rather than `\n`
+    pub fn crlf(mut self, yes: bool) -> Self {
+        self.crlf = yes;
+        self
+    }
+
+    /// The indentation of the key/value lines of tables `depth` levels below the root
+    ///
+    /// The root's lines are at depth 0, those of `[a]` at depth 1 and those of `[a.b]` at
+    /// depth 2.  Deeper tables than the deepest one set go on by the same step.
+    pub fn body_indent(mut self, depth: usize, indent: &str) -> Self {
+        set_indent(&mut self.body_indents, depth, indent);
+        self
+    }
+
+    /// The indentation of the headers of tables `depth` levels below the root
+    ///
+    /// `[a]` is at depth 1 and `[a.b]` at depth 2.  Deeper tables than the deepest one set go on
+    /// by the same step.
+    pub fn header_indent(mut self, depth: usize, indent: &str) -> Self {
+        set_indent(&mut self.header_indents, depth, indent);
+        self
+    }
+
+    /// How many blank lines to put before each table header, except the first
+    pub fn blank_lines_between_tables(mut self, count: usize) -> Self {
+        self.blank_lines = count;
+        self
+    }
+
+    /// Whether to put spaces inside the braces of inline tables, like `{ a = 1 }`
+    pub fn inline_table_padding(mut self, yes: bool) -> Self {
+        self.inline_table_padding = yes;
+        self
+    }
+
+    /// Whether to put spaces inside the brackets of arrays, like `[ 1, 2 ]`
+    pub fn array_padding(mut self, yes: bool) -> Self {
+        self.array_padding = yes;
+        self
+    }
+
+    /// Whether to write strings as `'literal strings'` where possible
+    pub fn literal_strings(mut self, yes: bool) -> Self {
+        self.literal_strings = yes;
+        self
+    }
+
+    pub(crate) fn is_crlf(&self) -> bool {
+        self.crlf
+    }
+
+    pub(crate) fn body_indent_at(&self, depth: usize) -> String {
+        indent_at(&self.body_indents, depth)
+    }
+
+    /// The default prefix of a table header
+    pub(crate) fn header_prefix(&self, depth: usize, first: bool) -> String {
+        let mut prefix = if first {
+            String::new()
+        } else {
+            "\n".repeat(self.blank_lines)
+        };
+        prefix.push_str(&indent_at(&self.header_indents, depth));
+        prefix
+    }
+
+    pub(crate) fn has_inline_table_padding(&self) -> bool {
+        self.inline_table_padding
+    }
+
+    pub(crate) fn has_array_padding(&self) -> bool {
+        self.array_padding
+    }
+
+    pub(crate) fn prefers_literal_strings(&self) -> bool {
+        self.literal_strings
+    }
+
+    /// The prevailing style of the nodes under `root`
+    ///
+    /// Line endings aren't kept in the nodes, so they are passed in.
+    pub(crate) fn infer(root: &Table, crlf: bool) -> Self {
+        let mut votes = Votes::default();
+        votes.table(root, 0);
+
+        // A header at the start of the document has no blank lines before it
+        votes.headers.sort_by_key(|&(position, _)| position);
+        let skip = usize::from(root.get_values().is_empty());
+        let mut blank_lines = Tally::default();
+        for (_, count) in votes.headers.iter().skip(skip) {
+            blank_lines.add(*count);
+        }
+
+        let default = Self::new();
+        Self {
+            crlf,
+            body_indents: votes.body_indents.iter().map(Tally::winner).collect(),
+            header_indents: votes.header_indents.iter().map(Tally::winner).collect(),
+            blank_lines: blank_lines.winner_or(default.blank_lines),
+            inline_table_padding: votes
+                .inline_table_padding
+                .winner_or(default.inline_table_padding),
+            array_padding: votes.array_padding.winner_or(default.array_padding),
+            literal_strings: votes.literal_strings.winner_or(default.literal_strings),
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn set_indent(indents: &mut Vec<String>, depth: usize, indent: &str) {
+    if indents.len() <= depth {
+        indents.resize(depth + 1, String::new());
+    }
+    indents[depth] = indent.to_owned();
+}
+
+// The indentation at `depth`, going on by the last step for depths past those that were set
+fn indent_at(indents: &[String], depth: usize) -> String {
+    if let Some(indent) = indents.get(depth) {
+        return indent.clone();
+    }
+    let Some((last, rest)) = indents.split_last() else {
+        return String::new();
+    };
+    let previous = rest.last().map(String::as_str).unwrap_or("");
+    let step = last.strip_prefix(previous).unwrap_or(last);
+    let mut indent = last.clone();
+    indent.push_str(&step.repeat(depth + 1 - indents.len()));
+    indent
+}
+
+// How often each choice is made
+struct Tally<T>(Vec<(T, usize)>);
+
+impl<T> Default for Tally<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: PartialEq + Clone + Default> Tally<T> {
+    fn add(&mut self, choice: T) {
+        if let Some((_, count)) = self.0.iter_mut().find(|(c, _)| *c == choice) {
+            *count += 1;
+        } else {
+            self.0.push((choice, 1));
+        }
+    }
+
+    // The most common choice, the earliest seen of them on a tie
+    fn winner_or(&self, default: T) -> T {
+        let mut winner = None;
+        for (choice, count) in &self.0 {
+            if winner.map(|(_, max)| max < *count).unwrap_or(true) {
+                winner = Some((choice, *count));
+            }
+        }
+        winner.map(|(choice, _)| choice.clone()).unwrap_or(default)
+    }
+
+    fn winner(&self) -> T {
+        self.winner_or(T::default())
+    }
+}
+
+#[derive(Default)]
+struct Votes {
+    // The position and number of blank lines before each header
+    headers: Vec<(Option<usize>, usize)>,
+    body_indents: Vec<Tally<String>>,
+    header_indents: Vec<Tally<String>>,
+    inline_table_padding: Tally<bool>,
+    array_padding: Tally<bool>,
+    literal_strings: Tally<bool>,
+}
+
+impl Votes {
+    fn table(&mut self, table: &Table, depth: usize) {
+        if !table.is_dotted() {
+            let is_visible = !(table.is_implicit() && table.get_values().is_empty());
+            if let (true, Some(prefix)) = (0 < depth && is_visible, raw(table.decor().prefix())) {
+                self.headers.push((table.position(), blank_lines(prefix)));
+                tally_at(&mut self.header_indents, depth).add(indent(prefix).to_owned());
+            }
+
+            for (path, value) in table.get_values() {
+                let leaf = path.last().expect("always at least one key").leaf_decor();
+                if let Some(prefix) = raw(leaf.prefix()) {
+                    tally_at(&mut self.body_indents, depth).add(indent(prefix).to_owned());
+                }
+                self.value(value);
+            }
+        }
+
+        for kv in table.items.values() {
+            match &kv.value {
+                Item::Table(table) => self.table(table, depth + 1),
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter() {
+                        self.table(table, depth + 1);
+                    }
+                }
+                Item::None | Item::Value(_) => {}
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::String(string) => {
+                let repr = string.as_repr().and_then(|r| r.as_raw().as_str());
+                if let Some(repr) = repr {
+                    self.literal_strings.add(repr.starts_with('\''));
+                }
+            }
+            Value::Array(array) => {
+                let is_single_line = raw(Some(array.trailing()))
+                    .map(|t| !t.contains('\n'))
+                    .unwrap_or(false)
+                    && array.iter().all(|v| {
+                        let prefix = raw(v.decor().prefix()).unwrap_or("");
+                        let suffix = raw(v.decor().suffix()).unwrap_or("");
+                        !prefix.contains('\n') && !suffix.contains('\n')
+                    });
+                let first = array.get(0).and_then(|v| raw(v.decor().prefix()));
+                if let (true, Some(first)) = (is_single_line, first) {
+                    self.array_padding.add(first.starts_with(' '));
+                }
+                for value in array.iter() {
+                    self.value(value);
+                }
+            }
+            Value::InlineTable(table) => {
+                let values = table.get_values();
+                if let Some((path, _)) = values.first() {
+                    let leaf = path.last().expect("always at least one key").leaf_decor();
+                    if let Some(prefix) = raw(leaf.prefix()) {
+                        self.inline_table_padding.add(prefix.starts_with(' '));
+                    }
+                }
+                for (_, value) in values {
+                    self.value(value);
+                }
+            }
+            Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Datetime(_) => {}
+        }
+    }
+}
+
+fn tally_at(tallies: &mut Vec<Tally<String>>, depth: usize) -> &mut Tally<String> {
+    if tallies.len() <= depth {
+        tallies.resize_with(depth + 1, Tally::default);
+    }
+    &mut tallies[depth]
+}
+
+// The whitespace on the last line of a prefix, before the node
+fn indent(prefix: &str) -> &str {
+    prefix.rsplit('\n').next().unwrap_or(prefix)
+}
+
+// The number of empty lines before the first comment of a prefix
+fn blank_lines(prefix: &str) -> usize {
+    let mut lines = prefix.split('\n').collect::<Vec<_>>();
+    lines.pop();
+    lines
+        .iter()
+        .take_while(|line| !line.trim_start().starts_with('#'))
+        .count()
+}
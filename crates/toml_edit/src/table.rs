@@ -5,13 +5,15 @@ use indexmap::map::IndexMap;
 use crate::key::Key;
 use crate::repr::Decor;
 use crate::value::DEFAULT_VALUE_DECOR;
-use crate::{InlineTable, InternalString, Item, KeyMut, Value};
+use crate::{InlineTable, InternalString, Item, KeyMut, RawString, Value};
 
 /// Type representing a TOML non-inline table
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Default)]
 pub struct Table {
     // Comments/spaces before and after the header
     pub(crate) decor: Decor,
+    // Comments/spaces after the last key/value pair
+    pub(crate) trailing: RawString,
     // Whether to hide an empty table
     pub(crate) implicit: bool,
     // Whether this is a proxy for dotted keys
@@ -34,17 +36,15 @@ impl Table {
     }
 
     pub(crate) fn with_pos(doc_position: Option<usize>) -> Self {
-        Self {
-            doc_position,
-            ..Default::default()
-        }
+        let mut table = Self::new();
+        table.doc_position = doc_position;
+        table
     }
 
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
-        Self {
-            items,
-            ..Default::default()
-        }
+        let mut table = Self::new();
+        table.items = items;
+        table
     }
 
     /// Convert to an inline table
@@ -52,7 +52,7 @@ impl Table {
         for (_, kv) in self.items.iter_mut() {
             kv.value.make_value();
         }
-        let mut t = InlineTable::with_pairs(self.items);
+        let mut t = InlineTable::with_pairs(std::mem::take(&mut self.items));
         t.fmt();
         t
     }
@@ -218,6 +218,98 @@ impl Table {
         &self.decor
     }
 
+    /// Set whitespace and comments after the last key/value pair
+    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
+        self.trailing = trailing.into();
+    }
+
+    /// Whitespace and comments after the last key/value pair
+    pub fn trailing(&self) -> &RawString {
+        &self.trailing
+    }
+
+    /// Returns the comment lines before the header, without the leading `#`
+    ///
+    /// When parsing, this includes the comments at the end of the previous table, which are
+    /// written before this header and so are removed along with it.
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::DocumentMut;
+    /// let mut doc = "[a]\n# about b\n[b] # inline\n".parse::<DocumentMut>().unwrap();
+    ///
+    /// let b = doc["b"].as_table_mut().unwrap();
+    /// assert_eq!(b.leading_comments(), vec!["about b"]);
+    /// assert_eq!(b.trailing_comment(), Some("inline"));
+    ///
+    /// b.set_leading_comments(["deprecated"]);
+    /// b.set_trailing_comment(None);
+    /// assert_eq!(doc.to_string(), "[a]\n# deprecated\n[b]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn leading_comments(&self) -> Vec<&str> {
+        self.decor.prefix_comments()
+    }
+
+    /// Replaces the comment lines before the header
+    ///
+    /// Blank lines before the comments are preserved.  Each line of a comment becomes its own `#`
+    /// comment line.
+    pub fn set_leading_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let comments = comments.into_iter().collect::<Vec<_>>();
+        self.decor
+            .set_prefix_comments(&comments, DEFAULT_TABLE_DECOR.0);
+    }
+
+    /// Returns the comment on the same line, after the header, without the leading `#`
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn trailing_comment(&self) -> Option<&str> {
+        self.decor.suffix_comment()
+    }
+
+    /// Sets or clears the comment on the same line, after the header
+    pub fn set_trailing_comment(&mut self, comment: Option<&str>) {
+        self.decor
+            .set_suffix_comment(comment, DEFAULT_TABLE_DECOR.1);
+    }
+
+    /// Returns the comment lines after the last key/value pair, without the leading `#`
+    ///
+    /// These are only set through [`Table::set_dangling_comments`].  When parsing, comments at the
+    /// end of a table are part of the next header's
+    /// [`leading_comments`][Table::leading_comments], or of
+    /// [`DocumentMut::trailing`][crate::DocumentMut::trailing] at the end of the document.
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    pub fn dangling_comments(&self) -> Vec<&str> {
+        self.trailing
+            .as_str()
+            .map(crate::comment::leading_comments)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the comment lines after the last key/value pair
+    pub fn set_dangling_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let comments = comments.into_iter().collect::<Vec<_>>();
+        let trailing = self.trailing.as_str().unwrap_or_default();
+        self.trailing = crate::comment::set_leading_comments(trailing, &comments).into();
+    }
+
     /// Returns an accessor to a key's formatting
     pub fn key(&self, key: &str) -> Option<&'_ Key> {
         self.items.get(key).map(|kv| &kv.key)
@@ -252,6 +344,7 @@ impl Table {
     pub(crate) fn despan(&mut self, input: &str) {
         self.span = None;
         self.decor.despan(input);
+        self.trailing.despan(input);
         for kv in self.items.values_mut() {
             kv.key.despan(input);
             kv.value.despan(input);
@@ -433,6 +526,45 @@ impl Table {
     }
 }
 
+impl Table {
+    // A copy of `self` holding `values`, in the order of `self.items`
+    pub(crate) fn clone_with(&self, values: Vec<Item>) -> Self {
+        Self {
+            decor: self.decor.clone(),
+            trailing: self.trailing.clone(),
+            implicit: self.implicit,
+            dotted: self.dotted,
+            doc_position: self.doc_position,
+            span: self.span.clone(),
+            items: clone_pairs(&self.items, values),
+        }
+    }
+}
+
+impl Clone for Table {
+    fn clone(&self) -> Self {
+        self.clone_with(crate::item::clone_items(
+            self.items.values().map(|kv| &kv.value),
+        ))
+    }
+}
+
+impl Drop for Table {
+    fn drop(&mut self) {
+        let items = std::mem::take(&mut self.items);
+        crate::item::drop_items(items.into_values().map(|kv| kv.value).collect());
+    }
+}
+
+// The keys of `items` paired with copies of their values
+pub(crate) fn clone_pairs(items: &KeyValuePairs, values: Vec<Item>) -> KeyValuePairs {
+    items
+        .iter()
+        .zip(values)
+        .map(|((k, kv), value)| (k.clone(), TableKeyValue::new(kv.key.clone(), value)))
+        .collect()
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -441,7 +573,7 @@ impl std::fmt::Display for Table {
         for (key_path, value) in children {
             crate::encode::encode_key_path_ref(&key_path, f, None, DEFAULT_KEY_DECOR)?;
             write!(f, "=")?;
-            crate::encode::encode_value(value, f, None, DEFAULT_VALUE_DECOR)?;
+            crate::encode::encode_value(value, f, None, DEFAULT_VALUE_DECOR, &Default::default())?;
             writeln!(f)?;
         }
         Ok(())
@@ -474,8 +606,12 @@ impl IntoIterator for Table {
     type Item = (InternalString, Item);
     type IntoIter = IntoIter;
 
-    fn into_iter(self) -> Self::IntoIter {
-        Box::new(self.items.into_iter().map(|(k, kv)| (k, kv.value)))
+    fn into_iter(mut self) -> Self::IntoIter {
+        Box::new(
+            std::mem::take(&mut self.items)
+                .into_iter()
+                .map(|(k, kv)| (k, kv.value)),
+        )
     }
 }
 
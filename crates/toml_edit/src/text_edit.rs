@@ -0,0 +1,188 @@
+use std::fmt::{Result, Write};
+use std::ops::Range;
+
+use crate::encode::Output;
+use crate::DocumentMut;
+
+/// Source edits
+impl DocumentMut {
+    /// Text replacements that turn `original`, the source this document was parsed from, into the
+    /// document as it is now
+    ///
+    /// Each edit is a byte range within `original` and the text to replace it with.  Edits are
+    /// sorted and do not overlap.  Keys, values, whitespace and comments are tracked from parsing
+    /// on, and any that were not modified or replaced since are never part of an edit.  Inserted
+    /// text uses `\r\n` for newlines if `original` does.
+    ///
+    /// If `original` is not the source of this document, the edits still produce
+    /// [`to_string`][ToString::to_string], just not minimally.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{value, DocumentMut};
+    /// let original = "[package]\nname = \"foo\"  # the name\nversion = \"0.1.0\"\n";
+    /// let mut doc = original.parse::<DocumentMut>().unwrap();
+    ///
+    /// doc["package"]["version"] = value("0.2.0");
+    /// let edits = doc.text_edits(original);
+    /// assert_eq!(edits, vec![(48..49, "2".to_owned())]);
+    /// # }
+    /// ```
+    pub fn text_edits(&self, original: &str) -> Vec<(Range<usize>, String)> {
+        let mut recorder = Recorder::default();
+        crate::encode::encode_document(self, &mut recorder).expect("recording cannot fail");
+
+        // Text can only be reused in the order it appears in `original`
+        let anchors = recorder
+            .pieces
+            .iter()
+            .enumerate()
+            .filter_map(|(i, piece)| match piece {
+                Piece::Origin { origin, text, .. }
+                    if original.get(origin.clone()) == Some(text) =>
+                {
+                    Some((i, origin.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let reused = longest_increasing(&anchors);
+
+        let crlf = original.contains("\r\n");
+        let mut edits = Vec::new();
+        let mut matched = 0;
+        let mut pending = String::new();
+        for (i, piece) in recorder.pieces.iter().enumerate() {
+            match piece {
+                Piece::Origin { origin, .. } if reused.contains(&i) => {
+                    push_edit(&mut edits, original, matched..origin.start, &pending, crlf);
+                    pending.clear();
+                    matched = origin.end;
+                }
+                Piece::Origin { rendered, .. } => pending.push_str(rendered),
+                Piece::Text(text) => pending.push_str(text),
+            }
+        }
+        push_edit(
+            &mut edits,
+            original,
+            matched..original.len(),
+            &pending,
+            crlf,
+        );
+        edits
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    pieces: Vec<Piece>,
+}
+
+enum Piece {
+    Text(String),
+    Origin {
+        origin: Range<usize>,
+        text: String,
+        rendered: String,
+    },
+}
+
+impl Write for Recorder {
+    fn write_str(&mut self, s: &str) -> Result {
+        match self.pieces.last_mut() {
+            Some(Piece::Text(text)) => text.push_str(s),
+            _ => self.pieces.push(Piece::Text(s.to_owned())),
+        }
+        Ok(())
+    }
+}
+
+impl Output for Recorder {
+    fn write_origin(&mut self, origin: Range<usize>, text: &str, rendered: &str) -> Result {
+        self.pieces.push(Piece::Origin {
+            origin,
+            text: text.to_owned(),
+            rendered: rendered.to_owned(),
+        });
+        Ok(())
+    }
+}
+
+/// Indices of the longest run of `anchors` whose origins are in increasing order
+fn longest_increasing(anchors: &[(usize, Range<usize>)]) -> std::collections::BTreeSet<usize> {
+    // Patience sorting: `tails[len]` is the anchor ending the best run of `len + 1` anchors
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; anchors.len()];
+    for (a, (_, origin)) in anchors.iter().enumerate() {
+        let len = tails.partition_point(|&t| anchors[t].1.end <= origin.start);
+        if 0 < len {
+            previous[a] = Some(tails[len - 1]);
+        }
+        if len == tails.len() {
+            tails.push(a);
+        } else {
+            tails[len] = a;
+        }
+    }
+
+    let mut reused = std::collections::BTreeSet::new();
+    let mut next = tails.last().copied();
+    while let Some(a) = next {
+        reused.insert(anchors[a].0);
+        next = previous[a];
+    }
+    reused
+}
+
+fn push_edit(
+    edits: &mut Vec<(Range<usize>, String)>,
+    original: &str,
+    range: Range<usize>,
+    new: &str,
+    crlf: bool,
+) {
+    let old = &original[range.clone()];
+    let new = if crlf { to_crlf(new) } else { new.to_owned() };
+
+    let prefix = common_prefix(old, &new);
+    let suffix = common_suffix(&old[prefix..], &new[prefix..]);
+    if prefix + suffix == old.len() && prefix + suffix == new.len() {
+        return;
+    }
+    let start = range.start + prefix;
+    let end = range.end - suffix;
+    edits.push((start..end, new[prefix..new.len() - suffix].to_owned()));
+}
+
+fn to_crlf(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if c == '\n' && previous != Some('\r') {
+            output.push('\r');
+        }
+        output.push(c);
+        previous = Some(c);
+    }
+    output
+}
+
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| a.len().min(b.len()))
+}
+
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .rev()
+        .zip(b.chars().rev())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, c), _)| a.len() - i - c.len_utf8())
+        .unwrap_or_else(|| a.len().min(b.len()))
+}
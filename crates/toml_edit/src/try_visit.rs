@@ -0,0 +1,339 @@
+#![allow(missing_docs)]
+
+//! Document tree traversal to walk a shared borrow of a document tree, stopping early.
+//!
+//! This is like [`visit`](crate::visit), except each method of the [`TryVisit`] trait returns a
+//! [`ControlFlow`].  Returning [`ControlFlow::Break`] from a hook stops the traversal and the
+//! break value is returned from the outermost call.
+//!
+//! To skip the children of a node, return [`ControlFlow::Continue`] from its hook without calling
+//! the default implementation.
+//!
+//! ```
+//! # use std::ops::ControlFlow;
+//! # use toml_edit::{Item, ArrayOfTables, Table, Value};
+//!
+//! pub trait TryVisit<'doc> {
+//!     type Break;
+//!
+//!     /* ... */
+//!
+//!     fn visit_item(&mut self, i: &'doc Item) -> ControlFlow<Self::Break> {
+//!         visit_item(self, i)
+//!     }
+//!
+//!     /* ... */
+//!     # fn visit_value(&mut self, i: &'doc Value) -> ControlFlow<Self::Break>;
+//!     # fn visit_table(&mut self, i: &'doc Table) -> ControlFlow<Self::Break>;
+//!     # fn visit_array_of_tables(&mut self, i: &'doc ArrayOfTables) -> ControlFlow<Self::Break>;
+//! }
+//!
+//! pub fn visit_item<'doc, V>(v: &mut V, node: &'doc Item) -> ControlFlow<V::Break>
+//! where
+//!     V: TryVisit<'doc> + ?Sized,
+//! {
+//!     match node {
+//!         Item::None => ControlFlow::Continue(()),
+//!         Item::Value(value) => v.visit_value(value),
+//!         Item::Table(table) => v.visit_table(table),
+//!         Item::ArrayOfTables(array) => v.visit_array_of_tables(array),
+//!     }
+//! }
+//! ```
+//!
+//! # Examples
+//!
+//! This visitor finds the first negative integer, ignoring anything under `[limits]`.
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # use std::ops::ControlFlow;
+//! # use toml_edit::*;
+//! use toml_edit::try_visit::*;
+//!
+//! struct FindNegative;
+//!
+//! impl<'doc> TryVisit<'doc> for FindNegative {
+//!     type Break = i64;
+//!
+//!     fn visit_table_like_kv(&mut self, key: &'doc str, node: &'doc Item) -> ControlFlow<i64> {
+//!         if key == "limits" {
+//!             // Skip the whole subtree
+//!             return ControlFlow::Continue(());
+//!         }
+//!         visit_table_like_kv(self, key, node)
+//!     }
+//!
+//!     fn visit_integer(&mut self, node: &'doc Formatted<i64>) -> ControlFlow<i64> {
+//!         if *node.value() < 0 {
+//!             return ControlFlow::Break(*node.value());
+//!         }
+//!         ControlFlow::Continue(())
+//!     }
+//! }
+//!
+//! let input = r#"
+//! limits = { min = -10 }
+//! values = [1, 2, -3, -4]
+//! "#;
+//!
+//! let document: DocumentMut = input.parse().unwrap();
+//! assert_eq!(FindNegative.visit_document(&document), ControlFlow::Break(-3));
+//! # }
+//! ```
+//!
+//! To report errors as a [`Result`], use the error as the `Break` type and match on the outcome.
+
+use std::ops::ControlFlow;
+
+use crate::visit::{KeyPath, PathStep};
+use crate::{
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, Formatted, InlineTable, Item, Key,
+    RawString, Table, TableLike, Value,
+};
+
+/// Document tree traversal to walk a shared borrow of a document tree, stopping early.
+///
+/// See the [module documentation](self) for details.
+pub trait TryVisit<'doc> {
+    /// The value that stops the traversal
+    type Break;
+
+    /// Where to track the path to the node being visited, see
+    /// [`Visit::key_path`](crate::visit::Visit::key_path)
+    fn key_path(&mut self) -> Option<&mut KeyPath<'doc>> {
+        None
+    }
+
+    fn visit_document(&mut self, node: &'doc DocumentMut) -> ControlFlow<Self::Break> {
+        visit_document(self, node)
+    }
+
+    fn visit_item(&mut self, node: &'doc Item) -> ControlFlow<Self::Break> {
+        visit_item(self, node)
+    }
+
+    fn visit_table(&mut self, node: &'doc Table) -> ControlFlow<Self::Break> {
+        visit_table(self, node)
+    }
+
+    fn visit_inline_table(&mut self, node: &'doc InlineTable) -> ControlFlow<Self::Break> {
+        visit_inline_table(self, node)
+    }
+
+    fn visit_table_like(&mut self, node: &'doc dyn TableLike) -> ControlFlow<Self::Break> {
+        visit_table_like(self, node)
+    }
+
+    fn visit_table_like_kv(
+        &mut self,
+        key: &'doc str,
+        node: &'doc Item,
+    ) -> ControlFlow<Self::Break> {
+        visit_table_like_kv(self, key, node)
+    }
+
+    /// Called for the key of each entry visited by
+    /// [`visit_table_like`](Self::visit_table_like), before
+    /// [`visit_table_like_kv`](Self::visit_table_like_kv)
+    fn visit_key(&mut self, node: &'doc Key) -> ControlFlow<Self::Break> {
+        visit_key(self, node)
+    }
+
+    fn visit_array(&mut self, node: &'doc Array) -> ControlFlow<Self::Break> {
+        visit_array(self, node)
+    }
+
+    fn visit_array_of_tables(&mut self, node: &'doc ArrayOfTables) -> ControlFlow<Self::Break> {
+        visit_array_of_tables(self, node)
+    }
+
+    fn visit_value(&mut self, node: &'doc Value) -> ControlFlow<Self::Break> {
+        visit_value(self, node)
+    }
+
+    fn visit_boolean(&mut self, node: &'doc Formatted<bool>) -> ControlFlow<Self::Break> {
+        visit_boolean(self, node)
+    }
+
+    fn visit_datetime(&mut self, node: &'doc Formatted<Datetime>) -> ControlFlow<Self::Break> {
+        visit_datetime(self, node)
+    }
+
+    fn visit_float(&mut self, node: &'doc Formatted<f64>) -> ControlFlow<Self::Break> {
+        visit_float(self, node)
+    }
+
+    fn visit_integer(&mut self, node: &'doc Formatted<i64>) -> ControlFlow<Self::Break> {
+        visit_integer(self, node)
+    }
+
+    fn visit_string(&mut self, node: &'doc Formatted<String>) -> ControlFlow<Self::Break> {
+        visit_string(self, node)
+    }
+
+    /// Called for the decor of each key, value and table
+    fn visit_decor(&mut self, node: &'doc Decor) -> ControlFlow<Self::Break> {
+        visit_decor(self, node)
+    }
+
+    /// Called for the whitespace and comments outside of any decor: after the last entry of a
+    /// table or document, after the last value of an array and before the first entry of an
+    /// inline table
+    fn visit_raw_string(&mut self, node: &'doc RawString) -> ControlFlow<Self::Break> {
+        visit_raw_string(self, node)
+    }
+}
+
+// `?` doesn't work on `ControlFlow` on stable
+macro_rules! tri {
+    ($flow: expr) => {
+        if let ControlFlow::Break(b) = $flow {
+            return ControlFlow::Break(b);
+        }
+    };
+}
+
+// Runs `f` with `step` on the path, even if it breaks
+fn with_step<'doc, V>(
+    v: &mut V,
+    step: PathStep<'doc>,
+    f: impl FnOnce(&mut V) -> ControlFlow<V::Break>,
+) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    if let Some(path) = v.key_path() {
+        path.push(step);
+    }
+    let flow = f(v);
+    if let Some(path) = v.key_path() {
+        path.pop();
+    }
+    flow
+}
+
+pub fn visit_document<'doc, V>(v: &mut V, node: &'doc DocumentMut) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    tri!(v.visit_table(node.as_table()));
+    v.visit_raw_string(node.trailing())
+}
+
+pub fn visit_item<'doc, V>(v: &mut V, node: &'doc Item) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    match node {
+        Item::None => ControlFlow::Continue(()),
+        Item::Value(value) => v.visit_value(value),
+        Item::Table(table) => v.visit_table(table),
+        Item::ArrayOfTables(array) => v.visit_array_of_tables(array),
+    }
+}
+
+pub fn visit_table<'doc, V>(v: &mut V, node: &'doc Table) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    tri!(v.visit_decor(node.decor()));
+    tri!(v.visit_table_like(node));
+    v.visit_raw_string(node.trailing())
+}
+
+pub fn visit_inline_table<'doc, V>(v: &mut V, node: &'doc InlineTable) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    tri!(v.visit_raw_string(node.preamble()));
+    v.visit_table_like(node)
+}
+
+pub fn visit_table_like<'doc, V>(v: &mut V, node: &'doc dyn TableLike) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    for (key, item) in node.iter() {
+        let (key, _) = node.get_key_value(key).expect("iterated");
+        tri!(with_step(v, PathStep::Key(key), |v| {
+            tri!(v.visit_key(key));
+            v.visit_table_like_kv(key.get(), item)
+        }));
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn visit_table_like_kv<'doc, V>(
+    v: &mut V,
+    _key: &'doc str,
+    node: &'doc Item,
+) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    v.visit_item(node)
+}
+
+pub fn visit_array<'doc, V>(v: &mut V, node: &'doc Array) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    for (index, value) in node.iter().enumerate() {
+        tri!(with_step(v, PathStep::Index(index), |v| v.visit_value(value)));
+    }
+    v.visit_raw_string(node.trailing())
+}
+
+pub fn visit_array_of_tables<'doc, V>(v: &mut V, node: &'doc ArrayOfTables) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    for (index, table) in node.iter().enumerate() {
+        tri!(with_step(v, PathStep::Index(index), |v| v.visit_table(table)));
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn visit_key<'doc, V>(v: &mut V, node: &'doc Key) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    tri!(v.visit_decor(node.leaf_decor()));
+    v.visit_decor(node.dotted_decor())
+}
+
+pub fn visit_value<'doc, V>(v: &mut V, node: &'doc Value) -> ControlFlow<V::Break>
+where
+    V: TryVisit<'doc> + ?Sized,
+{
+    tri!(v.visit_decor(node.decor()));
+    match node {
+        Value::String(s) => v.visit_string(s),
+        Value::Integer(i) => v.visit_integer(i),
+        Value::Float(f) => v.visit_float(f),
+        Value::Boolean(b) => v.visit_boolean(b),
+        Value::Datetime(dt) => v.visit_datetime(dt),
+        Value::Array(array) => v.visit_array(array),
+        Value::InlineTable(table) => v.visit_inline_table(table),
+    }
+}
+
+macro_rules! empty_visit {
+    ($name: ident, $t: ty) => {
+        fn $name<'doc, V>(_v: &mut V, _node: &'doc $t) -> ControlFlow<V::Break>
+        where
+            V: TryVisit<'doc> + ?Sized,
+        {
+            ControlFlow::Continue(())
+        }
+    };
+}
+
+empty_visit!(visit_boolean, Formatted<bool>);
+empty_visit!(visit_datetime, Formatted<Datetime>);
+empty_visit!(visit_float, Formatted<f64>);
+empty_visit!(visit_integer, Formatted<i64>);
+empty_visit!(visit_string, Formatted<String>);
+empty_visit!(visit_decor, Decor);
+empty_visit!(visit_raw_string, RawString);
@@ -0,0 +1,322 @@
+#![allow(missing_docs)]
+
+//! Document tree traversal to mutate an exclusive borrow of a document tree in place, stopping
+//! early.
+//!
+//! This is like [`visit_mut`](crate::visit_mut), except each method of the [`TryVisitMut`] trait
+//! returns a [`ControlFlow`].  Returning [`ControlFlow::Break`] from a hook stops the traversal
+//! and the break value is returned from the outermost call.  Changes made before then are kept.
+//!
+//! To skip the children of a node, return [`ControlFlow::Continue`] from its hook without calling
+//! the default implementation.
+//!
+//! # Examples
+//!
+//! This visitor bumps every `version`, failing on the first one that isn't a string.
+//!
+//! ```
+//! # #[cfg(feature = "parse")] {
+//! # #[cfg(feature = "display")] {
+//! # use std::ops::ControlFlow;
+//! # use toml_edit::*;
+//! use toml_edit::try_visit_mut::*;
+//!
+//! struct BumpVersions;
+//!
+//! impl TryVisitMut for BumpVersions {
+//!     type Break = String;
+//!
+//!     fn visit_table_like_kv_mut(&mut self, key: KeyMut<'_>, node: &mut Item) -> ControlFlow<String> {
+//!         if key.get() == "version" {
+//!             return match node.as_str() {
+//!                 Some(version) => {
+//!                     *node = value(format!("{version}.1"));
+//!                     ControlFlow::Continue(())
+//!                 }
+//!                 None => ControlFlow::Break(format!("`{}` is not a string", key.get())),
+//!             };
+//!         }
+//!         visit_table_like_kv_mut(self, key, node)
+//!     }
+//! }
+//!
+//! let mut document: DocumentMut = "a.version = \"1.0\"\nb.version = 2\n".parse().unwrap();
+//! let result = match BumpVersions.visit_document_mut(&mut document) {
+//!     ControlFlow::Continue(()) => Ok(()),
+//!     ControlFlow::Break(err) => Err(err),
+//! };
+//!
+//! assert_eq!(result, Err("`version` is not a string".to_owned()));
+//! assert_eq!(document.to_string(), "a.version = \"1.0.1\"\nb.version = 2\n");
+//! # }
+//! # }
+//! ```
+
+use std::ops::ControlFlow;
+
+use crate::visit_mut::KeyPathMut;
+use crate::{
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, Formatted, InlineTable, Item, KeyMut,
+    PathSegment, RawString, Table, TableLike, Value,
+};
+
+/// Document tree traversal to mutate an exclusive borrow of a document tree in-place, stopping
+/// early.
+///
+/// See the [module documentation](self) for details.
+pub trait TryVisitMut {
+    /// The value that stops the traversal
+    type Break;
+
+    /// Where to track the path to the node being visited, see
+    /// [`VisitMut::key_path`](crate::visit_mut::VisitMut::key_path)
+    fn key_path(&mut self) -> Option<&mut KeyPathMut> {
+        None
+    }
+
+    fn visit_document_mut(&mut self, node: &mut DocumentMut) -> ControlFlow<Self::Break> {
+        visit_document_mut(self, node)
+    }
+
+    fn visit_item_mut(&mut self, node: &mut Item) -> ControlFlow<Self::Break> {
+        visit_item_mut(self, node)
+    }
+
+    fn visit_table_mut(&mut self, node: &mut Table) -> ControlFlow<Self::Break> {
+        visit_table_mut(self, node)
+    }
+
+    fn visit_inline_table_mut(&mut self, node: &mut InlineTable) -> ControlFlow<Self::Break> {
+        visit_inline_table_mut(self, node)
+    }
+
+    /// [`visit_table_mut`](Self::visit_table_mut) and
+    /// [`visit_inline_table_mut`](Self::visit_inline_table_mut) both recurse into this method.
+    fn visit_table_like_mut(&mut self, node: &mut dyn TableLike) -> ControlFlow<Self::Break> {
+        visit_table_like_mut(self, node)
+    }
+
+    fn visit_table_like_kv_mut(
+        &mut self,
+        key: KeyMut<'_>,
+        node: &mut Item,
+    ) -> ControlFlow<Self::Break> {
+        visit_table_like_kv_mut(self, key, node)
+    }
+
+    /// Called for the key of each entry visited by
+    /// [`visit_table_like_mut`](Self::visit_table_like_mut), before
+    /// [`visit_table_like_kv_mut`](Self::visit_table_like_kv_mut)
+    fn visit_key_mut(&mut self, node: &mut KeyMut<'_>) -> ControlFlow<Self::Break> {
+        visit_key_mut(self, node)
+    }
+
+    fn visit_array_mut(&mut self, node: &mut Array) -> ControlFlow<Self::Break> {
+        visit_array_mut(self, node)
+    }
+
+    fn visit_array_of_tables_mut(&mut self, node: &mut ArrayOfTables) -> ControlFlow<Self::Break> {
+        visit_array_of_tables_mut(self, node)
+    }
+
+    fn visit_value_mut(&mut self, node: &mut Value) -> ControlFlow<Self::Break> {
+        visit_value_mut(self, node)
+    }
+
+    fn visit_boolean_mut(&mut self, node: &mut Formatted<bool>) -> ControlFlow<Self::Break> {
+        visit_boolean_mut(self, node)
+    }
+
+    fn visit_datetime_mut(&mut self, node: &mut Formatted<Datetime>) -> ControlFlow<Self::Break> {
+        visit_datetime_mut(self, node)
+    }
+
+    fn visit_float_mut(&mut self, node: &mut Formatted<f64>) -> ControlFlow<Self::Break> {
+        visit_float_mut(self, node)
+    }
+
+    fn visit_integer_mut(&mut self, node: &mut Formatted<i64>) -> ControlFlow<Self::Break> {
+        visit_integer_mut(self, node)
+    }
+
+    fn visit_string_mut(&mut self, node: &mut Formatted<String>) -> ControlFlow<Self::Break> {
+        visit_string_mut(self, node)
+    }
+
+    /// Called for the decor of each key, value and table
+    fn visit_decor_mut(&mut self, node: &mut Decor) -> ControlFlow<Self::Break> {
+        visit_decor_mut(self, node)
+    }
+
+    /// Called for the whitespace and comments outside of any decor: after the last entry of a
+    /// table or document, after the last value of an array and before the first entry of an
+    /// inline table
+    fn visit_raw_string_mut(&mut self, node: &mut RawString) -> ControlFlow<Self::Break> {
+        visit_raw_string_mut(self, node)
+    }
+}
+
+// `?` doesn't work on `ControlFlow` on stable
+macro_rules! tri {
+    ($flow: expr) => {
+        if let ControlFlow::Break(b) = $flow {
+            return ControlFlow::Break(b);
+        }
+    };
+}
+
+// Runs `f` with `segment` on the path, even if it breaks
+//
+// `segment` may be `None` when the path isn't being tracked, to avoid building it
+fn with_segment<V>(
+    v: &mut V,
+    segment: Option<PathSegment>,
+    f: impl FnOnce(&mut V) -> ControlFlow<V::Break>,
+) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    let tracked = segment.is_some();
+    if let (Some(path), Some(segment)) = (v.key_path(), segment) {
+        path.push(segment);
+    }
+    let flow = f(v);
+    if let (true, Some(path)) = (tracked, v.key_path()) {
+        path.pop();
+    }
+    flow
+}
+
+fn segment<V>(v: &mut V, segment: impl FnOnce() -> PathSegment) -> Option<PathSegment>
+where
+    V: TryVisitMut + ?Sized,
+{
+    v.key_path().map(|_| segment())
+}
+
+pub fn visit_document_mut<V>(v: &mut V, node: &mut DocumentMut) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    tri!(v.visit_table_mut(node.as_table_mut()));
+    v.visit_raw_string_mut(&mut node.trailing)
+}
+
+pub fn visit_item_mut<V>(v: &mut V, node: &mut Item) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    match node {
+        Item::None => ControlFlow::Continue(()),
+        Item::Value(value) => v.visit_value_mut(value),
+        Item::Table(table) => v.visit_table_mut(table),
+        Item::ArrayOfTables(array) => v.visit_array_of_tables_mut(array),
+    }
+}
+
+pub fn visit_table_mut<V>(v: &mut V, node: &mut Table) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    tri!(v.visit_decor_mut(node.decor_mut()));
+    tri!(v.visit_table_like_mut(node));
+    v.visit_raw_string_mut(&mut node.trailing)
+}
+
+pub fn visit_inline_table_mut<V>(v: &mut V, node: &mut InlineTable) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    tri!(v.visit_raw_string_mut(&mut node.preamble));
+    v.visit_table_like_mut(node)
+}
+
+pub fn visit_table_like_mut<V>(v: &mut V, node: &mut dyn TableLike) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    for (mut key, item) in node.iter_mut() {
+        let segment = segment(v, || PathSegment::Key(key.get().to_owned()));
+        tri!(with_segment(v, segment, |v| {
+            tri!(v.visit_key_mut(&mut key));
+            v.visit_table_like_kv_mut(key, item)
+        }));
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn visit_table_like_kv_mut<V>(
+    v: &mut V,
+    _key: KeyMut<'_>,
+    node: &mut Item,
+) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    v.visit_item_mut(node)
+}
+
+pub fn visit_array_mut<V>(v: &mut V, node: &mut Array) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    for (index, value) in node.iter_mut().enumerate() {
+        tri!(with_segment(v, Some(PathSegment::Index(index)), |v| v
+            .visit_value_mut(value)));
+    }
+    v.visit_raw_string_mut(&mut node.trailing)
+}
+
+pub fn visit_array_of_tables_mut<V>(v: &mut V, node: &mut ArrayOfTables) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    for (index, table) in node.iter_mut().enumerate() {
+        tri!(with_segment(v, Some(PathSegment::Index(index)), |v| v
+            .visit_table_mut(table)));
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn visit_key_mut<V>(v: &mut V, node: &mut KeyMut<'_>) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    tri!(v.visit_decor_mut(node.leaf_decor_mut()));
+    v.visit_decor_mut(node.dotted_decor_mut())
+}
+
+pub fn visit_value_mut<V>(v: &mut V, node: &mut Value) -> ControlFlow<V::Break>
+where
+    V: TryVisitMut + ?Sized,
+{
+    tri!(v.visit_decor_mut(node.decor_mut()));
+    match node {
+        Value::String(s) => v.visit_string_mut(s),
+        Value::Integer(i) => v.visit_integer_mut(i),
+        Value::Float(f) => v.visit_float_mut(f),
+        Value::Boolean(b) => v.visit_boolean_mut(b),
+        Value::Datetime(dt) => v.visit_datetime_mut(dt),
+        Value::Array(array) => v.visit_array_mut(array),
+        Value::InlineTable(table) => v.visit_inline_table_mut(table),
+    }
+}
+
+macro_rules! empty_visit_mut {
+    ($name: ident, $t: ty) => {
+        fn $name<V>(_v: &mut V, _node: &mut $t) -> ControlFlow<V::Break>
+        where
+            V: TryVisitMut + ?Sized,
+        {
+            ControlFlow::Continue(())
+        }
+    };
+}
+
+empty_visit_mut!(visit_boolean_mut, Formatted<bool>);
+empty_visit_mut!(visit_datetime_mut, Formatted<Datetime>);
+empty_visit_mut!(visit_float_mut, Formatted<f64>);
+empty_visit_mut!(visit_integer_mut, Formatted<i64>);
+empty_visit_mut!(visit_string_mut, Formatted<String>);
+empty_visit_mut!(visit_decor_mut, Decor);
+empty_visit_mut!(visit_raw_string_mut, RawString);
@@ -201,6 +201,57 @@ impl Value {
         self
     }
 
+    /// A string value quoted in `style`
+    ///
+    /// Fails when the string can't be written in `style`, see [`Formatted::set_style`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{StringStyle, Value};
+    ///
+    /// let v = Value::from_str_with_style(r"C:\Users", StringStyle::Literal).unwrap();
+    /// assert_eq!(v.to_string(), r"'C:\Users'");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn from_str_with_style(
+        value: impl Into<String>,
+        style: crate::StringStyle,
+    ) -> Result<Self, crate::EditError> {
+        let mut value = Formatted::new(value.into());
+        value.set_style(style)?;
+        Ok(Value::String(value))
+    }
+
+    /// Returns the comment on the same line, after the value, without the leading `#`
+    ///
+    /// This is for values of key/value pairs; for values within an array, see
+    /// [`Array::trailing_comment`].
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "key = 42  # the answer\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let value = doc["key"].as_value().unwrap();
+    /// assert_eq!(value.trailing_comment(), Some("the answer"));
+    /// # }
+    /// ```
+    pub fn trailing_comment(&self) -> Option<&str> {
+        self.decor().suffix_comment()
+    }
+
+    /// Sets or clears the comment on the same line, after the value
+    ///
+    /// This is for values of key/value pairs; for values within an array, see
+    /// [`Array::set_trailing_comment`].
+    pub fn set_trailing_comment(&mut self, comment: Option<&str>) {
+        self.decor_mut()
+            .set_suffix_comment(comment, DEFAULT_VALUE_DECOR.1);
+    }
+
     pub(crate) fn decorate(&mut self, prefix: impl Into<RawString>, suffix: impl Into<RawString>) {
         let decor = self.decor_mut();
         *decor = Decor::new(prefix, suffix);
@@ -354,7 +405,7 @@ impl<K: Into<Key>, V: Into<Value>> FromIterator<(K, V)> for Value {
 #[cfg(feature = "display")]
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_value(self, f, None, ("", ""))
+        crate::encode::encode_value(self, f, None, ("", ""), &Default::default())
     }
 }
 
@@ -73,16 +73,61 @@
 //!
 //! For a more complex example where the visitor has internal state, see `examples/visit.rs`
 //! [on GitHub](https://github.com/toml-rs/toml/blob/main/crates/toml_edit/examples/visit.rs).
+//!
+//! To stop the traversal early, see [`try_visit`](crate::try_visit).
 
 use crate::{
-    Array, ArrayOfTables, Datetime, DocumentMut, Formatted, InlineTable, Item, Table, TableLike,
-    Value,
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, Formatted, InlineTable, Item, Key,
+    PathSegment, RawString, Table, TableLike, Value,
 };
 
 /// Document tree traversal to mutate an exclusive borrow of a document tree in-place.
 ///
 /// See the [module documentation](self) for details.
 pub trait Visit<'doc> {
+    /// Where to track the path to the node being visited
+    ///
+    /// Return a [`KeyPath`] stored in the visitor to have it kept up to date while visiting.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # use toml_edit::*;
+    /// use toml_edit::visit::*;
+    ///
+    /// #[derive(Default)]
+    /// struct IntegerPaths<'doc> {
+    ///     path: KeyPath<'doc>,
+    ///     found: Vec<Vec<PathSegment>>,
+    /// }
+    ///
+    /// impl<'doc> Visit<'doc> for IntegerPaths<'doc> {
+    ///     fn key_path(&mut self) -> Option<&mut KeyPath<'doc>> {
+    ///         Some(&mut self.path)
+    ///     }
+    ///
+    ///     fn visit_integer(&mut self, _node: &'doc Formatted<i64>) {
+    ///         self.found.push(self.path.to_segments());
+    ///     }
+    /// }
+    ///
+    /// let document: DocumentMut = "a = [1, { b = 2 }]\n[[c]]\nd = 3\n".parse().unwrap();
+    /// let mut visitor = IntegerPaths::default();
+    /// visitor.visit_document(&document);
+    ///
+    /// assert_eq!(
+    ///     visitor.found,
+    ///     vec![
+    ///         vec!["a".into(), 0.into()],
+    ///         vec!["a".into(), 1.into(), "b".into()],
+    ///         vec!["c".into(), 0.into(), "d".into()],
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    fn key_path(&mut self) -> Option<&mut KeyPath<'doc>> {
+        None
+    }
+
     fn visit_document(&mut self, node: &'doc DocumentMut) {
         visit_document(self, node);
     }
@@ -107,6 +152,13 @@ pub trait Visit<'doc> {
         visit_table_like_kv(self, key, node);
     }
 
+    /// Called for the key of each entry visited by
+    /// [`visit_table_like`](Self::visit_table_like), before
+    /// [`visit_table_like_kv`](Self::visit_table_like_kv)
+    fn visit_key(&mut self, node: &'doc Key) {
+        visit_key(self, node);
+    }
+
     fn visit_array(&mut self, node: &'doc Array) {
         visit_array(self, node);
     }
@@ -138,6 +190,82 @@ pub trait Visit<'doc> {
     fn visit_string(&mut self, node: &'doc Formatted<String>) {
         visit_string(self, node);
     }
+
+    /// Called for the decor of each key, value and table
+    fn visit_decor(&mut self, node: &'doc Decor) {
+        visit_decor(self, node);
+    }
+
+    /// Called for the whitespace and comments outside of any decor: after the last entry of a
+    /// table or document, after the last value of an array and before the first entry of an
+    /// inline table
+    fn visit_raw_string(&mut self, node: &'doc RawString) {
+        visit_raw_string(self, node);
+    }
+}
+
+/// Keys and array indices leading to the node being visited, see [`Visit::key_path`]
+#[derive(Clone, Debug, Default)]
+pub struct KeyPath<'doc> {
+    steps: Vec<PathStep<'doc>>,
+}
+
+impl<'doc> KeyPath<'doc> {
+    /// An empty path, for the root table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Each step from the root table
+    pub fn steps(&self) -> &[PathStep<'doc>] {
+        &self.steps
+    }
+
+    /// The keys and indices of each step
+    pub fn to_segments(&self) -> Vec<PathSegment> {
+        self.steps
+            .iter()
+            .map(|step| match step {
+                PathStep::Key(key) => PathSegment::Key(key.get().to_owned()),
+                PathStep::Index(index) => PathSegment::Index(*index),
+            })
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, step: PathStep<'doc>) {
+        self.steps.push(step);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.steps.pop();
+    }
+}
+
+/// One step of a [`KeyPath`]
+#[derive(Copy, Clone, Debug)]
+pub enum PathStep<'doc> {
+    /// An entry of a table, with its formatting
+    Key(&'doc Key),
+    /// An element of an array or an array of tables
+    Index(usize),
+}
+
+fn push<'doc, V>(v: &mut V, step: PathStep<'doc>)
+where
+    V: Visit<'doc> + ?Sized,
+{
+    if let Some(path) = v.key_path() {
+        path.push(step);
+    }
+}
+
+fn pop<'doc, V>(v: &mut V)
+where
+    V: Visit<'doc> + ?Sized,
+{
+    if let Some(path) = v.key_path() {
+        path.pop();
+    }
 }
 
 pub fn visit_document<'doc, V>(v: &mut V, node: &'doc DocumentMut)
@@ -145,6 +273,7 @@ where
     V: Visit<'doc> + ?Sized,
 {
     v.visit_table(node.as_table());
+    v.visit_raw_string(node.trailing());
 }
 
 pub fn visit_item<'doc, V>(v: &mut V, node: &'doc Item)
@@ -163,13 +292,16 @@ pub fn visit_table<'doc, V>(v: &mut V, node: &'doc Table)
 where
     V: Visit<'doc> + ?Sized,
 {
+    v.visit_decor(node.decor());
     v.visit_table_like(node);
+    v.visit_raw_string(node.trailing());
 }
 
 pub fn visit_inline_table<'doc, V>(v: &mut V, node: &'doc InlineTable)
 where
     V: Visit<'doc> + ?Sized,
 {
+    v.visit_raw_string(node.preamble());
     v.visit_table_like(node);
 }
 
@@ -178,7 +310,11 @@ where
     V: Visit<'doc> + ?Sized,
 {
     for (key, item) in node.iter() {
-        v.visit_table_like_kv(key, item);
+        let (key, _) = node.get_key_value(key).expect("iterated");
+        push(v, PathStep::Key(key));
+        v.visit_key(key);
+        v.visit_table_like_kv(key.get(), item);
+        pop(v);
     }
 }
 
@@ -193,24 +329,38 @@ pub fn visit_array<'doc, V>(v: &mut V, node: &'doc Array)
 where
     V: Visit<'doc> + ?Sized,
 {
-    for value in node.iter() {
+    for (index, value) in node.iter().enumerate() {
+        push(v, PathStep::Index(index));
         v.visit_value(value);
+        pop(v);
     }
+    v.visit_raw_string(node.trailing());
 }
 
 pub fn visit_array_of_tables<'doc, V>(v: &mut V, node: &'doc ArrayOfTables)
 where
     V: Visit<'doc> + ?Sized,
 {
-    for table in node.iter() {
+    for (index, table) in node.iter().enumerate() {
+        push(v, PathStep::Index(index));
         v.visit_table(table);
+        pop(v);
     }
 }
 
+pub fn visit_key<'doc, V>(v: &mut V, node: &'doc Key)
+where
+    V: Visit<'doc> + ?Sized,
+{
+    v.visit_decor(node.leaf_decor());
+    v.visit_decor(node.dotted_decor());
+}
+
 pub fn visit_value<'doc, V>(v: &mut V, node: &'doc Value)
 where
     V: Visit<'doc> + ?Sized,
 {
+    v.visit_decor(node.decor());
     match node {
         Value::String(s) => v.visit_string(s),
         Value::Integer(i) => v.visit_integer(i),
@@ -237,3 +387,5 @@ empty_visit!(visit_datetime, Formatted<Datetime>);
 empty_visit!(visit_float, Formatted<f64>);
 empty_visit!(visit_integer, Formatted<i64>);
 empty_visit!(visit_string, Formatted<String>);
+empty_visit!(visit_decor, Decor);
+empty_visit!(visit_raw_string, RawString);
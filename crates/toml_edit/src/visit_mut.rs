@@ -88,16 +88,58 @@
 //!
 //! For a more complex example where the visitor has internal state, see `examples/visit.rs`
 //! [on GitHub](https://github.com/toml-rs/toml/blob/main/crates/toml_edit/examples/visit.rs).
+//!
+//! To stop the traversal early, see [`try_visit_mut`](crate::try_visit_mut).
 
 use crate::{
-    Array, ArrayOfTables, Datetime, DocumentMut, Formatted, InlineTable, Item, KeyMut, Table,
-    TableLike, Value,
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, Formatted, InlineTable, Item, KeyMut,
+    PathSegment, RawString, Table, TableLike, Value,
 };
 
 /// Document tree traversal to mutate an exclusive borrow of a document tree in-place.
 ///
 /// See the [module documentation](self) for details.
 pub trait VisitMut {
+    /// Where to track the path to the node being visited
+    ///
+    /// Return a [`KeyPathMut`] stored in the visitor to have it kept up to date while visiting.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// # use toml_edit::*;
+    /// use toml_edit::visit_mut::*;
+    ///
+    /// #[derive(Default)]
+    /// struct DocumentPaths {
+    ///     path: KeyPathMut,
+    /// }
+    ///
+    /// impl VisitMut for DocumentPaths {
+    ///     fn key_path(&mut self) -> Option<&mut KeyPathMut> {
+    ///         Some(&mut self.path)
+    ///     }
+    ///
+    ///     fn visit_string_mut(&mut self, node: &mut Formatted<String>) {
+    ///         let path = self.path.segments().iter().map(|s| match s {
+    ///             PathSegment::Key(k) => k.clone(),
+    ///             PathSegment::Index(i) => i.to_string(),
+    ///         });
+    ///         *node = Formatted::new(path.collect::<Vec<_>>().join("."));
+    ///     }
+    /// }
+    ///
+    /// let mut document: DocumentMut = "a = [\"\", { b = \"\" }]\n".parse().unwrap();
+    /// DocumentPaths::default().visit_document_mut(&mut document);
+    ///
+    /// assert_eq!(document.to_string(), "a = [\"a.0\", { b = \"a.1.b\" }]\n");
+    /// # }
+    /// # }
+    /// ```
+    fn key_path(&mut self) -> Option<&mut KeyPathMut> {
+        None
+    }
+
     fn visit_document_mut(&mut self, node: &mut DocumentMut) {
         visit_document_mut(self, node);
     }
@@ -124,6 +166,13 @@ pub trait VisitMut {
         visit_table_like_kv_mut(self, key, node);
     }
 
+    /// Called for the key of each entry visited by
+    /// [`visit_table_like_mut`](Self::visit_table_like_mut), before
+    /// [`visit_table_like_kv_mut`](Self::visit_table_like_kv_mut)
+    fn visit_key_mut(&mut self, node: &mut KeyMut<'_>) {
+        visit_key_mut(self, node);
+    }
+
     fn visit_array_mut(&mut self, node: &mut Array) {
         visit_array_mut(self, node);
     }
@@ -155,6 +204,68 @@ pub trait VisitMut {
     fn visit_string_mut(&mut self, node: &mut Formatted<String>) {
         visit_string_mut(self, node);
     }
+
+    /// Called for the decor of each key, value and table
+    fn visit_decor_mut(&mut self, node: &mut Decor) {
+        visit_decor_mut(self, node);
+    }
+
+    /// Called for the whitespace and comments outside of any decor: after the last entry of a
+    /// table or document, after the last value of an array and before the first entry of an
+    /// inline table
+    fn visit_raw_string_mut(&mut self, node: &mut RawString) {
+        visit_raw_string_mut(self, node);
+    }
+}
+
+/// Keys and array indices leading to the node being visited, see [`VisitMut::key_path`]
+///
+/// Unlike [`visit::KeyPath`](crate::visit::KeyPath), this holds copies of the keys rather than
+/// the [`Key`](crate::Key)s themselves, as they are borrowed mutably while visiting.  The key of
+/// the entry being visited is passed to
+/// [`visit_table_like_kv_mut`](VisitMut::visit_table_like_kv_mut) and
+/// [`visit_key_mut`](VisitMut::visit_key_mut).
+#[derive(Clone, Debug, Default)]
+pub struct KeyPathMut {
+    segments: Vec<PathSegment>,
+}
+
+impl KeyPathMut {
+    /// An empty path, for the root table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The key or index of each step from the root table
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub(crate) fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.segments.pop();
+    }
+}
+
+fn push<V>(v: &mut V, segment: impl FnOnce() -> PathSegment)
+where
+    V: VisitMut + ?Sized,
+{
+    if let Some(path) = v.key_path() {
+        path.push(segment());
+    }
+}
+
+fn pop<V>(v: &mut V)
+where
+    V: VisitMut + ?Sized,
+{
+    if let Some(path) = v.key_path() {
+        path.pop();
+    }
 }
 
 pub fn visit_document_mut<V>(v: &mut V, node: &mut DocumentMut)
@@ -162,6 +273,7 @@ where
     V: VisitMut + ?Sized,
 {
     v.visit_table_mut(node.as_table_mut());
+    v.visit_raw_string_mut(&mut node.trailing);
 }
 
 pub fn visit_item_mut<V>(v: &mut V, node: &mut Item)
@@ -180,13 +292,16 @@ pub fn visit_table_mut<V>(v: &mut V, node: &mut Table)
 where
     V: VisitMut + ?Sized,
 {
+    v.visit_decor_mut(node.decor_mut());
     v.visit_table_like_mut(node);
+    v.visit_raw_string_mut(&mut node.trailing);
 }
 
 pub fn visit_inline_table_mut<V>(v: &mut V, node: &mut InlineTable)
 where
     V: VisitMut + ?Sized,
 {
+    v.visit_raw_string_mut(&mut node.preamble);
     v.visit_table_like_mut(node);
 }
 
@@ -194,8 +309,11 @@ pub fn visit_table_like_mut<V>(v: &mut V, node: &mut dyn TableLike)
 where
     V: VisitMut + ?Sized,
 {
-    for (key, item) in node.iter_mut() {
+    for (mut key, item) in node.iter_mut() {
+        push(v, || PathSegment::Key(key.get().to_owned()));
+        v.visit_key_mut(&mut key);
         v.visit_table_like_kv_mut(key, item);
+        pop(v);
     }
 }
 
@@ -210,24 +328,38 @@ pub fn visit_array_mut<V>(v: &mut V, node: &mut Array)
 where
     V: VisitMut + ?Sized,
 {
-    for value in node.iter_mut() {
+    for (index, value) in node.iter_mut().enumerate() {
+        push(v, || PathSegment::Index(index));
         v.visit_value_mut(value);
+        pop(v);
     }
+    v.visit_raw_string_mut(&mut node.trailing);
 }
 
 pub fn visit_array_of_tables_mut<V>(v: &mut V, node: &mut ArrayOfTables)
 where
     V: VisitMut + ?Sized,
 {
-    for table in node.iter_mut() {
+    for (index, table) in node.iter_mut().enumerate() {
+        push(v, || PathSegment::Index(index));
         v.visit_table_mut(table);
+        pop(v);
     }
 }
 
+pub fn visit_key_mut<V>(v: &mut V, node: &mut KeyMut<'_>)
+where
+    V: VisitMut + ?Sized,
+{
+    v.visit_decor_mut(node.leaf_decor_mut());
+    v.visit_decor_mut(node.dotted_decor_mut());
+}
+
 pub fn visit_value_mut<V>(v: &mut V, node: &mut Value)
 where
     V: VisitMut + ?Sized,
 {
+    v.visit_decor_mut(node.decor_mut());
     match node {
         Value::String(s) => v.visit_string_mut(s),
         Value::Integer(i) => v.visit_integer_mut(i),
@@ -254,3 +386,5 @@ empty_visit_mut!(visit_datetime_mut, Formatted<Datetime>);
 empty_visit_mut!(visit_float_mut, Formatted<f64>);
 empty_visit_mut!(visit_integer_mut, Formatted<i64>);
 empty_visit_mut!(visit_string_mut, Formatted<String>);
+empty_visit_mut!(visit_decor_mut, Decor);
+empty_visit_mut!(visit_raw_string_mut, RawString);
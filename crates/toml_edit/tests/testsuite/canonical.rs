@@ -0,0 +1,84 @@
+use snapbox::assert_eq;
+use toml_edit::DocumentMut;
+
+fn canonical(input: &str) -> String {
+    input.parse::<DocumentMut>().unwrap().to_canonical_string()
+}
+
+#[test]
+fn canonical_values() {
+    assert_eq(
+        r#""bare key?" = "tab\tquote\"backslash\\nul\u0000"
+bool = false
+date = 1979-05-27T07:32:00.5-07:00
+empty = []
+float = [0.1, -0.0, 1.0, 100000000000000000000.0, inf, -inf, nan, nan]
+int = [255, 17, 1000]
+literal = "C:\\Users"
+local = [1979-05-27T07:32:00, 1979-05-27, 07:32:00]
+multiline = "a\nb"
+nested = [[1, 2], [{ a = 1 }, {}]]
+"#,
+        canonical(
+            r#"
+int = [0xff, 0o21, 1_000]
+float = [1e-1, -0.0, 1.0, 1e20, +inf, -inf, nan, -nan]
+literal = 'C:\Users'
+multiline = """
+a
+b"""
+"bare key?" = "tab\tquote\"backslash\\nul\u0000"
+bool = false
+date = 1979-05-27 07:32:00.500-07:00
+local = [1979-05-27T07:32:00.000, 1979-05-27, 07:32:00]
+empty = []
+nested = [ [ 1, 2 ], [ { a = 1 }, {} ] ]
+"#,
+        ),
+    );
+}
+
+#[test]
+fn canonical_tables() {
+    let expected = r#"a = 1
+[b]
+c = 2
+[b.d.e]
+[[b.f]]
+g = 3
+[[b.f]]
+[b.f.h]
+i = 4
+[j]
+"#;
+    assert_eq(
+        expected,
+        canonical(
+            r#"
+a = 1 # comment
+[j]
+[b]
+f = [{ g = 3 }, { h.i = 4 }]
+d = { e = {} }
+c = 2
+"#,
+        ),
+    );
+    assert_eq(
+        expected,
+        canonical(
+            r#"
+a = 1
+b.c = 2
+[b.d]
+e = {}
+[[b.f]]
+g = 3
+[[b.f]]
+h = { i = 4 }
+
+[j]
+"#,
+        ),
+    );
+}
@@ -0,0 +1,149 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use toml_edit::{value, DocumentMut, Item, Value};
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[track_caller]
+fn assert_same(a: &str, b: &str) {
+    let a = a.parse::<DocumentMut>().unwrap();
+    let b = b.parse::<DocumentMut>().unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[track_caller]
+fn assert_different(a: &str, b: &str) {
+    let a = a.parse::<DocumentMut>().unwrap();
+    let b = b.parse::<DocumentMut>().unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn formatting_is_ignored() {
+    assert_same(
+        r#"
+# comment
+a = 'x'   # trailing
+"b c" = [ 1,2, ]
+"#,
+        r#"'b c' = [1, 2]
+a = "x""#,
+    );
+    assert_same("hex = 0xff\nf = 1e2", "f = 100.0\nhex = 255");
+}
+
+#[test]
+fn table_kinds_are_ignored() {
+    let standard = r#"
+[package]
+name = "foo"
+
+[package.metadata]
+x = 1
+"#;
+    assert_same(
+        standard,
+        r#"package = { name = "foo", metadata = { x = 1 } }"#,
+    );
+    assert_same(standard, "package.name = \"foo\"\npackage.metadata.x = 1\n");
+    assert_same(
+        "[[bin]]\nname = \"a\"\n[[bin]]\nname = \"b\"\n",
+        "bin = [{ name = \"a\" }, { name = \"b\" }]",
+    );
+}
+
+#[test]
+fn data_is_compared() {
+    assert_different("a = 1", "a = 2");
+    assert_different("a = 1", "a = 1.0");
+    assert_different("a = 1", "a = 1\nb = 2");
+    assert_different("a = [1, 2]", "a = [2, 1]");
+    assert_different("a = {}", "a = []");
+    assert_different("[a]", "a = 1");
+}
+
+#[test]
+fn floats() {
+    assert_same("a = nan", "a = -nan");
+    assert_same("a = 0.0", "a = -0.0");
+    assert_different("a = inf", "a = -inf");
+}
+
+#[test]
+fn missing_items() {
+    let mut doc = "a = 1".parse::<DocumentMut>().unwrap();
+    doc["b"] = Item::None;
+    assert_eq!(doc, "a = 1".parse::<DocumentMut>().unwrap());
+    assert_eq!(Item::None, Item::None);
+    assert_ne!(Item::None, value(1));
+    assert_eq!(Value::from(1), Value::from(1));
+}
+
+#[test]
+fn hash_is_stable() {
+    // Records what is hashed, which doesn't depend on the build
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    let doc = "b = 1979-05-27T07:32:00Z\na = [true]"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let mut recorder = Recorder::default();
+    doc.hash(&mut recorder);
+
+    let mut expected = vec![7];
+    expected.extend(2u64.to_ne_bytes());
+    expected.extend(1u64.to_ne_bytes());
+    expected.extend(b"a");
+    expected.push(6);
+    expected.extend(1u64.to_ne_bytes());
+    expected.extend([4, 1]);
+    expected.extend(1u64.to_ne_bytes());
+    expected.extend(b"b");
+    expected.extend([5, 1]);
+    expected.extend(1979u16.to_ne_bytes());
+    expected.extend([5, 27, 1, 7, 32, 0]);
+    expected.extend(0u32.to_ne_bytes());
+    expected.push(1);
+    assert_eq!(recorder.0, expected);
+}
+
+#[test]
+fn exact() {
+    let parse = |s: &str| s.parse::<DocumentMut>().unwrap();
+    let original = "# comment\n[a]\nx = 1 # one\ny = 'two'\n";
+    assert!(parse(original).exact_eq(&parse(original)));
+
+    for other in [
+        "[a]\nx = 1 # one\ny = 'two'\n",
+        "# comment\n[a]\nx = 1\ny = 'two'\n",
+        "# comment\n[a]\ny = 'two'\nx = 1 # one\n",
+        "# comment\n[a]\nx = 1 # one\ny = \"two\"\n",
+        "# comment\n[a]\n'x' = 1 # one\ny = 'two'\n",
+        "# comment\na = { x = 1, y = 'two' }\n",
+    ] {
+        assert_eq!(parse(original), parse(other), "{other}");
+        assert!(!parse(original).exact_eq(&parse(other)), "{other}");
+    }
+
+    let mut doc = parse(original);
+    doc["a"]["x"] = value(1);
+    assert!(!doc.exact_eq(&parse(original)));
+    assert!(doc["a"]["y"].exact_eq(&parse(original)["a"]["y"]));
+}
@@ -0,0 +1,154 @@
+use toml_edit::{value, DocumentMut, ImDocument, Node, NodeMut, PathSegment};
+
+const INPUT: &str = r#"title = "example"
+
+[package]
+name = "foo"
+authors = ["a", "b"]
+meta = { x = 1, y.z = 2 }
+
+[[bin]]
+name = "first"
+
+[[bin]]
+name = "second"
+"#;
+
+fn keys(path: &[PathSegment]) -> Vec<String> {
+    path.iter()
+        .map(|s| match s {
+            PathSegment::Key(k) => k.clone(),
+            PathSegment::Index(i) => i.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn navigation() {
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let root = doc.cursor();
+    assert!(root.parent().is_none());
+    assert!(root.key().is_none());
+    assert_eq!(
+        root.children()
+            .map(|c| c.key().unwrap().get().to_owned())
+            .collect::<Vec<_>>(),
+        ["title", "package", "bin"]
+    );
+
+    let b = root
+        .child("package")
+        .and_then(|c| c.child("authors"))
+        .and_then(|c| c.child(1))
+        .unwrap();
+    assert_eq!(keys(b.key_path()), ["package", "authors", "1"]);
+    assert!(matches!(b.node(), Node::Value(v) if v.as_str() == Some("b")));
+    assert!(b.key().is_none());
+    assert!(b.next_sibling().is_none());
+    assert_eq!(
+        keys(b.prev_sibling().unwrap().key_path()),
+        ["package", "authors", "0"]
+    );
+    assert_eq!(
+        b.ancestors()
+            .map(|c| keys(c.key_path()).join("."))
+            .collect::<Vec<_>>(),
+        ["package.authors", "package", ""]
+    );
+
+    let z = root
+        .child("package")
+        .and_then(|c| c.child("meta"))
+        .and_then(|c| c.child("y"))
+        .and_then(|c| c.child("z"))
+        .unwrap();
+    assert!(matches!(z.node(), Node::Value(v) if v.as_integer() == Some(2)));
+    assert!(z.next_sibling().is_none());
+
+    let second = root.child("bin").and_then(|c| c.child(1)).unwrap();
+    assert!(matches!(second.node(), Node::Table(t) if t["name"].as_str() == Some("second")));
+    assert!(matches!(second.parent().unwrap().node(), Node::ArrayOfTables(a) if a.len() == 2));
+    assert!(root.child("missing").is_none());
+    assert!(root.child(0).is_none());
+}
+
+#[test]
+fn paths_survive_table_edits() {
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    let path = doc
+        .cursor()
+        .child("package")
+        .and_then(|c| c.child("name"))
+        .unwrap()
+        .key_path()
+        .to_vec();
+    assert_eq!(path, [PathSegment::from("package"), "name".into()]);
+
+    doc.as_table_mut().remove("title");
+    doc["package"]["edition"] = value("2021");
+    doc["package"].as_table_mut().unwrap().sort_values();
+
+    match doc.node_mut(&path) {
+        Some(NodeMut::Value(v)) => *v = "bar".into(),
+        node => panic!("unexpected {node:?}"),
+    }
+    let name = doc.cursor_at(&path).unwrap();
+    assert!(matches!(name.node(), Node::Value(v) if v.as_str() == Some("bar")));
+
+    assert!(doc.cursor_at(&[]).unwrap().parent().is_none());
+    doc["package"].as_table_mut().unwrap().remove("name");
+    assert!(doc.cursor_at(&path).is_none());
+    assert!(doc.node_mut(&path).is_none());
+}
+
+#[test]
+fn paths_shift_with_array_inserts() {
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    let second = doc
+        .cursor()
+        .child("bin")
+        .and_then(|c| c.child(1))
+        .and_then(|c| c.child("name"))
+        .unwrap()
+        .key_path()
+        .to_vec();
+    let b = doc
+        .cursor()
+        .child("package")
+        .and_then(|c| c.child("authors"))
+        .and_then(|c| c.child(1))
+        .unwrap()
+        .key_path()
+        .to_vec();
+
+    // Paths hold indices, so edits earlier in an array change the node a path leads to
+    doc["bin"].as_array_of_tables_mut().unwrap().remove(0);
+    doc["package"]["authors"]
+        .as_array_mut()
+        .unwrap()
+        .insert(0, "z");
+
+    let at = |doc: &DocumentMut, path: &[PathSegment]| match doc.cursor_at(path).map(|c| c.node()) {
+        Some(Node::Value(v)) => v.as_str().map(str::to_owned),
+        _ => None,
+    };
+    assert_eq!(at(&doc, &second), None);
+    assert_eq!(at(&doc, &b), Some("a".to_owned()));
+}
+
+#[test]
+fn node_at_offset() {
+    let doc = ImDocument::parse(INPUT).unwrap();
+    let at = |needle: &str| {
+        let offset = INPUT.find(needle).unwrap();
+        keys(doc.node_at(offset).unwrap().key_path())
+    };
+    assert_eq!(at("title"), ["title"]);
+    assert_eq!(at("\"example\""), ["title"]);
+    assert_eq!(at("\"b\""), ["package", "authors", "1"]);
+    assert_eq!(at("x = 1"), ["package", "meta", "x"]);
+    assert_eq!(at("z = 2"), ["package", "meta", "y", "z"]);
+    assert_eq!(at("\"second\""), ["bin", "1", "name"]);
+    assert_eq!(at("\n\n[package]"), Vec::<String>::new());
+    assert!(doc.node_at(INPUT.len()).is_none());
+}
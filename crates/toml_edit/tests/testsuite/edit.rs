@@ -2,7 +2,10 @@ use std::fmt;
 use std::iter::FromIterator;
 
 use snapbox::assert_eq;
-use toml_edit::{array, table, value, DocumentMut, Item, Key, Table, Value};
+use toml_edit::{
+    array, table, value, ConvertOptions, DocumentMut, EditError, FloatStyle, IntegerStyle, Item,
+    Key, Radix, StringStyle, Style, Table, Value,
+};
 
 macro_rules! parse_key {
     ($s:expr) => {{
@@ -54,6 +57,14 @@ impl Test {
         self
     }
 
+    fn running_on_doc<F>(&mut self, func: F) -> &mut Self
+    where
+        F: Fn(&mut DocumentMut),
+    {
+        func(&mut self.doc);
+        self
+    }
+
     #[track_caller]
     fn produces_display(&self, expected: &str) -> &Self {
         assert_eq(expected, self.doc.to_string());
@@ -86,9 +97,9 @@ fn test_insert_leaf_table() {
         ip = "10.0.0.1"
         dc = "eqdc10"
 
-[servers.beta]
-ip = "10.0.0.2"
-dc = "eqdc10"
+        [servers.beta]
+        ip = "10.0.0.2"
+        dc = "eqdc10"
 
         [other.table]
 "#,
@@ -114,8 +125,7 @@ fn test_inserted_leaf_table_goes_after_last_sibling() {
         [dependencies]
         [[example]]
         [dependencies.opencl]
-
-[dependencies.newthing]
+        [dependencies.newthing]
         [dev-dependencies]
 "#,
     );
@@ -148,7 +158,7 @@ fn test_insert_nonleaf_table() {
 
 [servers]
 
-[servers.alpha]
+        [servers.alpha]
 ip = "10.0.0.1"
 dc = "eqdc10"
 "#,
@@ -178,10 +188,10 @@ fn test_insert_array() {
         [package]
         title = "withoutarray"
 
-[[bin]]
-hello = "world"
+        [[bin]]
+        hello = "world"
 
-[[bin]]
+        [[bin]]
 "#,
     );
 }
@@ -722,8 +732,8 @@ fn test_insert_into_inline_table() {
     })
     .produces_display(
         r#"
-        a = { a = 2, c = 3, b = 42 }
-        b = { hello = "world" }
+        a = {a = 2, c = 3, b = 42}
+        b = {hello = "world"}
 "#,
     );
 }
@@ -860,3 +870,1425 @@ fn sorting_with_references() {
     let mut array = toml_edit::Array::from_iter(values);
     array.sort_by(|lhs, rhs| lhs.as_str().cmp(&rhs.as_str()));
 }
+
+// comments
+
+#[test]
+fn test_key_and_value_comments() {
+    given(
+        r#"[package]
+name = "foo"
+
+# pinned for now
+version = "1.0"  # see changelog
+"#,
+    )
+    .running(|root| {
+        let package = root["package"].as_table_mut().unwrap();
+        assert_eq!(
+            package.key("version").unwrap().leading_comments(),
+            vec!["pinned for now"]
+        );
+        assert_eq!(
+            package["version"].as_value().unwrap().trailing_comment(),
+            Some("see changelog")
+        );
+
+        package
+            .key_mut("name")
+            .unwrap()
+            .set_leading_comments(["deprecated", "use `id`"]);
+        package["name"]
+            .as_value_mut()
+            .unwrap()
+            .set_trailing_comment(Some("legacy"));
+        package
+            .key_mut("version")
+            .unwrap()
+            .set_leading_comments(None::<&str>);
+        package["version"]
+            .as_value_mut()
+            .unwrap()
+            .set_trailing_comment(None);
+    })
+    .produces_display(
+        r#"[package]
+# deprecated
+# use `id`
+name = "foo" # legacy
+
+version = "1.0"
+"#,
+    );
+}
+
+#[test]
+fn test_table_comments() {
+    given(
+        r#"[a]
+x = 1
+# end of a
+
+# about b
+[b] # header
+y = 2
+
+[[c]]
+z = 3
+"#,
+    )
+    .running(|root| {
+        // Comments before a header stay with it, even past a blank line
+        let a = root["a"].as_table().unwrap();
+        assert!(a.dangling_comments().is_empty());
+        let b = root["b"].as_table().unwrap();
+        assert_eq!(b.leading_comments(), vec!["end of a", "about b"]);
+        assert_eq!(b.trailing_comment(), Some("header"));
+
+        root.remove("a");
+        let c = root["c"].as_array_of_tables_mut().unwrap();
+        let c = c.get_mut(0).unwrap();
+        c.set_leading_comments(["first c"]);
+        c.set_trailing_comment(Some("entry"));
+        c.set_dangling_comments(["TODO: more"]);
+    })
+    .produces_display(
+        r#"# end of a
+
+# about b
+[b] # header
+y = 2
+
+# first c
+[[c]] # entry
+z = 3
+# TODO: more
+"#,
+    );
+}
+
+#[test]
+fn test_array_comments() {
+    given(
+        r#"a = [
+    1, # one
+    # before two
+    2,
+    3 # three
+]
+b = [1, 2]
+c = [
+    1, # one
+    2,
+]
+"#,
+    )
+    .running(|root| {
+        let a = root["a"].as_array_mut().unwrap();
+        assert_eq!(a.trailing_comment(0), Some("one"));
+        assert_eq!(a.trailing_comment(1), None);
+        assert_eq!(a.trailing_comment(2), Some("three"));
+        assert_eq!(a.leading_comments(0), Vec::<&str>::new());
+        assert_eq!(a.leading_comments(1), vec!["before two"]);
+
+        a.set_trailing_comment(0, None);
+        a.set_trailing_comment(1, Some("two"));
+        a.set_trailing_comment(2, Some("last"));
+        a.set_leading_comments(1, ["second"]);
+
+        let b = root["b"].as_array_mut().unwrap();
+        b.set_trailing_comment(0, Some("one"));
+        b.set_trailing_comment(1, Some("two"));
+
+        // A comment parsed after the comma is moved to the value it trails
+        let c = root["c"].as_array_mut().unwrap();
+        c.set_trailing_comment(0, Some("first"));
+        let suffix = c.get(0).unwrap().decor().suffix().unwrap();
+        assert_eq!(suffix.as_str(), Some(" # first"));
+        let prefix = c.get(1).unwrap().decor().prefix().unwrap();
+        assert_eq!(prefix.as_str(), Some("\n    "));
+    })
+    .produces_display(
+        r#"a = [
+    1,
+    # second
+    2, # two
+    3 # last
+]
+b = [
+    1, # one
+    2, # two
+]
+c = [
+    1, # first
+    2,
+]
+"#,
+    );
+}
+
+#[test]
+fn test_single_line_array_comments() {
+    given(
+        r#"a = [1, 2, 3]
+b = [1, 2, 3]
+"#,
+    )
+    .running(|root| {
+        // Each value is moved onto its own line
+        let a = root["a"].as_array_mut().unwrap();
+        a.set_trailing_comment(0, Some("one"));
+        let b = root["b"].as_array_mut().unwrap();
+        b.set_leading_comments(1, ["two"]);
+    })
+    .produces_display(
+        r#"a = [
+    1, # one
+    2,
+    3,
+]
+b = [
+    1,
+    # two
+    2,
+    3,
+]
+"#,
+    );
+}
+
+// restructuring
+
+#[test]
+fn test_rename_key_keeps_position_and_comments() {
+    given(
+        r#"
+a = 1
+# about b
+b = 2 # two
+c = 3
+
+[t]
+[t.sub]
+x = 1
+[[t.arr]]
+y = 2
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.rename_key(&["b"], "renamed").unwrap();
+        doc.rename_key(&["a"], "first").unwrap();
+        doc.rename_key(&["t"], "table").unwrap();
+    })
+    .produces_display(
+        r#"
+first = 1
+# about b
+renamed = 2 # two
+c = 3
+
+[table]
+[table.sub]
+x = 1
+[[table.arr]]
+y = 2
+"#,
+    );
+}
+
+#[test]
+fn test_rename_table_path() {
+    given(
+        r#"[package]
+name = "foo"
+
+[package.metadata]
+x = 1
+
+[[package.metadata.bin]]
+name = "a"
+
+[[package.metadata.bin]]
+name = "b"
+
+[other]
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.rename_table_path(&["package", "metadata"], &["package", "meta"])
+            .unwrap();
+        doc.rename_table_path(&["package"], &["project"]).unwrap();
+    })
+    .produces_display(
+        r#"[project]
+name = "foo"
+
+[project.meta]
+x = 1
+
+[[project.meta.bin]]
+name = "a"
+
+[[project.meta.bin]]
+name = "b"
+
+[other]
+"#,
+    );
+}
+
+#[test]
+fn test_move_table_path() {
+    given(
+        r#"[project]
+name = "foo"
+
+[project.meta]
+x = 1
+
+[[project.meta.bin]]
+name = "a"
+
+[[project.meta.bin]]
+name = "b"
+
+[other]
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.rename_table_path(&["project", "meta"], &["other", "nested", "meta"])
+            .unwrap();
+    })
+    .produces_display(
+        r#"[project]
+name = "foo"
+
+[other.nested.meta]
+x = 1
+
+[[other.nested.meta.bin]]
+name = "a"
+
+[[other.nested.meta.bin]]
+name = "b"
+
+[other]
+"#,
+    );
+}
+
+#[test]
+fn test_move_item_between_tables() {
+    given(
+        r#"[a]
+# keep me
+x = 1 # one
+y = { z = 2 }
+
+[b]
+w = 0
+
+[c.d]
+e = 1
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.move_item(&["a", "x"], &["b", "x"]).unwrap();
+        doc.move_item(&["a", "y", "z"], &["b", "z"]).unwrap();
+        doc.move_item(&["b", "w"], &["a", "y", "w"]).unwrap();
+        doc.move_item(&["c", "d"], &["b", "d"]).unwrap();
+    })
+    .produces_display(
+        r#"[a]
+y = { w = 0 }
+
+[b]
+# keep me
+x = 1 # one
+z = 2
+
+[b.d]
+e = 1
+"#,
+    );
+}
+
+#[test]
+fn test_restructure_errors() {
+    let input = r#"a = 1
+b = 2
+
+[t]
+x = 1
+
+[[arr]]
+"#;
+    given(input)
+        .running_on_doc(|doc| {
+            assert_eq!(
+                doc.rename_key(&["a"], "b"),
+                Err(EditError::AlreadyExists(vec!["b".to_owned()]))
+            );
+            assert_eq!(
+                doc.rename_key(&["t", "missing"], "y"),
+                Err(EditError::NotFound(vec![
+                    "t".to_owned(),
+                    "missing".to_owned()
+                ]))
+            );
+            assert_eq!(
+                doc.move_item(&["t", "x"], &["a", "x"]),
+                Err(EditError::NotATable(vec!["a".to_owned()]))
+            );
+            assert_eq!(
+                doc.move_item(&["t", "x"], &["arr", "x"]),
+                Err(EditError::NotATable(vec!["arr".to_owned()]))
+            );
+            assert_eq!(
+                doc.move_item(&["t"], &["t", "inner"]),
+                Err(EditError::MoveIntoSelf(vec![
+                    "t".to_owned(),
+                    "inner".to_owned()
+                ]))
+            );
+            assert_eq!(
+                doc.rename_table_path(&["a"], &["c"]),
+                Err(EditError::NotATable(vec!["a".to_owned()]))
+            );
+            assert_eq!(doc.move_item(&[], &["a"]), Err(EditError::EmptyPath));
+        })
+        .produces_display(input);
+}
+
+// placement
+
+const PROFILES: &str = r#"[profile.dev]
+opt-level = 1
+
+[profile.dev.package.foo]
+opt-level = 3
+
+[[bin]]
+name = "a"
+
+[profile.test]
+debug = true
+"#;
+
+#[test]
+fn test_insert_table_after_nested_sibling() {
+    given(PROFILES)
+        .running_on_doc(|doc| {
+            let mut release = Table::new();
+            release["lto"] = value(true);
+            let mut package = Table::new();
+            package["opt-level"] = value(2);
+            let mut packages = Table::new();
+            packages.set_implicit(true);
+            packages["bar"] = Item::Table(package);
+            release["package"] = Item::Table(packages);
+            doc.insert_table_after(&["profile", "release"], "dev", release)
+                .unwrap();
+            assert_eq!(
+                doc["profile"]
+                    .as_table()
+                    .unwrap()
+                    .iter()
+                    .map(|(k, _)| k)
+                    .collect::<Vec<_>>(),
+                ["dev", "release", "test"]
+            );
+        })
+        .produces_display(
+            r#"[profile.dev]
+opt-level = 1
+
+[profile.dev.package.foo]
+opt-level = 3
+
+[profile.release]
+lto = true
+
+[profile.release.package.bar]
+opt-level = 2
+
+[[bin]]
+name = "a"
+
+[profile.test]
+debug = true
+"#,
+        );
+}
+
+#[test]
+fn test_insert_table_before_and_move_to_end() {
+    given(PROFILES)
+        .running_on_doc(|doc| {
+            let mut release = Table::new();
+            release["lto"] = value(true);
+            doc.insert_table_before(&["profile", "release"], "test", release)
+                .unwrap();
+            doc.move_to_end(&["profile", "dev"]).unwrap();
+        })
+        .produces_display(
+            r#"
+[[bin]]
+name = "a"
+
+[profile.release]
+lto = true
+
+[profile.test]
+debug = true
+[profile.dev]
+opt-level = 1
+
+[profile.dev.package.foo]
+opt-level = 3
+"#,
+        );
+}
+
+#[test]
+fn test_renumber_positions() {
+    given(PROFILES)
+        .running_on_doc(|doc| {
+            doc["profile"]["dev"]["package"]["baz"] = table();
+            doc.renumber_positions();
+            let position = |doc: &DocumentMut, path: &[&str]| {
+                let item = path.iter().fold(doc.as_item(), |item, key| &item[key]);
+                item.as_table().unwrap().position().unwrap()
+            };
+            let foo = position(doc, &["profile", "dev", "package", "foo"]);
+            assert_eq!(
+                position(doc, &["profile", "dev", "package", "baz"]),
+                foo + 1
+            );
+            assert_eq!(position(doc, &["profile", "test"]), foo + 3);
+        })
+        .produces_display(
+            r#"[profile.dev]
+opt-level = 1
+
+[profile.dev.package.foo]
+opt-level = 3
+
+[profile.dev.package.baz]
+
+[[bin]]
+name = "a"
+
+[profile.test]
+debug = true
+"#,
+        );
+}
+
+#[test]
+fn test_placement_errors() {
+    given(PROFILES)
+        .running_on_doc(|doc| {
+            assert_eq!(
+                doc.insert_table_after(&["profile", "test"], "dev", Table::new()),
+                Err(EditError::AlreadyExists(vec![
+                    "profile".to_owned(),
+                    "test".to_owned()
+                ]))
+            );
+            assert_eq!(
+                doc.insert_table_after(&["profile", "x"], "missing", Table::new()),
+                Err(EditError::NotFound(vec![
+                    "profile".to_owned(),
+                    "missing".to_owned()
+                ]))
+            );
+            assert_eq!(
+                doc.insert_table_before(&["bin", "x"], "name", Table::new()),
+                Err(EditError::NotATable(vec!["bin".to_owned()]))
+            );
+            assert_eq!(
+                doc.move_to_end(&["profile", "dev", "opt-level"]),
+                Err(EditError::NotATable(vec![
+                    "profile".to_owned(),
+                    "dev".to_owned(),
+                    "opt-level".to_owned()
+                ]))
+            );
+        })
+        .produces_display(PROFILES);
+}
+
+// representation conversions
+
+#[test]
+fn test_make_inline_carries_comments() {
+    given(
+        r#"name = "foo"
+
+# about dep
+[dep] # pinned
+# the version
+version = "1.0" # stable
+[dep.extra]
+x = 1
+
+[[bin]]
+name = "a"
+"#,
+    )
+    .running(|root| {
+        let options = ConvertOptions::new();
+        assert!(root.make_inline("dep", &options));
+        assert!(root.make_inline("bin", &options));
+        assert!(!root.make_inline("name", &options));
+    })
+    .produces_display(
+        r#"name = "foo"
+# about dep
+# the version
+# stable
+dep = { version = "1.0", extra = { x = 1 } } # pinned
+bin = [{ name = "a" }]
+"#,
+    );
+}
+
+#[test]
+fn test_make_inline_respects_width() {
+    let input = r#"[dep]
+version = "1.0"
+features = ["std", "derive"]
+"#;
+    given(input)
+        .running(|root| {
+            let options = ConvertOptions::new().max_inline_width(Some(30));
+            assert!(!root.make_inline("dep", &options));
+        })
+        .produces_display(input);
+}
+
+#[test]
+fn test_make_standard_with_depth() {
+    given(
+        r#"# about a
+a = { b = { c = { d = 1 } }, e.f = 2 } # note
+"#,
+    )
+    .running(|root| {
+        assert!(root.make_standard("a", &ConvertOptions::new().max_depth(Some(2))));
+    })
+    .produces_display(
+        r#"# about a
+[a] # note
+
+[a.b]
+c = { d = 1 }
+
+[a.e]
+f = 2
+"#,
+    );
+}
+
+#[test]
+fn test_make_dotted() {
+    given(
+        r#"[package]
+# the name
+name = "foo"
+meta = { x = 1 }
+
+# more
+[package.metadata]
+y = 2
+"#,
+    )
+    .running(|root| {
+        assert!(root.make_dotted("package"));
+    })
+    .produces_display(
+        r#"# more
+# the name
+package.name = "foo"
+package.meta.x = 1
+package.metadata.y = 2
+"#,
+    );
+}
+
+#[test]
+fn test_make_dotted_keeps_empty_tables() {
+    given(
+        r#"[a]
+x = 1
+e = {}
+
+# empty
+[a.b]
+
+[a.c]
+[a.c.d]
+"#,
+    )
+    .running(|root| {
+        assert!(root.make_dotted("a"));
+    })
+    .produces_display(
+        r#"# empty
+a.x = 1
+a.e = {}
+a.b = {}
+a.c.d = {}
+"#,
+    );
+}
+
+#[test]
+fn test_normalize_tables() {
+    given(
+        r#"a.b = 1
+
+[dependencies]
+short = { version = "1" }
+long = { version = "1.0.0", features = ["one", "two", "three"] }
+
+[dependencies.other]
+version = "2"
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.normalize_tables(
+            &ConvertOptions::new()
+                .max_depth(Some(1))
+                .max_inline_width(Some(40)),
+        );
+    })
+    .produces_display(
+        r#"[a]
+b = 1
+
+[dependencies]
+short = { version = "1" }
+other = { version = "2" }
+
+[dependencies.long]
+version = "1.0.0"
+features = ["one", "two", "three"]
+"#,
+    );
+}
+
+// arrays of tables
+
+const BINS: &str = r#"[[bin]]
+name = "b"
+
+[bin.meta]
+x = 1
+
+[other]
+
+[[bin]]
+name = "c"
+
+# the first
+[[bin]] # main
+name = "a"
+"#;
+
+fn bins(root: &mut Table) -> &mut toml_edit::ArrayOfTables {
+    root["bin"].as_array_of_tables_mut().unwrap()
+}
+
+#[test]
+fn test_sort_array_of_tables_moves_nested_tables() {
+    given(BINS)
+        .running(|root| {
+            bins(root).sort_by_key(|t| t["name"].as_str().unwrap().to_owned());
+        })
+        // `[other]` keeps its place between the slots of the array
+        .produces_display(
+            r#"
+# the first
+[[bin]] # main
+name = "a"
+
+[other]
+[[bin]]
+name = "b"
+
+[bin.meta]
+x = 1
+
+[[bin]]
+name = "c"
+"#,
+        );
+}
+
+#[test]
+fn test_swap_array_of_tables() {
+    given(BINS)
+        .running(|root| {
+            bins(root).swap(0, 1);
+        })
+        .produces_display(
+            r#"
+[[bin]]
+name = "c"
+
+[other]
+[[bin]]
+name = "b"
+
+[bin.meta]
+x = 1
+
+# the first
+[[bin]] # main
+name = "a"
+"#,
+        );
+}
+
+#[test]
+fn test_insert_and_splice_array_of_tables() {
+    given(BINS)
+        .running(|root| {
+            let mut d = Table::new();
+            d["name"] = value("d");
+            bins(root).insert(0, d);
+            let a = bins(root).get(3).unwrap().clone();
+            let removed = bins(root)
+                .splice(1..3, [a])
+                .map(|t| t["name"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(removed, ["b", "c"]);
+        })
+        .produces_display(
+            r#"[[bin]]
+name = "d"
+
+[other]
+
+# the first
+[[bin]] # main
+name = "a"
+
+# the first
+[[bin]] # main
+name = "a"
+"#,
+        );
+}
+
+#[test]
+fn test_drain_and_find_array_of_tables() {
+    given(BINS)
+        .running(|root| {
+            let bins = bins(root);
+            assert!(bins.find_by("name", "z").is_none());
+            bins.find_by_mut("name", "c").unwrap()["name"] = value("C");
+            let drained = bins.drain(..1).count();
+            assert_eq!(drained, 1);
+        })
+        .produces_display(
+            r#"
+[other]
+
+[[bin]]
+name = "C"
+
+# the first
+[[bin]] # main
+name = "a"
+"#,
+        );
+}
+
+#[test]
+fn test_array_of_tables_to_array_and_back() {
+    let mut doc = BINS.parse::<DocumentMut>().unwrap();
+    let tables = doc.remove("bin").unwrap().into_array_of_tables().unwrap();
+    let array = toml_edit::Array::from(tables);
+    assert_eq(
+        r#"[
+    { name = "b", meta = { x = 1 } },
+    { name = "c" },
+    # the first
+    { name = "a" }, # main
+]"#,
+        array.to_string(),
+    );
+
+    let tables = toml_edit::ArrayOfTables::try_from(array).unwrap();
+    doc["bin"] = Item::ArrayOfTables(tables);
+    assert_eq(
+        r#"
+[other]
+
+[[bin]]
+name = "b"
+meta = { x = 1 }
+
+[[bin]]
+name = "c"
+
+# the first
+[[bin]] # main
+name = "a"
+"#,
+        doc.to_string(),
+    );
+
+    let mixed = toml_edit::Array::from_iter([1, 2]);
+    assert!(toml_edit::ArrayOfTables::try_from(mixed).is_err());
+}
+
+#[test]
+fn test_keyed_array_of_tables() {
+    given(
+        r#"[[package]]
+name = "a" # first
+version = "1.0.0"
+
+[[package]]
+name = "b"
+
+[[package]]
+name = 'a'
+"#,
+    )
+    .running(|root| {
+        let packages = root["package"].as_array_of_tables_mut().unwrap();
+        let mut packages = packages.keyed_by("name");
+        assert_eq!(packages.key(), "name");
+        assert_eq!(packages.duplicates(), [&Value::from("a")]);
+        assert!(packages.contains("b"));
+        assert!(!packages.contains("c"));
+        assert_eq!(
+            packages.get("a").unwrap()["version"].as_str(),
+            Some("1.0.0")
+        );
+
+        let mut b = Table::new();
+        b["name"] = value("b");
+        b["version"] = value("2.0.0");
+        let old = packages.upsert(b).unwrap().unwrap();
+        assert!(old.get("version").is_none());
+        assert!(packages.upsert(Table::new()).is_err());
+
+        packages
+            .entry("a")
+            .and_modify(|t| t["version"] = value("1.1.0"))
+            .or_default();
+        assert!(packages.remove("a").is_some());
+        assert!(packages.remove("z").is_none());
+    })
+    .produces_display(
+        r#"
+[[package]]
+name = "b"
+version = "2.0.0"
+
+[[package]]
+name = 'a'
+"#,
+    );
+}
+
+#[test]
+fn test_keyed_upsert_keeps_format() {
+    given(
+        r#"[[package]]
+name = "a"
+
+[other]
+x = 1
+
+# the b package
+[[package]] # second
+name = "b"
+version = "1.0.0"
+"#,
+    )
+    .running(|root| {
+        let packages = root["package"].as_array_of_tables_mut().unwrap();
+        let mut packages = packages.keyed_by("name");
+        assert!(format!("{packages:?}").starts_with("Keyed { key: \"name\""));
+        assert!(format!("{:?}", packages.entry("c")).starts_with("Vacant(VacantKeyedEntry"));
+
+        let mut b = Table::new();
+        b["name"] = value("b");
+        b["version"] = value("2.0.0");
+        packages.upsert(b).unwrap();
+    })
+    .produces_display(
+        r#"[[package]]
+name = "a"
+
+[other]
+x = 1
+
+# the b package
+[[package]] # second
+name = "b"
+version = "2.0.0"
+"#,
+    );
+}
+
+#[test]
+fn test_keyed_array_of_inline_tables() {
+    given(
+        r#"deps = [
+  { name = "a", version = "1" }, # pinned
+  2,
+  { name = "b" },
+]
+"#,
+    )
+    .running(|root| {
+        let deps = root["deps"].as_array_mut().unwrap();
+        let mut deps = deps.keyed_by("name");
+        assert!(deps.duplicates().is_empty());
+        deps.get_mut("b").unwrap().insert("version", "2".into());
+        match deps.entry("c") {
+            toml_edit::KeyedEntry::Vacant(entry) => {
+                assert_eq!(entry.id().as_str(), Some("c"));
+                entry.insert(toml_edit::InlineTable::new());
+            }
+            toml_edit::KeyedEntry::Occupied(_) => unreachable!(),
+        }
+    })
+    .produces_display(
+        r#"deps = [
+  { name = "a", version = "1" }, # pinned
+  2,
+  { name = "b" , version = "2" }, { name = "c" },
+]
+"#,
+    );
+}
+
+#[test]
+fn test_array_splice_drain_dedup() {
+    given(
+        r#"a = [1, 1, 2, 3, 3, 4]
+"#,
+    )
+    .running(|root| {
+        let a = root["a"].as_array_mut().unwrap();
+        a.dedup();
+        assert_eq!(a.len(), 4);
+        let removed = a.splice(0..1, ["x", "y"]).collect::<Vec<_>>();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(a.drain(3..).count(), 2);
+        a.extend_formatted([Value::from(5).decorated(" ", " ")]);
+        a.swap(0, 1);
+        a.reverse();
+        a.truncate(4);
+        a.dedup_by(|a, b| a.is_str() && b.is_str());
+        assert_eq!(a.len(), 3);
+    })
+    .produces_display(
+        r#"a = [ 5 , 2,"x"]
+"#,
+    );
+}
+
+#[test]
+fn test_array_fixed_layout() {
+    given(
+        r#"single = ["c", "a", "b"]
+multi = [
+  # the first
+  "c", # third
+  "a",
+  "b", # second
+]
+"#,
+    )
+    .running(|root| {
+        let single = root["single"].as_array_mut().unwrap();
+        single
+            .fixed_layout()
+            .sort_by_key(|v| v.as_str().map(String::from));
+
+        let multi = root["multi"].as_array_mut().unwrap();
+        let mut layout = multi.fixed_layout();
+        layout.reverse();
+        layout.swap(1, 2);
+    })
+    .produces_display(
+        r#"single = ["a", "b", "c"]
+multi = [
+  "b", # second
+  # the first
+  "c", # third
+  "a",
+]
+"#,
+    );
+}
+
+// alignment
+
+#[test]
+fn test_align_entries_and_comments() {
+    given(
+        r#"[server]
+host = "localhost" # where
+# the port
+port = 8080 # http
+tls.enabled = true
+paths = [
+  "/",
+] # roots
+
+timeout = 30 # seconds
+retries = 3   # at most
+"#,
+    )
+    .running(|root| {
+        let server = root["server"].as_table_mut().unwrap();
+        server.align_entries();
+        server.align_comments();
+    })
+    .produces_display(
+        r#"[server]
+host        = "localhost" # where
+# the port
+port        = 8080        # http
+tls.enabled = true
+paths       = [
+  "/",
+] # roots
+
+timeout = 30 # seconds
+retries = 3  # at most
+"#,
+    );
+}
+
+#[test]
+fn test_insert_aligned() {
+    given(
+        r#"[package]
+name = "foo"
+
+[aligned]
+a   = 1
+bcd = 2
+"#,
+    )
+    .running(|root| {
+        let package = root["package"].as_table_mut().unwrap();
+        package.insert_aligned("version", value("1.0"));
+
+        let aligned = root["aligned"].as_table_mut().unwrap();
+        aligned.insert_aligned("e", value(3));
+        aligned.insert_aligned("a", value(0));
+        aligned.insert_aligned("fghij", value(4));
+    })
+    .produces_display(
+        r#"[package]
+name = "foo"
+version = "1.0"
+
+[aligned]
+a     = 0
+bcd   = 2
+e     = 3
+fghij = 4
+"#,
+    );
+}
+
+// style
+
+#[test]
+fn test_insert_follows_inferred_style() {
+    given("[a]\r\n  x = 'one'\r\n  y = [ 1, 2 ]\r\n  z = {b = 1}\r\n")
+        .running_on_doc(|doc| {
+            let root = doc.as_table_mut();
+            let a = root["a"].as_table_mut().unwrap();
+            a.insert("s", value("two"));
+            a.insert("arr", value(Value::from_iter([3, 4])));
+            let mut pushed = toml_edit::Array::new();
+            pushed.push(5);
+            pushed.push(6);
+            a.insert("pushed", value(pushed));
+            let mut inline = toml_edit::InlineTable::new();
+            inline.insert("c", 2.into());
+            a.insert("t", value(inline));
+            root["b"] = table();
+            root["b"]["k"] = value(true);
+        })
+        .produces_display(
+            "[a]\r\n  x = 'one'\r\n  y = [ 1, 2 ]\r\n  z = {b = 1}\r\n  s = 'two'\r\n  arr = [ 3, 4 ]\r\n  pushed = [ 5, 6 ]\r\n  t = {c = 2}\r\n\r\n[b]\r\n  k = true\r\n",
+        );
+}
+
+#[test]
+fn test_insert_extends_inferred_indent() {
+    given("[t]\n  x = 1\n  [t.u]\n    y = 2\n")
+        .running_on_doc(|doc| {
+            doc["t"]["u"]["v"] = table();
+            doc["t"]["u"]["v"]["q"] = value(1);
+            let mut arr = toml_edit::ArrayOfTables::new();
+            let mut entry = Table::new();
+            entry["z"] = value(1);
+            arr.push(entry);
+            doc["t"]["arr"] = Item::ArrayOfTables(arr);
+        })
+        .produces_display(
+            "[t]\n  x = 1\n  [t.u]\n    y = 2\n    [t.u.v]\n      q = 1\n  [[t.arr]]\n    z = 1\n",
+        );
+}
+
+#[test]
+fn test_set_style_overrides_inferred() {
+    given("[a]\r\n  x = 'one'\r\n  y = [ 1, 2 ]\r\n  z = {b = 1}\r\n")
+        .running_on_doc(|doc| {
+            doc.set_style(Style::new());
+            let root = doc.as_table_mut();
+            let a = root["a"].as_table_mut().unwrap();
+            a.insert("s", value("two"));
+            a.insert("arr", value(Value::from_iter([3, 4])));
+            let mut inline = toml_edit::InlineTable::new();
+            inline.insert("c", 2.into());
+            a.insert("t", value(inline));
+            root["b"] = table();
+            root["b"]["k"] = value(true);
+        })
+        .produces_display(
+            "[a]\n  x = 'one'\n  y = [ 1, 2 ]\n  z = {b = 1}\ns = \"two\"\narr = [3, 4]\nt = { c = 2 }\n\n[b]\nk = true\n",
+        );
+
+    let doc = "[a]\r\n  x = 'one'\r\n".parse::<DocumentMut>().unwrap();
+    let inferred = Style::new()
+        .crlf(true)
+        .header_indent(1, "")
+        .body_indent(1, "  ")
+        .literal_strings(true);
+    assert_eq!(doc.style(), &inferred);
+    assert_eq!(doc.infer_style(), inferred);
+    assert_eq!(DocumentMut::new().style(), &Style::new());
+}
+
+#[test]
+fn test_set_style() {
+    given("")
+        .running(|root| {
+            root["a"] = table();
+            root["a"]["b"] = table();
+            root["a"]["b"]["c"] = value("it's");
+            root["a"]["b"]["d"] = value("x");
+        })
+        .produces_display("[a]\n\n[a.b]\nc = \"it's\"\nd = \"x\"\n");
+
+    let mut doc = DocumentMut::new();
+    doc.set_style(
+        Style::new()
+            .header_indent(2, "  ")
+            .body_indent(2, "    ")
+            .blank_lines_between_tables(0)
+            .literal_strings(true),
+    );
+    doc["a"] = table();
+    doc["a"]["b"] = table();
+    doc["a"]["b"]["c"] = value("it's");
+    doc["a"]["b"]["d"] = value("x");
+    assert_eq(
+        "[a]\n  [a.b]\n    c = \"it's\"\n    d = 'x'\n",
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn test_crlf_round_trip() {
+    let input = "# top\r\na = 1 # one\r\nb = \"\"\"\r\nx\r\ny\"\"\"\r\nc = '''\r\nz'''\r\n\r\n# before\r\n[t]\r\nd = 2\r\n";
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc.set_style(Style::new().crlf(true));
+    assert_eq(input, doc.to_string());
+
+    doc["t"]["e"] = value("new");
+    let output = doc.to_string();
+    assert_eq(
+        "# top\r\na = 1 # one\r\nb = \"\"\"\r\nx\r\ny\"\"\"\r\nc = '''\r\nz'''\r\n\r\n# before\r\n[t]\r\nd = 2\r\ne = \"new\"\r\n",
+        &output,
+    );
+    let reparsed = output.parse::<DocumentMut>().unwrap();
+    assert_eq!(reparsed["b"].as_str(), doc["b"].as_str());
+    assert_eq!(reparsed["c"].as_str(), doc["c"].as_str());
+}
+
+// string styles
+
+#[test]
+fn test_set_string_style() {
+    given(
+        r#"
+a = "plain"
+b = "it's"
+c = "multi\nline"
+d = "C:\\Users""#,
+    )
+    .running(|root| {
+        for (key, style, is_ok) in [
+            ("a", StringStyle::MultilineLiteral, true),
+            ("b", StringStyle::Literal, false),
+            ("c", StringStyle::MultilineLiteral, true),
+            ("d", StringStyle::Literal, true),
+        ] {
+            let Some(Value::String(s)) = root[key].as_value_mut() else {
+                panic!("`{key}` is a string");
+            };
+            assert_eq!(s.style(), Some(StringStyle::Basic));
+            let result = s.set_style(style);
+            assert_eq!(
+                result,
+                if is_ok {
+                    Ok(())
+                } else {
+                    Err(EditError::UnrepresentableString(style))
+                }
+            );
+            if is_ok {
+                assert_eq!(s.style(), Some(style));
+            }
+        }
+    })
+    .produces_display(
+        r#"
+a = '''
+plain'''
+b = "it's"
+c = '''
+multi
+line'''
+d = 'C:\Users'
+"#,
+    );
+}
+
+// number styles
+
+#[test]
+fn test_set_number_value_keeps_style() {
+    given(
+        r#"
+hex = 0xDE_AD_BE_EF
+oct = 0o755
+bin = 0b1111_0000
+dec = 1_000
+neg = 0xff
+plain = 7
+exp = 6.626e-34
+fixed = 1.50
+nan = nan"#,
+    )
+    .running(|root| {
+        let mut set_int = |key: &str, value| match root[key].as_value_mut() {
+            Some(Value::Integer(i)) => i.set_value(value),
+            _ => panic!("`{key}` is an integer"),
+        };
+        set_int("hex", 0xCAFE);
+        set_int("oct", 0o644);
+        set_int("bin", 0b1_0101);
+        set_int("dec", -12_345_678);
+        set_int("neg", -1);
+        set_int("plain", 8);
+
+        let mut set_float = |key: &str, value| match root[key].as_value_mut() {
+            Some(Value::Float(f)) => f.set_value(value),
+            _ => panic!("`{key}` is a float"),
+        };
+        set_float("exp", 1.25e10);
+        set_float("fixed", 2.0);
+        set_float("nan", 0.1);
+    })
+    .produces_display(
+        r#"
+hex = 0xCA_FE
+oct = 0o644
+bin = 0b1_0101
+dec = -12_345_678
+neg = -1
+plain = 8
+exp = 1.25e10
+fixed = 2.0
+nan = 0.1
+"#,
+    );
+}
+
+#[test]
+fn test_set_float_value_writes_shortest_digits() {
+    given(
+        r#"
+padded = 2.5000
+short = 1.5
+exp = 1.5e3"#,
+    )
+    .running(|root| {
+        let mut set_float = |key: &str, value| match root[key].as_value_mut() {
+            Some(Value::Float(f)) => f.set_value(value),
+            _ => panic!("`{key}` is a float"),
+        };
+        set_float("padded", 2.5);
+        set_float("short", 3.125);
+        set_float("exp", 1.2345e-3);
+    })
+    .produces_display(
+        r#"
+padded = 2.5
+short = 3.125
+exp = 1.2345e-3
+"#,
+    );
+}
+
+#[test]
+fn test_number_style() {
+    let doc = "a = 0xFF_FF\nb = -1_0\nc = 1.5E+3\nd = 1e3\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let int_style = |key: &str| match doc[key].as_value() {
+        Some(Value::Integer(i)) => i.style().unwrap(),
+        _ => panic!("`{key}` is an integer"),
+    };
+    let float_style = |key: &str| match doc[key].as_value() {
+        Some(Value::Float(f)) => f.style().unwrap(),
+        _ => panic!("`{key}` is a float"),
+    };
+    assert_eq!(
+        int_style("a"),
+        IntegerStyle::new(Radix::Hexadecimal)
+            .group(Some(2))
+            .uppercase(true)
+    );
+    assert_eq!(
+        int_style("b"),
+        IntegerStyle::new(Radix::Decimal).group(Some(1))
+    );
+    assert_eq!(
+        float_style("c"),
+        FloatStyle::new().exponent(true).precision(Some(1))
+    );
+    assert_eq!(float_style("d"), FloatStyle::new().exponent(true));
+
+    let mut i = toml_edit::Formatted::new(300);
+    i.set_style(IntegerStyle::new(Radix::Binary).group(Some(4)));
+    assert_eq!(i.to_string(), "0b1_0010_1100");
+    i.set_value(5);
+    assert_eq!(i.to_string(), "0b101");
+
+    let mut f = toml_edit::Formatted::new(0.000_5);
+    f.set_style(FloatStyle::new().precision(Some(6)));
+    assert_eq!(f.to_string(), "0.000500");
+    f.set_style(FloatStyle::new().exponent(true));
+    assert_eq!(f.to_string(), "5e-4");
+}
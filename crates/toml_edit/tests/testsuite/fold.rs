@@ -0,0 +1,135 @@
+use snapbox::assert_eq;
+use toml_edit::fold::{self, Fold};
+use toml_edit::{Decor, DocumentMut, Formatted, Item, Key, Value};
+
+const INPUT: &str = r#"
+# leading
+a = [1, { b = 2 }]   # trailing
+
+[[c]]
+d = 3
+[[c]]
+"e" . f = 4
+"#;
+
+#[test]
+fn fold_identity() {
+    struct Identity;
+
+    impl Fold for Identity {}
+
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let folded = Identity.fold_document(doc.clone());
+    assert!(folded.exact_eq(&doc));
+    assert_eq(INPUT, folded.to_string());
+}
+
+#[test]
+fn fold_integer() {
+    struct Double;
+
+    impl Fold for Double {
+        fn fold_integer(&mut self, node: Formatted<i64>) -> Formatted<i64> {
+            let mut doubled = Formatted::new(node.value() * 2);
+            *doubled.decor_mut() = node.decor().clone();
+            doubled
+        }
+    }
+
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let doc = Double.fold_document(doc);
+    assert_eq(
+        r#"
+# leading
+a = [2, { b = 4 }]   # trailing
+
+[[c]]
+d = 6
+[[c]]
+"e" . f = 8
+"#,
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn fold_table_kv_removes_and_renames() {
+    struct Rename;
+
+    impl Fold for Rename {
+        fn fold_table_kv(&mut self, key: Key, node: Item) -> (Key, Item) {
+            match key.get() {
+                "drop" => (key, Item::None),
+                "old" => (
+                    Key::new("new").with_leaf_decor(key.leaf_decor().clone()),
+                    fold::fold_item(self, node),
+                ),
+                _ => fold::fold_table_kv(self, key, node),
+            }
+        }
+    }
+
+    let input = r#"old = 1
+drop = 2
+new = 3
+keep = 4
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let doc = Rename.fold_document(doc);
+    // The later `new` replaces the renamed entry
+    assert_eq(
+        r#"new = 3
+keep = 4
+"#,
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn fold_inline_table_kv_removes() {
+    struct DropOptional;
+
+    impl Fold for DropOptional {
+        fn fold_inline_table_kv(&mut self, key: Key, node: Value) -> Option<(Key, Value)> {
+            if key.get() == "optional" {
+                return None;
+            }
+            fold::fold_inline_table_kv(self, key, node)
+        }
+    }
+
+    let input = r#"dep = { version = "1", optional = true, features = [] }
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let doc = DropOptional.fold_document(doc);
+    assert_eq(
+        r#"dep = { version = "1", features = [] }
+"#,
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn fold_decor() {
+    struct StripComments;
+
+    impl Fold for StripComments {
+        fn fold_decor(&mut self, _node: Decor) -> Decor {
+            Decor::default()
+        }
+    }
+
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let doc = StripComments.fold_document(doc);
+    assert_eq(
+        r#"a = [1, { b = 2 }]
+
+[[c]]
+d = 3
+
+[[c]]
+"e".f = 4
+"#,
+        doc.to_string(),
+    );
+}
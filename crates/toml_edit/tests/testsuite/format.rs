@@ -0,0 +1,201 @@
+use snapbox::assert_eq;
+use toml_edit::{ArrayWrap, DocumentMut, Formatter, KeyQuoting};
+
+const INPUT: &str = r#"
+# leading
+title="example"    # trailing
+
+
+"quoted key"   =   1
+a . b={ c=1,d . e=[1,2] }
+[server]   # header
+
+
+   # before port
+port=[ 8080 , # http
+  8443, # https
+  # spare
+]
+[[bin]]
+name="a"
+[[bin]]
+name="b"
+# end
+"#;
+
+fn format(formatter: &Formatter) -> String {
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    formatter.format(&mut doc);
+    doc.to_string()
+}
+
+#[test]
+fn format_default() {
+    assert_eq(
+        r#"# leading
+title = "example" # trailing
+
+"quoted key" = 1
+a.b = { c = 1, d.e = [1, 2] }
+
+[server] # header
+# before port
+port = [
+    8080, # http
+    8443, # https
+    # spare
+]
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+# end
+"#,
+        format(&Formatter::new()),
+    );
+}
+
+#[test]
+fn format_is_idempotent() {
+    let formatter = Formatter::new();
+    let once = format(&formatter);
+    let mut doc = once.parse::<DocumentMut>().unwrap();
+    formatter.format(&mut doc);
+    assert_eq(once, doc.to_string());
+}
+
+#[test]
+fn format_options() {
+    let formatter = Formatter::new()
+        .indent_width(2)
+        .array_wrap(ArrayWrap::Always)
+        .trailing_comma(false)
+        .blank_lines_between_tables(2)
+        .space_around_eq(false)
+        .key_quoting(KeyQuoting::Minimal);
+    assert_eq(
+        r#"# leading
+title="example" # trailing
+
+"quoted key"=1
+a.b={ c=1, d.e=[
+  1,
+  2
+] }
+
+
+[server] # header
+# before port
+port=[
+  8080, # http
+  8443 # https
+  # spare
+]
+
+
+[[bin]]
+name="a"
+
+
+[[bin]]
+name="b"
+# end
+"#,
+        format(&formatter),
+    );
+}
+
+#[test]
+fn format_without_comments() {
+    let formatter = Formatter::new()
+        .array_wrap(ArrayWrap::Never)
+        .preserve_comments(false);
+    assert_eq(
+        r#"title = "example"
+
+"quoted key" = 1
+a.b = { c = 1, d.e = [1, 2] }
+
+[server]
+port = [8080, 8443]
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+"#,
+        format(&formatter),
+    );
+}
+
+#[test]
+fn format_first_header() {
+    let mut doc = "\n\n# leading\n[a]\nb=1\n".parse::<DocumentMut>().unwrap();
+    Formatter::new().format(&mut doc);
+    assert_eq("# leading\n[a]\nb = 1\n", doc.to_string());
+}
+
+#[test]
+fn format_max_width() {
+    let mut doc = r#"
+short = [1, 2]
+features = ["serde", "std", "alloc", ["nested", "array", "too"]]
+kept = [
+  1]
+dep = { version = "1.0", features = ["derive"], default-features = false }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    Formatter::new()
+        .max_width(Some(30))
+        .expand_inline_tables(true)
+        .format(&mut doc);
+    assert_eq(
+        r#"short = [1, 2]
+features = [
+    "serde",
+    "std",
+    "alloc",
+    ["nested", "array", "too"],
+]
+kept = [
+    1,
+]
+
+[dep]
+version = "1.0"
+features = ["derive"]
+default-features = false
+"#,
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn format_max_width_never_wrap() {
+    let input = "features = [\"serde\", \"std\", \"alloc\"]\ndep = { version = \"1.0\" }\n";
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    Formatter::new()
+        .max_width(Some(10))
+        .array_wrap(ArrayWrap::Never)
+        .format(&mut doc);
+    assert_eq(input, doc.to_string());
+}
+
+#[test]
+fn format_alignment() {
+    let mut doc = "a=1 # one\nlong.key=\"two\" # two\n\nc=3\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    Formatter::new()
+        .align_entries(true)
+        .align_comments(true)
+        .format(&mut doc);
+    assert_eq(
+        "a        = 1     # one\nlong.key = \"two\" # two\n\nc = 3\n",
+        doc.to_string(),
+    );
+}
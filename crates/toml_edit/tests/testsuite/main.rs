@@ -1,9 +1,17 @@
 #![recursion_limit = "256"]
 
+mod canonical;
+mod compare;
 mod convert;
+mod cursor;
 mod datetime;
 mod edit;
 mod float;
+mod fold;
+mod format;
 mod invalid;
 mod parse;
+mod source_map;
 mod stackoverflow;
+mod text_edit;
+mod visit;
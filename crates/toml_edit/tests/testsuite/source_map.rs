@@ -0,0 +1,115 @@
+use toml_edit::{value, DocumentMut, NodeKind, PathSegment};
+
+#[track_caller]
+fn spanned(input: &str) -> (String, toml_edit::SourceMap) {
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let (output, map) = doc.to_string_with_spans();
+    assert_eq!(output, doc.to_string());
+    (output, map)
+}
+
+#[test]
+fn keys_and_values() {
+    let (output, map) = spanned(
+        r#"title = "TOML"   # comment
+  a . b = 1
+"#,
+    );
+    let title = [PathSegment::from("title")];
+    assert_eq!(&output[map.key(&title).unwrap()], "title");
+    assert_eq!(&output[map.value(&title).unwrap()], "\"TOML\"");
+
+    let dotted = ["a".into(), "b".into()];
+    assert_eq!(&output[map.key(&dotted).unwrap()], "a . b");
+    assert_eq!(&output[map.value(&dotted).unwrap()], "1");
+}
+
+#[test]
+fn headers() {
+    let (output, map) = spanned(
+        r#"[package]
+name = "foo"
+
+[ dependencies . serde ]  # serde
+version = "1"
+
+[[bin]]
+name = "a"
+
+[[bin]]
+name = "b"
+"#,
+    );
+    assert_eq!(
+        &output[map.header(&["package".into()]).unwrap()],
+        "[package]"
+    );
+    assert_eq!(&output[map.key(&["package".into()]).unwrap()], "package");
+    let serde = ["dependencies".into(), "serde".into()];
+    assert_eq!(
+        &output[map.header(&serde).unwrap()],
+        "[ dependencies . serde ]"
+    );
+    assert_eq!(&output[map.key(&serde).unwrap()], "dependencies . serde");
+
+    let second = ["bin".into(), 1.into()];
+    assert_eq!(&output[map.header(&second).unwrap()], "[[bin]]");
+    assert!(
+        map.header(&second).unwrap().start > map.header(&["bin".into(), 0.into()]).unwrap().start
+    );
+    let name = ["bin".into(), 1.into(), "name".into()];
+    assert_eq!(&output[map.value(&name).unwrap()], "\"b\"");
+}
+
+#[test]
+fn nested_values() {
+    let (output, map) = spanned(
+        r#"array = [ 1, [ "x", { y = 2 } ] ]
+"#,
+    );
+    assert_eq!(
+        &output[map.value(&["array".into()]).unwrap()],
+        r#"[ 1, [ "x", { y = 2 } ] ]"#
+    );
+    assert_eq!(
+        &output[map.value(&["array".into(), 0.into()]).unwrap()],
+        "1"
+    );
+    assert_eq!(
+        &output[map.value(&["array".into(), 1.into(), 1.into()]).unwrap()],
+        "{ y = 2 }"
+    );
+    let y = ["array".into(), 1.into(), 1.into(), "y".into()];
+    assert_eq!(&output[map.key(&y).unwrap()], "y");
+    assert_eq!(&output[map.value(&y).unwrap()], "2");
+}
+
+#[test]
+fn edited() {
+    let mut doc = "[a]\nx = 1\n".parse::<DocumentMut>().unwrap();
+    doc["a"]["y"] = value("new");
+    doc["b"] = toml_edit::table();
+    doc["b"]["z"] = value(true);
+    let (output, map) = doc.to_string_with_spans();
+    assert_eq!(output, "[a]\nx = 1\ny = \"new\"\n\n[b]\nz = true\n");
+
+    let spans = map
+        .iter()
+        .map(|s| (s.kind(), s.path().to_vec(), &output[s.span()]))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        spans,
+        vec![
+            (NodeKind::Key, vec!["a".into()], "a"),
+            (NodeKind::Header, vec!["a".into()], "[a]"),
+            (NodeKind::Key, vec!["a".into(), "x".into()], "x"),
+            (NodeKind::Value, vec!["a".into(), "x".into()], "1"),
+            (NodeKind::Key, vec!["a".into(), "y".into()], "y"),
+            (NodeKind::Value, vec!["a".into(), "y".into()], "\"new\""),
+            (NodeKind::Key, vec!["b".into()], "b"),
+            (NodeKind::Header, vec!["b".into()], "[b]"),
+            (NodeKind::Key, vec!["b".into(), "z".into()], "z"),
+            (NodeKind::Value, vec!["b".into(), "z".into()], "true"),
+        ]
+    );
+}
@@ -52,3 +52,72 @@ fn inline_dotted_key_recursion_limit() {
         assert_eq!(document.is_ok(), is_ok, "depth: {}", depth);
     }
 }
+
+const DEEP: usize = 100_000;
+
+#[test]
+fn deep_array_display_clone_drop() {
+    let mut value = toml_edit::Value::from(true);
+    for _ in 0..DEEP {
+        let mut array = toml_edit::Array::new();
+        array.push(value);
+        value = toml_edit::Value::Array(array);
+    }
+    let copy = value.clone();
+    let expected = format!("{}true{}", "[".repeat(DEEP), "]".repeat(DEEP));
+    assert_eq!(value.to_string(), expected);
+    drop(value);
+    assert_eq!(copy.to_string(), expected);
+}
+
+#[test]
+fn deep_inline_table_display_clone_drop() {
+    let mut value = toml_edit::Value::from(true);
+    for _ in 0..DEEP {
+        let mut table = toml_edit::InlineTable::new();
+        table.insert("x", value);
+        value = toml_edit::Value::InlineTable(table);
+    }
+    let copy = value.clone();
+    let expected = format!("{}true{}", "{ x = ".repeat(DEEP), " }".repeat(DEEP));
+    assert_eq!(value.to_string(), expected);
+    drop(value);
+    assert_eq!(copy.to_string(), expected);
+}
+
+#[test]
+fn deep_table_clone_drop() {
+    let mut item = toml_edit::value(true);
+    for _ in 0..DEEP {
+        let mut table = toml_edit::Table::new();
+        table.insert("x", item);
+        item = toml_edit::Item::Table(table);
+    }
+    let copy = item.clone();
+    drop(item);
+    let mut depth = 0;
+    let mut current = &copy;
+    while let Some(table) = current.as_table() {
+        current = &table["x"];
+        depth += 1;
+    }
+    assert_eq!(depth, DEEP);
+    assert_eq!(current.as_bool(), Some(true));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn deep_value_skipped_by_deserialize() {
+    let mut value = toml_edit::Value::from(true);
+    for _ in 0..DEEP {
+        let mut array = toml_edit::Array::new();
+        array.push(value);
+        value = toml_edit::Value::Array(array);
+    }
+    let mut doc = toml_edit::DocumentMut::new();
+    doc["kept"] = toml_edit::value(1);
+    doc["skipped"] = toml_edit::value(value);
+
+    // The values of the root table are each skipped
+    toml_edit::de::from_document::<serde::de::IgnoredAny>(doc).unwrap();
+}
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use snapbox::assert_eq;
+use toml_edit::{value, DocumentMut};
+
+fn apply(original: &str, edits: &[(Range<usize>, String)]) -> String {
+    let mut output = original.to_owned();
+    for (range, text) in edits.iter().rev() {
+        output.replace_range(range.clone(), text);
+    }
+    output
+}
+
+#[track_caller]
+fn edits_for(original: &str, edit: impl FnOnce(&mut DocumentMut)) -> Vec<(Range<usize>, String)> {
+    let mut doc = original.parse::<DocumentMut>().unwrap();
+    edit(&mut doc);
+    let edits = doc.text_edits(original);
+    assert_eq(doc.to_string(), apply(original, &edits));
+    edits
+}
+
+#[test]
+fn unmodified() {
+    let original = r#"# leading
+[package]
+name = "foo"   # name
+authors = [ "a",
+  "b", # trailing
+]
+inline = { x = 1, y.z = 2 }
+
+[[bin]]
+path = "main.rs"
+# end
+"#;
+    let edits = edits_for(original, |_| {});
+    assert!(edits.is_empty(), "{edits:?}");
+}
+
+#[test]
+fn replaced_value() {
+    let original = r#"[package]
+name = "foo"   # name
+version = "0.1.0"
+"#;
+    let edits = edits_for(original, |doc| {
+        doc["package"]["name"] = value("bar");
+    });
+    // Replacing the value also replaced its comment
+    assert_eq!(edits, vec![(18..31, "bar\"".to_owned())]);
+}
+
+#[test]
+fn inserted_and_removed_keys() {
+    let original = r#"[package]
+name = "foo"
+version = "0.1.0"
+
+[dependencies]
+serde = "1"
+"#;
+    let edits = edits_for(original, |doc| {
+        doc["package"].as_table_mut().unwrap().remove("version");
+        doc["dependencies"]["toml"] = value("0.8");
+    });
+    assert_eq!(
+        edits,
+        vec![
+            (23..41, "".to_owned()),
+            (69..69, "toml = \"0.8\"\n".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn comments() {
+    let original = r#"a = 1 # one
+# about b
+b = 2
+"#;
+    let edits = edits_for(original, |doc| {
+        doc.key_mut("b").unwrap().set_leading_comments(["B"]);
+    });
+    assert_eq!(edits, vec![(14..21, "B".to_owned())]);
+}
+
+#[test]
+fn reordered() {
+    let original = r#"c = 3
+a = 1
+b = 2
+"#;
+    let edits = edits_for(original, |doc| {
+        doc.sort_values();
+    });
+    assert_eq!(
+        edits,
+        vec![(0..6, "".to_owned()), (18..18, "c = 3\n".to_owned())]
+    );
+}
+
+#[test]
+fn crlf() {
+    let original = "[a]\r\nx = 1\r\n\r\n[b]\r\ny = 2\r\n";
+    let mut doc = original.parse::<DocumentMut>().unwrap();
+    doc["a"]["z"] = value(3);
+    let edits = doc.text_edits(original);
+    assert_eq!(edits, vec![(12..12, "z = 3\r\n".to_owned())]);
+    assert_eq(
+        "[a]\r\nx = 1\r\nz = 3\r\n\r\n[b]\r\ny = 2\r\n",
+        apply(original, &edits),
+    );
+}
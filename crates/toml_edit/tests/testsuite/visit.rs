@@ -0,0 +1,237 @@
+use std::ops::ControlFlow;
+
+use snapbox::assert_eq;
+use toml_edit::{try_visit, try_visit_mut, visit, visit_mut};
+use toml_edit::{Decor, DocumentMut, Formatted, Key, KeyMut, PathSegment, RawString};
+
+const INPUT: &str = r#"
+# leading
+a = [1, { b = 2 }]   # trailing
+
+[[c]]
+d = 3
+[[c]]
+"e" . f = 4
+"#;
+
+fn render(path: &[PathSegment]) -> String {
+    path.iter()
+        .map(|s| match s {
+            PathSegment::Key(k) => k.clone(),
+            PathSegment::Index(i) => i.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+const INTEGER_PATHS: [&str; 4] = ["a.0", "a.1.b", "c.0.d", "c.1.e.f"];
+
+#[test]
+fn visit_key_path() {
+    #[derive(Default)]
+    struct Paths<'doc> {
+        path: visit::KeyPath<'doc>,
+        integers: Vec<String>,
+        keys: Vec<String>,
+    }
+
+    impl<'doc> visit::Visit<'doc> for Paths<'doc> {
+        fn key_path(&mut self) -> Option<&mut visit::KeyPath<'doc>> {
+            Some(&mut self.path)
+        }
+
+        fn visit_key(&mut self, node: &'doc Key) {
+            let last = match self.path.steps().last() {
+                Some(visit::PathStep::Key(key)) => key.get(),
+                _ => unreachable!(),
+            };
+            assert_eq!(last, node.get());
+            self.keys.push(node.display_repr().into_owned());
+        }
+
+        fn visit_integer(&mut self, _node: &'doc Formatted<i64>) {
+            self.integers.push(render(&self.path.to_segments()));
+        }
+    }
+
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let mut visitor = Paths::default();
+    visit::Visit::visit_document(&mut visitor, &doc);
+    assert_eq!(visitor.integers, INTEGER_PATHS);
+    assert_eq!(visitor.keys, ["a", "b", "c", "d", "\"e\"", "f"]);
+    assert!(visitor.path.steps().is_empty());
+}
+
+#[test]
+fn visit_mut_key_path() {
+    #[derive(Default)]
+    struct Paths {
+        path: visit_mut::KeyPathMut,
+        integers: Vec<String>,
+    }
+
+    impl visit_mut::VisitMut for Paths {
+        fn key_path(&mut self) -> Option<&mut visit_mut::KeyPathMut> {
+            Some(&mut self.path)
+        }
+
+        fn visit_integer_mut(&mut self, _node: &mut Formatted<i64>) {
+            self.integers.push(render(self.path.segments()));
+        }
+    }
+
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    let mut visitor = Paths::default();
+    visit_mut::VisitMut::visit_document_mut(&mut visitor, &mut doc);
+    assert_eq!(visitor.integers, INTEGER_PATHS);
+}
+
+#[test]
+fn try_visit_key_path() {
+    #[derive(Default)]
+    struct FindSecond<'doc> {
+        path: visit::KeyPath<'doc>,
+        seen: usize,
+    }
+
+    impl<'doc> try_visit::TryVisit<'doc> for FindSecond<'doc> {
+        type Break = String;
+
+        fn key_path(&mut self) -> Option<&mut visit::KeyPath<'doc>> {
+            Some(&mut self.path)
+        }
+
+        fn visit_integer(&mut self, _node: &'doc Formatted<i64>) -> ControlFlow<String> {
+            self.seen += 1;
+            if self.seen == 2 {
+                return ControlFlow::Break(render(&self.path.to_segments()));
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let mut visitor = FindSecond::default();
+    let found = try_visit::TryVisit::visit_document(&mut visitor, &doc);
+    assert_eq!(found, ControlFlow::Break("a.1.b".to_owned()));
+    // Unwound even though it stopped early
+    assert!(visitor.path.steps().is_empty());
+}
+
+#[test]
+fn try_visit_mut_key_path() {
+    #[derive(Default)]
+    struct Paths {
+        path: visit_mut::KeyPathMut,
+        integers: Vec<String>,
+    }
+
+    impl try_visit_mut::TryVisitMut for Paths {
+        type Break = ();
+
+        fn key_path(&mut self) -> Option<&mut visit_mut::KeyPathMut> {
+            Some(&mut self.path)
+        }
+
+        fn visit_key_mut(&mut self, node: &mut KeyMut<'_>) -> ControlFlow<()> {
+            // Stop once `c` is reached
+            if node.get() == "c" {
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn visit_integer_mut(&mut self, _node: &mut Formatted<i64>) -> ControlFlow<()> {
+            self.integers.push(render(self.path.segments()));
+            ControlFlow::Continue(())
+        }
+    }
+
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    let mut visitor = Paths::default();
+    let flow = try_visit_mut::TryVisitMut::visit_document_mut(&mut visitor, &mut doc);
+    assert_eq!(flow, ControlFlow::Break(()));
+    assert_eq!(visitor.integers, &INTEGER_PATHS[..2]);
+    assert!(visitor.path.segments().is_empty());
+}
+
+#[test]
+fn visit_decor_mut() {
+    struct StripComments;
+
+    impl visit_mut::VisitMut for StripComments {
+        fn visit_decor_mut(&mut self, node: &mut Decor) {
+            node.clear();
+        }
+    }
+
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    visit_mut::VisitMut::visit_document_mut(&mut StripComments, &mut doc);
+    assert_eq(
+        r#"a = [1, { b = 2 }]
+
+[[c]]
+d = 3
+
+[[c]]
+"e".f = 4
+"#,
+        doc.to_string(),
+    );
+}
+
+#[test]
+fn visit_raw_string() {
+    const INPUT: &str = r#"a = [
+    1,
+    # after one
+]
+b = { }
+
+[c]
+d = 2
+# end
+"#;
+
+    #[derive(Default)]
+    struct RawStrings(Vec<String>);
+
+    impl<'doc> visit::Visit<'doc> for RawStrings {
+        fn visit_raw_string(&mut self, node: &'doc RawString) {
+            self.0.push(node.as_str().unwrap().to_owned());
+        }
+    }
+
+    struct StripRawStrings;
+
+    impl visit_mut::VisitMut for StripRawStrings {
+        fn visit_raw_string_mut(&mut self, node: &mut RawString) {
+            *node = RawString::default();
+        }
+    }
+
+    let mut doc = INPUT.parse::<DocumentMut>().unwrap();
+    doc["c"]
+        .as_table_mut()
+        .unwrap()
+        .set_dangling_comments(["dangling"]);
+
+    let mut visitor = RawStrings::default();
+    visit::Visit::visit_document(&mut visitor, &doc);
+    assert_eq!(
+        visitor.0,
+        ["\n    # after one\n", " ", "# dangling\n", "", "# end\n"]
+    );
+
+    visit_mut::VisitMut::visit_document_mut(&mut StripRawStrings, &mut doc);
+    assert_eq(
+        r#"a = [
+    1,]
+b = {}
+
+[c]
+d = 2
+"#,
+        doc.to_string(),
+    );
+}